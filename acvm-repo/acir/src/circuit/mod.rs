@@ -52,6 +52,48 @@ impl Circuit {
     }
 }
 
+/// A collection of circuits where the `main` circuit may call into the other `functions`
+/// through [`Opcode::Call`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Default, Debug)]
+pub struct Program {
+    pub main: Circuit,
+    /// The circuits which can be called, indexed by the `id` of an [`Opcode::Call`].
+    pub functions: Vec<Circuit>,
+}
+
+impl Program {
+    fn write<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
+        let buf = bincode::serialize(self).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(writer, Compression::default());
+        encoder.write_all(&buf)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    fn read<R: std::io::Read>(reader: R) -> std::io::Result<Self> {
+        let mut gz_decoder = flate2::read::GzDecoder::new(reader);
+        let mut buf_d = Vec::new();
+        gz_decoder.read_to_end(&mut buf_d)?;
+        bincode::deserialize(&buf_d)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))
+    }
+
+    pub fn serialize_program(program: &Program) -> Vec<u8> {
+        let mut program_bytes: Vec<u8> = Vec::new();
+        program.write(&mut program_bytes).expect("expected program to be serializable");
+        program_bytes
+    }
+
+    pub fn deserialize_program(serialized_program: &[u8]) -> std::io::Result<Self> {
+        Program::read(serialized_program)
+    }
+
+    /// Returns the circuit called by an [`Opcode::Call`] with the given `id`.
+    pub fn function(&self, id: u32) -> Option<&Circuit> {
+        self.functions.get(id as usize)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 /// Opcodes are locatable so that callers can
 /// map opcodes to debug information related to their context.
@@ -174,6 +216,38 @@ impl Circuit {
         let circuit = Self::deserialize_circuit(&circuit_bytes).map_err(D::Error::custom)?;
         Ok(circuit)
     }
+
+    // Serialize and base64 encode each of a list of circuits
+    pub fn serialize_circuits_base64<S>(circuits: &[Circuit], s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoded_b64: Vec<String> = circuits
+            .iter()
+            .map(|circuit| {
+                let circuit_bytes = Circuit::serialize_circuit(circuit);
+                base64::engine::general_purpose::STANDARD.encode(circuit_bytes)
+            })
+            .collect();
+        encoded_b64.serialize(s)
+    }
+
+    // Deserialize and base64 decode each of a list of circuits
+    pub fn deserialize_circuits_base64<'de, D>(deserializer: D) -> Result<Vec<Circuit>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytecodes_b64: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+        bytecodes_b64
+            .into_iter()
+            .map(|bytecode_b64| {
+                let circuit_bytes = base64::engine::general_purpose::STANDARD
+                    .decode(bytecode_b64)
+                    .map_err(D::Error::custom)?;
+                Self::deserialize_circuit(&circuit_bytes).map_err(D::Error::custom)
+            })
+            .collect()
+    }
 }
 
 impl std::fmt::Display for Circuit {
@@ -233,7 +307,7 @@ mod tests {
 
     use super::{
         opcodes::{BlackBoxFuncCall, FunctionInput},
-        Circuit, Compression, Opcode, PublicInputs,
+        Circuit, Compression, Opcode, Program, PublicInputs,
     };
     use crate::native_types::Witness;
     use acir_field::FieldElement;
@@ -272,6 +346,33 @@ mod tests {
         assert_eq!(circ, got_circ);
     }
 
+    #[test]
+    fn program_serialization_roundtrip() {
+        let function = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![and_opcode()],
+            private_parameters: BTreeSet::from_iter(vec![Witness(1), Witness(2)]),
+            return_values: PublicInputs(BTreeSet::from_iter(vec![Witness(3)])),
+            ..Circuit::default()
+        };
+        let main = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![Opcode::Call {
+                id: 0,
+                inputs: vec![Witness(1), Witness(2)],
+                outputs: vec![Witness(3)],
+                predicate: None,
+            }],
+            private_parameters: BTreeSet::from_iter(vec![Witness(1), Witness(2)]),
+            ..Circuit::default()
+        };
+        let program = Program { main, functions: vec![function] };
+
+        let bytes = Program::serialize_program(&program);
+        let got_program = Program::deserialize_program(&bytes).unwrap();
+        assert_eq!(program, got_program);
+    }
+
     #[test]
     fn test_serialize() {
         let circuit = Circuit {
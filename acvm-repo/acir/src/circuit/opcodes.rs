@@ -31,6 +31,19 @@ pub enum Opcode {
         block_id: BlockId,
         init: Vec<Witness>,
     },
+    /// Calls another circuit of the [`Program`][super::Program] which contains this opcode.
+    ///
+    /// The `inputs` are bound in order to the [arguments][super::Circuit::circuit_arguments] of the
+    /// callee and the `outputs` are bound in order to its [return values][super::Circuit::return_values],
+    /// both sorted by witness index.
+    Call {
+        /// Index of the callee in [`Program::functions`][super::Program::functions]
+        id: u32,
+        inputs: Vec<Witness>,
+        outputs: Vec<Witness>,
+        /// Predicate of the call - indicates if it should be skipped
+        predicate: Option<Expression>,
+    },
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -59,6 +72,7 @@ impl Opcode {
             Opcode::Brillig(_) => "brillig",
             Opcode::MemoryOp { .. } => "mem",
             Opcode::MemoryInit { .. } => "init memory block",
+            Opcode::Call { .. } => "call",
         }
     }
 
@@ -168,6 +182,21 @@ impl std::fmt::Display for Opcode {
                 write!(f, "INIT ")?;
                 write!(f, "(id: {}, len: {}) ", block_id.0, init.len())
             }
+            Opcode::Call { id, inputs, outputs, predicate } => {
+                write!(f, "CALL ")?;
+                if let Some(pred) = predicate {
+                    writeln!(f, "PREDICATE = {pred}")?;
+                }
+
+                let inputs: Vec<_> = inputs.iter().map(|w| format!("_{}", w.0)).collect();
+                let outputs: Vec<_> = outputs.iter().map(|w| format!("_{}", w.0)).collect();
+                write!(
+                    f,
+                    "(func: {id}, inputs: [{}], outputs: [{}])",
+                    inputs.join(", "),
+                    outputs.join(", ")
+                )
+            }
         }
     }
 }
@@ -33,6 +33,11 @@ pub struct AcirTransformationMap {
 }
 
 impl AcirTransformationMap {
+    /// Returns the number of opcodes in the transformed circuit.
+    pub fn opcodes_count(&self) -> usize {
+        self.acir_opcode_positions.len()
+    }
+
     pub fn new_locations(
         &self,
         old_location: OpcodeLocation,
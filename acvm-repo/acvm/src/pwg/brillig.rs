@@ -26,6 +26,13 @@ pub struct BrilligSolver<'b, B: BlackBoxFunctionSolver> {
     acir_index: usize,
 }
 
+// Implemented manually as deriving `Clone` would require the blackbox solver to be `Clone`.
+impl<'b, B: BlackBoxFunctionSolver> Clone for BrilligSolver<'b, B> {
+    fn clone(&self) -> Self {
+        Self { vm: self.vm.clone(), acir_index: self.acir_index }
+    }
+}
+
 impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
     /// Evaluates if the Brillig block should be skipped entirely
     pub(super) fn should_skip(
@@ -126,6 +133,22 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         self.vm.program_counter()
     }
 
+    pub fn get_registers(&self) -> &Registers {
+        self.vm.get_registers()
+    }
+
+    pub fn set_register(&mut self, register_index: usize, value: Value) {
+        self.vm.set_register(RegisterIndex(register_index), value);
+    }
+
+    pub fn get_memory(&self) -> &[Value] {
+        self.vm.get_memory()
+    }
+
+    pub fn write_memory_at(&mut self, ptr: usize, value: Value) {
+        self.vm.write_memory_at(ptr, value);
+    }
+
     fn handle_vm_status(
         &self,
         vm_status: VMStatus,
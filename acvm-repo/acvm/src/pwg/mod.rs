@@ -46,6 +46,22 @@ pub enum ACVMStatus {
     ///
     /// Once this is done, the ACVM can be restarted to solve the remaining opcodes.
     RequiresForeignCall(ForeignCallWaitInfo),
+
+    /// The ACVM has encountered an [`Opcode::Call`] to another circuit of the program.
+    /// The callee must be executed on the given inputs and its return values passed back to the ACVM
+    /// using [`ACVM::resolve_pending_acir_call`].
+    ///
+    /// Once this is done, the ACVM can be restarted to solve the remaining opcodes.
+    RequiresAcirCall(AcirCallWaitInfo),
+}
+
+/// The information required to execute the callee of an [`Opcode::Call`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcirCallWaitInfo {
+    /// Index of the called circuit within its program.
+    pub id: u32,
+    /// Values of the call's inputs, in the order of the callee's circuit arguments.
+    pub inputs: Vec<FieldElement>,
 }
 
 impl std::fmt::Display for ACVMStatus {
@@ -55,6 +71,7 @@ impl std::fmt::Display for ACVMStatus {
             ACVMStatus::InProgress => write!(f, "In progress"),
             ACVMStatus::Failure(_) => write!(f, "Execution failure"),
             ACVMStatus::RequiresForeignCall(_) => write!(f, "Waiting on foreign call"),
+            ACVMStatus::RequiresAcirCall(_) => write!(f, "Waiting on ACIR call"),
         }
     }
 }
@@ -147,6 +164,21 @@ pub struct ACVM<'a, B: BlackBoxFunctionSolver> {
     brillig_solver: Option<BrilligSolver<'a, B>>,
 }
 
+// Implemented manually as deriving `Clone` would require the blackbox solver to be `Clone`.
+impl<'a, B: BlackBoxFunctionSolver> Clone for ACVM<'a, B> {
+    fn clone(&self) -> Self {
+        ACVM {
+            status: self.status.clone(),
+            backend: self.backend,
+            block_solvers: self.block_solvers.clone(),
+            opcodes: self.opcodes,
+            instruction_pointer: self.instruction_pointer,
+            witness_map: self.witness_map.clone(),
+            brillig_solver: self.brillig_solver.clone(),
+        }
+    }
+}
+
 impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
     pub fn new(backend: &'a B, opcodes: &'a [Opcode], initial_witness: WitnessMap) -> Self {
         let status = if opcodes.is_empty() { ACVMStatus::Solved } else { ACVMStatus::InProgress };
@@ -168,6 +200,17 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         &self.witness_map
     }
 
+    /// Sets the value of `witness` in the ACVM's [`WitnessMap`], returning its previous value.
+    ///
+    /// This is intended for debuggers to explore how execution is affected by different values.
+    pub fn overwrite_witness(
+        &mut self,
+        witness: Witness,
+        value: FieldElement,
+    ) -> Option<FieldElement> {
+        self.witness_map.insert(witness, value)
+    }
+
     /// Returns a slice containing the opcodes of the circuit being executed.
     pub fn opcodes(&self) -> &[Opcode] {
         self.opcodes
@@ -233,12 +276,56 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         self.status(ACVMStatus::InProgress);
     }
 
+    /// Sets the status of the VM to `RequiresAcirCall`.
+    /// Indicating that the VM is now waiting for a call to another circuit to be resolved.
+    fn wait_for_acir_call(&mut self, acir_call: AcirCallWaitInfo) -> ACVMStatus {
+        self.status(ACVMStatus::RequiresAcirCall(acir_call))
+    }
+
+    /// Return a reference to the inputs of the next pending ACIR call, if one exists.
+    pub fn get_pending_acir_call(&self) -> Option<&AcirCallWaitInfo> {
+        if let ACVMStatus::RequiresAcirCall(acir_call) = &self.status {
+            Some(acir_call)
+        } else {
+            None
+        }
+    }
+
+    /// Resolves an [`Opcode::Call`] using the return values of the callee, calculated outside of the ACVM.
+    ///
+    /// The ACVM can then be restarted to solve the remaining ACIR opcodes.
+    pub fn resolve_pending_acir_call(&mut self, return_values: Vec<FieldElement>) {
+        if !matches!(self.status, ACVMStatus::RequiresAcirCall(_)) {
+            panic!("ACVM is not expecting an ACIR call response as no call was made");
+        }
+
+        let opcodes = self.opcodes;
+        let Opcode::Call { outputs, .. } = &opcodes[self.instruction_pointer] else {
+            unreachable!("Not executing a Call opcode");
+        };
+        assert_eq!(
+            outputs.len(),
+            return_values.len(),
+            "ACIR call returned an unexpected number of values"
+        );
+
+        let witness = &mut self.witness_map;
+        let resolution = outputs
+            .iter()
+            .zip(return_values)
+            .try_for_each(|(output, value)| insert_value(output, value, witness));
+
+        // Now that the call has been resolved then we can resume execution.
+        self.handle_opcode_resolution(resolution);
+    }
+
     /// Executes the ACVM's circuit until execution halts.
     ///
-    /// Execution can halt due to three reasons:
+    /// Execution can halt due to four reasons:
     /// 1. All opcodes have been executed successfully.
     /// 2. The circuit has been found to be unsatisfiable.
-    /// 2. A Brillig [foreign call][`ForeignCallWaitInfo`] has been encountered and must be resolved.
+    /// 3. A Brillig [foreign call][`ForeignCallWaitInfo`] has been encountered and must be resolved.
+    /// 4. A [call][`AcirCallWaitInfo`] to another circuit has been encountered and must be resolved.
     pub fn solve(&mut self) -> ACVMStatus {
         while self.status == ACVMStatus::InProgress {
             self.solve_opcode();
@@ -267,6 +354,10 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
                 Ok(Some(foreign_call)) => return self.wait_for_foreign_call(foreign_call),
                 res => res.map(|_| ()),
             },
+            Opcode::Call { .. } => match self.solve_call_opcode() {
+                Ok(Some(acir_call)) => return self.wait_for_acir_call(acir_call),
+                res => res.map(|_| ()),
+            },
         };
         self.handle_opcode_resolution(resolution)
     }
@@ -343,6 +434,30 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         }
     }
 
+    fn solve_call_opcode(&mut self) -> Result<Option<AcirCallWaitInfo>, OpcodeResolutionError> {
+        let opcodes = self.opcodes;
+        let Opcode::Call { id, inputs, outputs, predicate } = &opcodes[self.instruction_pointer]
+        else {
+            unreachable!("Not executing a Call opcode");
+        };
+
+        let witness = &mut self.witness_map;
+        if let Some(predicate) = predicate {
+            if get_value(predicate, witness)?.is_zero() {
+                for output in outputs {
+                    insert_value(output, FieldElement::zero(), witness)?;
+                }
+                return Ok(None);
+            }
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|input| witness_to_value(witness, *input).copied())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(AcirCallWaitInfo { id: *id, inputs }))
+    }
+
     pub fn step_into_brillig_opcode(&mut self) -> StepResult<'a, B> {
         let Opcode::Brillig(brillig) = &self.opcodes[self.instruction_pointer] else {
             return StepResult::Status(self.solve_opcode());
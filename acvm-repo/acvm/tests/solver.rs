@@ -12,7 +12,10 @@ use acir::{
 };
 
 use acvm::{
-    pwg::{ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError, ACVM},
+    pwg::{
+        ACVMStatus, AcirCallWaitInfo, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError,
+        ACVM,
+    },
     BlackBoxFunctionSolver,
 };
 use acvm_blackbox_solver::BlackBoxResolutionError;
@@ -648,3 +651,69 @@ fn memory_operations() {
 
     assert_eq!(witness_map[&Witness(8)], FieldElement::from(6u128));
 }
+
+#[test]
+fn acir_calls() {
+    let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+        (Witness(1), FieldElement::from(2u128)),
+        (Witness(2), FieldElement::from(3u128)),
+    ]));
+
+    let call = Opcode::Call {
+        id: 0,
+        inputs: vec![Witness(1), Witness(2)],
+        outputs: vec![Witness(3)],
+        predicate: None,
+    };
+    // Witness(3) + 1 = Witness(4)
+    let expression = Opcode::Arithmetic(Expression {
+        mul_terms: Vec::new(),
+        linear_combinations: vec![
+            (FieldElement::one(), Witness(3)),
+            (-FieldElement::one(), Witness(4)),
+        ],
+        q_c: FieldElement::one(),
+    });
+    let opcodes = vec![call, expression];
+
+    let mut acvm = ACVM::new(&StubbedBackend, &opcodes, initial_witness);
+    let solver_status = acvm.solve();
+    assert_eq!(
+        solver_status,
+        ACVMStatus::RequiresAcirCall(AcirCallWaitInfo {
+            id: 0,
+            inputs: vec![FieldElement::from(2u128), FieldElement::from(3u128)],
+        }),
+        "should require an ACIR call"
+    );
+
+    acvm.resolve_pending_acir_call(vec![FieldElement::from(6u128)]);
+    let solver_status = acvm.solve();
+    assert_eq!(solver_status, ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+
+    assert_eq!(witness_map[&Witness(4)], FieldElement::from(7u128));
+}
+
+#[test]
+fn skipped_acir_call_zeroes_outputs() {
+    let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+        (Witness(1), FieldElement::from(2u128)),
+        (Witness(2), FieldElement::zero()),
+    ]));
+
+    let call = Opcode::Call {
+        id: 0,
+        inputs: vec![Witness(1)],
+        outputs: vec![Witness(3)],
+        predicate: Some(Witness(2).into()),
+    };
+    let opcodes = vec![call];
+
+    let mut acvm = ACVM::new(&StubbedBackend, &opcodes, initial_witness);
+    let solver_status = acvm.solve();
+    assert_eq!(solver_status, ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+
+    assert_eq!(witness_map[&Witness(3)], FieldElement::zero());
+}
@@ -108,6 +108,11 @@ pub async fn execute_circuit_with_black_box_solver(
 
                 acvm.resolve_pending_foreign_call(result);
             }
+            ACVMStatus::RequiresAcirCall(acir_call) => {
+                let error_string =
+                    format!("Calls to other circuits are not supported (call to {})", acir_call.id);
+                return Err(JsExecutionError::new(error_string.into(), None).into());
+            }
         }
     }
 
@@ -58,7 +58,7 @@ pub enum VMStatus {
     },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq)]
 /// VM encapsulates the state of the Brillig VM during execution.
 pub struct VM<'a, B: BlackBoxFunctionSolver> {
     /// Register storage
@@ -83,6 +83,23 @@ pub struct VM<'a, B: BlackBoxFunctionSolver> {
     black_box_solver: &'a B,
 }
 
+// Implemented manually as deriving `Clone` would require the blackbox solver to be `Clone`.
+impl<'a, B: BlackBoxFunctionSolver> Clone for VM<'a, B> {
+    fn clone(&self) -> Self {
+        Self {
+            registers: self.registers.clone(),
+            program_counter: self.program_counter,
+            foreign_call_counter: self.foreign_call_counter,
+            foreign_call_results: self.foreign_call_results.clone(),
+            bytecode: self.bytecode,
+            status: self.status.clone(),
+            memory: self.memory.clone(),
+            call_stack: self.call_stack.clone(),
+            black_box_solver: self.black_box_solver,
+        }
+    }
+}
+
 impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
     /// Constructs a new VM instance
     pub fn new(
@@ -164,10 +181,20 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
         &self.registers
     }
 
+    /// Sets the value of the register at `register_index` to `value`.
+    pub fn set_register(&mut self, register_index: RegisterIndex, value: Value) {
+        self.registers.set(register_index, value);
+    }
+
     pub fn get_memory(&self) -> &Vec<Value> {
         self.memory.values()
     }
 
+    /// Writes `value` to memory at `ptr`, growing the memory if necessary.
+    pub fn write_memory_at(&mut self, ptr: usize, value: Value) {
+        self.memory.write(ptr, value);
+    }
+
     /// Process a single opcode and modify the program counter.
     pub fn process_opcode(&mut self) -> VMStatus {
         let opcode = &self.bytecode[self.program_counter];
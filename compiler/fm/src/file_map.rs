@@ -49,6 +49,12 @@ impl FileId {
     }
 }
 
+impl From<usize> for FileId {
+    fn from(file_id: usize) -> Self {
+        FileId(file_id)
+    }
+}
+
 pub struct File<'input>(&'input SimpleFile<PathString, String>);
 
 impl<'input> File<'input> {
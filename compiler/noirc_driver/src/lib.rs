@@ -3,20 +3,24 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-use clap::Args;
+use clap::{builder::PossibleValuesParser, Args};
 use fm::FileId;
 use iter_extended::vecmap;
 use noirc_abi::{AbiParameter, AbiType, ContractEvent};
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
+use noirc_evaluator::audit::{audit_circuit, audit_functions};
 use noirc_evaluator::errors::RuntimeError;
-use noirc_evaluator::{create_circuit, into_abi_params};
+use noirc_evaluator::{create_circuit, into_abi_params, ssa_pass_names, SsaEvaluatorOptions};
 use noirc_frontend::graph::{CrateId, CrateName};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
+use noirc_frontend::hir::resolution::unused_items::find_unused_items;
 use noirc_frontend::hir::Context;
-use noirc_frontend::monomorphization::monomorphize;
+use noirc_frontend::monomorphization::{
+    monomorphize, monomorphize_debug, monomorphize_debug_variables,
+};
 use noirc_frontend::node_interner::FuncId;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 mod contract;
 mod debug;
@@ -45,9 +49,25 @@ pub struct CompileOptions {
     #[arg(long, hide = true)]
     pub show_ssa: bool,
 
+    /// Emit debug information for the intermediate SSA IR after the given passes only
+    #[arg(long, hide = true, value_parser = PossibleValuesParser::new(ssa_pass_names()))]
+    pub show_ssa_pass: Vec<String>,
+
+    /// Write the intermediate SSA IR of every function after every pass to files in this directory
+    #[arg(long, hide = true)]
+    pub dump_ssa_dir: Option<PathBuf>,
+
+    /// Skip the given SSA optimization passes, e.g. to find the pass causing a miscompilation
+    #[arg(long, hide = true, value_parser = PossibleValuesParser::new(ssa_pass_names()))]
+    pub skip_ssa_pass: Vec<String>,
+
     #[arg(long, hide = true)]
     pub show_brillig: bool,
 
+    /// Check the intermediate SSA IR for broken invariants after every optimization pass
+    #[arg(long, hide = true)]
+    pub validate_ssa: bool,
+
     /// Display the ACIR for compiled circuit
     #[arg(long)]
     pub print_acir: bool,
@@ -59,6 +79,22 @@ pub struct CompileOptions {
     /// Suppress warnings
     #[arg(long, conflicts_with = "deny_warnings")]
     pub silence_warnings: bool,
+
+    /// Compile the whole program, including `main`, to a single unconstrained Brillig function.
+    /// The resulting circuit can be executed but cannot be used to create proofs
+    #[arg(long)]
+    pub force_brillig: bool,
+
+    /// Record where the values of the program's variables are held so that the debugger can
+    /// display them. The compiled circuit is unchanged
+    #[arg(long, hide = true)]
+    pub debug_variables: bool,
+
+    /// Instrument the program with oracle calls reporting the values of its variables to the
+    /// debugger, instead of recording where they are held. The instrumented circuit differs from
+    /// the original one so it cannot be used to create proofs for the program
+    #[arg(long)]
+    pub instrument_debug: bool,
 }
 
 /// Helper type used to signify where only warnings are expected in file diagnostics
@@ -111,7 +147,8 @@ pub fn add_dep(
         .expect("cyclic dependency triggered");
 }
 
-/// Run the lexing, parsing, name resolution, and type checking passes.
+/// Run the lexing, parsing, name resolution, and type checking passes,
+/// then report any items in the crate which are never used.
 ///
 /// This returns a (possibly empty) vector of any warnings found on success.
 /// On error, this returns a non-empty vector of warnings and error messages, with at least one error.
@@ -121,7 +158,8 @@ pub fn check_crate(
     deny_warnings: bool,
 ) -> CompilationResult<()> {
     let mut errors = vec![];
-    let diagnostics = CrateDefMap::collect_defs(crate_id, context);
+    let mut diagnostics = CrateDefMap::collect_defs(crate_id, context);
+    diagnostics.extend(find_unused_items(context, crate_id));
     errors.extend(diagnostics.into_iter().map(|(error, file_id)| {
         let diagnostic: CustomDiagnostic = error.into();
         diagnostic.in_file(file_id)
@@ -181,6 +219,10 @@ pub fn compile_main(
     if options.print_acir {
         println!("Compiled ACIR for main (unoptimized):");
         println!("{}", compiled_program.circuit);
+        for (id, function) in compiled_program.functions.iter().enumerate() {
+            println!("Compiled ACIR for function {id} (unoptimized):");
+            println!("{function}");
+        }
     }
 
     Ok((compiled_program, warnings))
@@ -248,6 +290,54 @@ fn has_errors(errors: &[FileDiagnostic], deny_warnings: bool) -> bool {
     }
 }
 
+/// Compile the `main` function or the contract entry points of a crate and audit their circuits
+/// for values computed by unconstrained code which are never sufficiently constrained.
+///
+/// On success this returns a warning for each finding alongside any compilation warnings.
+/// This function assumes [`check_crate`] is called beforehand.
+pub fn audit_crate(
+    context: &Context,
+    crate_id: CrateId,
+    options: &CompileOptions,
+) -> CompilationResult<()> {
+    let functions = match context.get_main_function(&crate_id) {
+        Some(main) => vec![main],
+        None => context
+            .get_all_contracts(&crate_id)
+            .into_iter()
+            .flat_map(|contract| contract.functions)
+            .filter(|function| function.is_entry_point)
+            .map(|function| function.function_id)
+            .collect(),
+    };
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for function_id in functions {
+        // The outputs of an unconstrained function are never constrained by design.
+        if context.def_interner.function_modifiers(&function_id).is_unconstrained {
+            continue;
+        }
+        match compile_no_check(context, options, function_id, None, true) {
+            Ok(program) => {
+                warnings.extend(program.warnings.into_iter().map(FileDiagnostic::from));
+                let findings = audit_circuit(&program.circuit, &program.debug)
+                    .into_iter()
+                    .chain(audit_functions(&program.circuit, &program.functions, &program.debug));
+                warnings.extend(findings.map(FileDiagnostic::from));
+            }
+            Err(error) => errors.push(FileDiagnostic::from(error)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(((), warnings))
+    } else {
+        errors.extend(warnings);
+        Err(errors)
+    }
+}
+
 /// Compile all of the functions associated with a Noir contract.
 fn compile_contract_inner(
     context: &Context,
@@ -278,6 +368,17 @@ fn compile_contract_inner(
                 continue;
             }
         };
+        // Contract artifacts hold a single circuit per function, so folded functions cannot be called.
+        if !function.functions.is_empty() {
+            let location = context.def_interner.function_meta(&function_id).location;
+            let error = CustomDiagnostic::simple_error(
+                format!("Contract function `{name}` calls a `#[fold]` function"),
+                "`#[fold]` functions are not supported within contracts".to_owned(),
+                location.span,
+            );
+            errors.push(error.in_file(location.file));
+            continue;
+        }
         warnings.extend(function.warnings);
         let modifiers = context.def_interner.function_modifiers(&function_id);
         let func_type = modifiers
@@ -331,28 +432,63 @@ pub fn compile_no_check(
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
 ) -> Result<CompiledProgram, RuntimeError> {
-    let program = monomorphize(main_function, &context.def_interner);
+    let (mut program, debug_variables) = if options.instrument_debug {
+        let (program, debug_variables) = monomorphize_debug(main_function, &context.def_interner);
+        (program, Some(debug_variables))
+    } else if options.debug_variables {
+        let (program, debug_variables) =
+            monomorphize_debug_variables(main_function, &context.def_interner);
+        (program, Some(debug_variables))
+    } else {
+        (monomorphize(main_function, &context.def_interner), None)
+    };
+    if options.force_brillig {
+        // Once every function is unconstrained, the SSA for the whole program is inlined into
+        // an unconstrained `main` which is then compiled into a single Brillig opcode.
+        for function in &mut program.functions {
+            function.unconstrained = true;
+        }
+    }
 
     let hash = fxhash::hash64(&program);
     let hashes_match = cached_program.as_ref().map_or(false, |program| program.hash == hash);
 
     // If user has specified that they want to see intermediate steps printed then we should
     // force compilation even if the program hasn't changed.
-    let force_compile =
-        force_compile || options.print_acir || options.show_brillig || options.show_ssa;
+    let force_compile = force_compile
+        || options.print_acir
+        || options.show_brillig
+        || options.show_ssa
+        || !options.show_ssa_pass.is_empty()
+        || options.dump_ssa_dir.is_some()
+        || !options.skip_ssa_pass.is_empty()
+        || options.validate_ssa;
 
     if !force_compile && hashes_match {
         return Ok(cached_program.expect("cache must exist for hashes to match"));
     }
 
-    let (circuit, debug, abi, warnings) =
-        create_circuit(context, program, options.show_ssa, options.show_brillig)?;
+    let ssa_options = SsaEvaluatorOptions {
+        print_ssa_passes: options.show_ssa,
+        print_ssa_pass_names: options.show_ssa_pass.clone(),
+        dump_ssa_dir: options.dump_ssa_dir.clone(),
+        skipped_ssa_passes: options.skip_ssa_pass.clone(),
+        validate_ssa: options.validate_ssa,
+        print_brillig_trace: options.show_brillig,
+    };
+    let (circuit, functions, mut debug, abi, warnings) =
+        create_circuit(context, program, &ssa_options)?;
+    if let Some(debug_variables) = debug_variables {
+        debug.variables = debug_variables.variables;
+        debug.functions = debug_variables.functions;
+    }
 
     let file_map = filter_relevant_files(&[debug.clone()], &context.file_manager);
 
     Ok(CompiledProgram {
         hash,
         circuit,
+        functions,
         debug,
         abi,
         file_map,
@@ -360,3 +496,101 @@ pub fn compile_no_check(
         warnings,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fm::FileManager;
+    use noirc_frontend::{
+        graph::{CrateGraph, CrateId},
+        hir::Context,
+    };
+
+    use crate::{audit_crate, check_crate, compile_contract, prepare_crate, CompileOptions};
+
+    fn prepare_source(source: &'static str) -> (Context, CrateId) {
+        let file_manager =
+            FileManager::new(Path::new("/test"), Box::new(move |_path| Ok(source.to_string())));
+        let mut context = Context::new(file_manager, CrateGraph::default());
+        let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+        (context, crate_id)
+    }
+
+    #[test]
+    fn audit_reports_unconstrained_brillig_outputs() {
+        let (mut context, crate_id) = prepare_source(
+            "
+            unconstrained fn double(x: Field) -> Field {
+                x * 2
+            }
+
+            fn main(x: Field) {
+                let _y = double(x);
+            }
+        ",
+        );
+        check_crate(&mut context, crate_id, false).expect("test program should type check");
+
+        let (_, warnings) = audit_crate(&context, crate_id, &CompileOptions::default())
+            .expect("test program should compile");
+        assert!(warnings.iter().any(|warning| {
+            warning.diagnostic.message
+                == "Value computed by unconstrained code is never constrained"
+        }));
+    }
+
+    #[test]
+    fn audit_reports_unconstrained_brillig_outputs_of_folded_functions() {
+        let (mut context, crate_id) = prepare_source(
+            "
+            unconstrained fn double(x: Field) -> Field {
+                x * 2
+            }
+
+            #[fold]
+            fn folded(x: Field) -> Field {
+                let _y = double(x);
+                x
+            }
+
+            fn main(x: Field) -> pub Field {
+                folded(x)
+            }
+        ",
+        );
+        check_crate(&mut context, crate_id, false).expect("test program should type check");
+
+        let (_, warnings) = audit_crate(&context, crate_id, &CompileOptions::default())
+            .expect("test program should compile");
+        assert!(warnings.iter().any(|warning| {
+            warning.diagnostic.message
+                == "Value computed by unconstrained code is never constrained"
+        }));
+    }
+
+    #[test]
+    fn contract_functions_cannot_call_folded_functions() {
+        let (mut context, crate_id) = prepare_source(
+            "
+            #[fold]
+            fn double(x: Field) -> Field {
+                x * 2
+            }
+
+            contract Foo {
+                fn entry(x: Field) -> pub Field {
+                    crate::double(x)
+                }
+            }
+        ",
+        );
+
+        let errors = compile_contract(&mut context, crate_id, &CompileOptions::default())
+            .err()
+            .expect("contract should fail to compile");
+        assert!(errors.iter().any(|error| {
+            error.diagnostic.message == "Contract function `entry` calls a `#[fold]` function"
+        }));
+    }
+}
@@ -23,6 +23,14 @@ pub struct CompiledProgram {
         deserialize_with = "Circuit::deserialize_circuit_base64"
     )]
     pub circuit: Circuit,
+    /// The circuits of the functions which are called from `circuit` rather than being inlined into it,
+    /// indexed by the `id` of their calls.
+    #[serde(
+        default,
+        serialize_with = "Circuit::serialize_circuits_base64",
+        deserialize_with = "Circuit::deserialize_circuits_base64"
+    )]
+    pub functions: Vec<Circuit>,
     pub abi: noirc_abi::Abi,
     pub debug: DebugInfo,
     pub file_map: BTreeMap<FileId, DebugFile>,
@@ -1,6 +1,9 @@
+use acvm::acir::brillig::RegisterIndex;
 use acvm::acir::circuit::OpcodeLocation;
+use acvm::acir::native_types::Expression;
 use acvm::compiler::AcirTransformationMap;
 
+use noirc_printable_type::PrintableType;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use std::collections::BTreeMap;
@@ -18,6 +21,63 @@ pub struct DebugInfo {
     /// that they should be serialized to/from strings.
     #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
     pub locations: BTreeMap<OpcodeLocation, Vec<Location>>,
+    /// The variables of a program compiled for the debugger, keyed by the id which is either
+    /// found in [`variable_assignments`][Self::variable_assignments] or passed to the
+    /// [`DEBUG_VAR_ASSIGN_ORACLE`] when the variable is assigned.
+    #[serde(default)]
+    pub variables: BTreeMap<u32, DebugVariable>,
+    /// The names of the functions of a program compiled for the debugger, keyed by the id
+    /// which is found in [`DebugVariable::function`] or passed to the [`DEBUG_FN_ENTER_ORACLE`]
+    /// and [`DEBUG_FN_EXIT_ORACLE`].
+    #[serde(default)]
+    pub functions: BTreeMap<u32, String>,
+    /// The variables which have been assigned by the time execution reaches an opcode, along with
+    /// where their new values can be read from.
+    #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
+    #[serde(default)]
+    pub variable_assignments: BTreeMap<OpcodeLocation, Vec<DebugVarAssignment>>,
+}
+
+/// Called by programs compiled with debug instrumentation with a variable's id followed by
+/// its new value whenever the variable is assigned.
+pub const DEBUG_VAR_ASSIGN_ORACLE: &str = "__debug_var_assign";
+/// Called by programs compiled with debug instrumentation with a function's id on entry to the function.
+pub const DEBUG_FN_ENTER_ORACLE: &str = "__debug_fn_enter";
+/// Called by programs compiled with debug instrumentation with a function's id on exit from the function.
+pub const DEBUG_FN_EXIT_ORACLE: &str = "__debug_fn_exit";
+
+/// A variable in the source code of a program along with the type of its value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DebugVariable {
+    pub name: String,
+    pub typ: PrintableType,
+    /// The id of the function which declares the variable.
+    #[serde(default)]
+    pub function: u32,
+}
+
+/// An assignment to a variable, recorded by the ACIR and Brillig generation of a program.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DebugVarAssignment {
+    pub var_id: u32,
+    /// The locations of the calls which were inlined into the function assigning the variable,
+    /// outermost first. These identify the stack frame of the variable.
+    pub call_sites: Vec<Location>,
+    /// Where each of the fields making up the variable's new value can be read from,
+    /// in the order expected by [`noirc_printable_type::decode_value`].
+    pub sources: Vec<DebugValueSource>,
+}
+
+/// Where the values of a variable can be read from while the program is executing.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DebugValueSource {
+    /// An expression over the witnesses which have already been solved, or a constant.
+    Expression(Expression),
+    /// A register of the Brillig VM.
+    Register(RegisterIndex),
+    /// An array of `size` fields in the memory of the Brillig VM, starting at the address
+    /// held in the `pointer` register.
+    Array { pointer: RegisterIndex, size: usize },
 }
 
 /// Holds OpCodes Counts for Acir and Brillig Opcodes
@@ -29,7 +89,12 @@ pub struct OpCodesCount {
 
 impl DebugInfo {
     pub fn new(locations: BTreeMap<OpcodeLocation, Vec<Location>>) -> Self {
-        DebugInfo { locations }
+        DebugInfo {
+            locations,
+            variables: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            variable_assignments: BTreeMap::new(),
+        }
     }
 
     /// Updates the locations map when the [`Circuit`][acvm::acir::circuit::Circuit] is modified.
@@ -45,6 +110,35 @@ impl DebugInfo {
                 self.locations.insert(new_opcode_location, source_locations.clone());
             });
         }
+
+        // Assignments recorded before an opcode which has been optimized away are carried over
+        // to the next opcode which remains, as the values they refer to are still available there.
+        let old_assignments = mem::take(&mut self.variable_assignments);
+        let mut pending_assignments = Vec::new();
+        for (old_opcode_location, mut assignments) in old_assignments {
+            let new_opcode_location = update_map.new_locations(old_opcode_location).next();
+            if let OpcodeLocation::Brillig { .. } = old_opcode_location {
+                if let Some(new_opcode_location) = new_opcode_location {
+                    self.variable_assignments.insert(new_opcode_location, assignments);
+                }
+                continue;
+            }
+
+            pending_assignments.append(&mut assignments);
+            if let Some(new_opcode_location) = new_opcode_location {
+                self.variable_assignments
+                    .entry(new_opcode_location)
+                    .or_default()
+                    .append(&mut pending_assignments);
+            }
+        }
+        if !pending_assignments.is_empty() {
+            let end_of_circuit = OpcodeLocation::Acir(update_map.opcodes_count());
+            self.variable_assignments
+                .entry(end_of_circuit)
+                .or_default()
+                .extend(pending_assignments);
+        }
     }
 
     pub fn opcode_location(&self, loc: &OpcodeLocation) -> Option<Vec<Location>> {
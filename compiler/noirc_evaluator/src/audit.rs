@@ -0,0 +1,269 @@
+//! Detects witnesses of an ACIR circuit which are not sufficiently constrained.
+//!
+//! Witnesses written by Brillig calls and directives are computed outside of the circuit, so
+//! a malicious prover is free to choose their values. Unless the circuit later constrains such a
+//! witness, the proof is valid for any value of it, which is almost always a soundness bug.
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::brillig::BrilligOutputs;
+use acvm::acir::circuit::directives::{Directive, QuotientDirective};
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+use acvm::acir::native_types::{Expression, Witness};
+use noirc_errors::debug_info::DebugInfo;
+
+use crate::errors::{InternalWarning, SsaReport};
+
+/// How strongly a witness computed outside of the circuit is constrained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WitnessConstraint {
+    /// The witness does not appear in any constraint.
+    Unconstrained,
+    /// The witness only appears in range constraints, which bound its value without
+    /// determining it.
+    RangeOnly,
+}
+
+/// A witness written by a Brillig call or a directive which is not sufficiently constrained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnderConstrainedWitness {
+    pub witness: Witness,
+    /// The location of the opcode which writes the witness.
+    pub opcode_location: OpcodeLocation,
+    pub constraint: WitnessConstraint,
+}
+
+/// Returns the witnesses of `circuit` which are written by a Brillig call or a directive
+/// and are then either never constrained or only range constrained.
+pub fn find_under_constrained_witnesses(circuit: &Circuit) -> Vec<UnderConstrainedWitness> {
+    let mut written_witnesses = BTreeMap::new();
+    let mut constrained_witnesses = BTreeSet::new();
+    let mut range_constrained_witnesses = BTreeSet::new();
+
+    for (acir_index, opcode) in circuit.opcodes.iter().enumerate() {
+        let opcode_location = OpcodeLocation::Acir(acir_index);
+        match opcode {
+            Opcode::Brillig(brillig) => {
+                for output in &brillig.outputs {
+                    let witnesses = match output {
+                        BrilligOutputs::Simple(witness) => std::slice::from_ref(witness),
+                        BrilligOutputs::Array(witnesses) => witnesses.as_slice(),
+                    };
+                    for witness in witnesses {
+                        written_witnesses.entry(*witness).or_insert(opcode_location);
+                    }
+                }
+            }
+            Opcode::Directive(directive) => {
+                for witness in directive_outputs(directive) {
+                    written_witnesses.entry(witness).or_insert(opcode_location);
+                }
+            }
+            Opcode::Arithmetic(expression) => {
+                constrained_witnesses.extend(expression_witnesses(expression));
+            }
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                range_constrained_witnesses.insert(input.witness);
+            }
+            Opcode::BlackBoxFuncCall(call) => {
+                constrained_witnesses
+                    .extend(call.get_inputs_vec().iter().map(|input| input.witness));
+                constrained_witnesses.extend(call.get_outputs_vec());
+            }
+            Opcode::MemoryOp { op, predicate, .. } => {
+                constrained_witnesses.extend(expression_witnesses(&op.operation));
+                constrained_witnesses.extend(expression_witnesses(&op.index));
+                constrained_witnesses.extend(expression_witnesses(&op.value));
+                if let Some(predicate) = predicate {
+                    constrained_witnesses.extend(expression_witnesses(predicate));
+                }
+            }
+            Opcode::MemoryInit { init, .. } => {
+                constrained_witnesses.extend(init.iter().copied());
+            }
+            // The callee constrains its arguments and return values.
+            Opcode::Call { inputs, outputs, predicate, .. } => {
+                constrained_witnesses.extend(inputs.iter().chain(outputs).copied());
+                if let Some(predicate) = predicate {
+                    constrained_witnesses.extend(expression_witnesses(predicate));
+                }
+            }
+        }
+    }
+
+    written_witnesses
+        .into_iter()
+        .filter(|(witness, _)| !constrained_witnesses.contains(witness))
+        .map(|(witness, opcode_location)| {
+            let constraint = if range_constrained_witnesses.contains(&witness) {
+                WitnessConstraint::RangeOnly
+            } else {
+                WitnessConstraint::Unconstrained
+            };
+            UnderConstrainedWitness { witness, opcode_location, constraint }
+        })
+        .collect()
+}
+
+/// Runs [`find_under_constrained_witnesses`] on `circuit`, returning a warning for each finding.
+///
+/// Findings which cannot be attributed to a location in the source code are reported with an
+/// empty call stack.
+pub fn audit_circuit(circuit: &Circuit, debug_info: &DebugInfo) -> Vec<SsaReport> {
+    find_under_constrained_witnesses(circuit)
+        .into_iter()
+        .map(|finding| {
+            let call_stack = debug_info
+                .opcode_location(&finding.opcode_location)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            let witness = finding.witness.witness_index();
+            let opcode_location = finding.opcode_location;
+            let warning = match finding.constraint {
+                WitnessConstraint::Unconstrained => {
+                    InternalWarning::UnconstrainedWitness { witness, opcode_location, call_stack }
+                }
+                WitnessConstraint::RangeOnly => InternalWarning::RangeOnlyConstrainedWitness {
+                    witness,
+                    opcode_location,
+                    call_stack,
+                },
+            };
+            SsaReport::Warning(warning)
+        })
+        .collect()
+}
+
+/// Runs [`audit_circuit`] on the circuits of the `#[fold]` functions called from `circuit`,
+/// returning a warning for each finding.
+///
+/// The debug information of these circuits is discarded when they are compiled, so the findings
+/// within a function are reported at the location of the first call to it from `circuit`.
+pub fn audit_functions(
+    circuit: &Circuit,
+    functions: &[Circuit],
+    debug_info: &DebugInfo,
+) -> Vec<SsaReport> {
+    functions
+        .iter()
+        .enumerate()
+        .flat_map(|(function_index, function)| {
+            let call_index = circuit.opcodes.iter().position(|opcode| {
+                matches!(opcode, Opcode::Call { id, .. } if *id as usize == function_index)
+            });
+            let call_stack = call_index
+                .and_then(|index| debug_info.opcode_location(&OpcodeLocation::Acir(index)))
+                .unwrap_or_default();
+            let locations = (0..function.opcodes.len())
+                .map(|opcode_index| (OpcodeLocation::Acir(opcode_index), call_stack.clone()))
+                .collect();
+            audit_circuit(function, &DebugInfo::new(locations))
+        })
+        .collect()
+}
+
+fn directive_outputs(directive: &Directive) -> Vec<Witness> {
+    match directive {
+        Directive::Quotient(QuotientDirective { q, r, .. }) => vec![*q, *r],
+        Directive::ToLeRadix { b, .. } => b.clone(),
+        Directive::PermutationSort { bits, .. } => bits.clone(),
+    }
+}
+
+fn expression_witnesses(expression: &Expression) -> impl Iterator<Item = Witness> + '_ {
+    let mul_witnesses = expression.mul_terms.iter().flat_map(|(_, lhs, rhs)| [*lhs, *rhs]);
+    let linear_witnesses = expression.linear_combinations.iter().map(|(_, witness)| *witness);
+    mul_witnesses.chain(linear_witnesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::brillig::{Brillig, BrilligOutputs};
+    use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+    use acvm::acir::native_types::{Expression, Witness};
+    use noirc_errors::debug_info::DebugInfo;
+
+    use crate::errors::{InternalWarning, SsaReport};
+
+    use super::{
+        audit_circuit, find_under_constrained_witnesses, UnderConstrainedWitness, WitnessConstraint,
+    };
+
+    fn brillig_call(outputs: Vec<Witness>) -> Opcode {
+        Opcode::Brillig(Brillig {
+            inputs: Vec::new(),
+            outputs: outputs.into_iter().map(BrilligOutputs::Simple).collect(),
+            bytecode: Vec::new(),
+            predicate: None,
+        })
+    }
+
+    #[test]
+    fn reports_unconstrained_and_range_only_witnesses() {
+        let (constrained, range_only, unconstrained) = (Witness(1), Witness(2), Witness(3));
+        let circuit = Circuit {
+            opcodes: vec![
+                brillig_call(vec![constrained, range_only, unconstrained]),
+                Opcode::Arithmetic(Expression::from(constrained)),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                    input: FunctionInput { witness: range_only, num_bits: 8 },
+                }),
+            ],
+            ..Circuit::default()
+        };
+
+        let findings = find_under_constrained_witnesses(&circuit);
+        assert_eq!(
+            findings,
+            vec![
+                UnderConstrainedWitness {
+                    witness: range_only,
+                    opcode_location: OpcodeLocation::Acir(0),
+                    constraint: WitnessConstraint::RangeOnly,
+                },
+                UnderConstrainedWitness {
+                    witness: unconstrained,
+                    opcode_location: OpcodeLocation::Acir(0),
+                    constraint: WitnessConstraint::Unconstrained,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn range_and_arithmetic_constraints_fully_constrain_a_witness() {
+        let witness = Witness(1);
+        let circuit = Circuit {
+            opcodes: vec![
+                brillig_call(vec![witness]),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                    input: FunctionInput { witness, num_bits: 8 },
+                }),
+                Opcode::Arithmetic(Expression::from(witness)),
+            ],
+            ..Circuit::default()
+        };
+
+        assert!(find_under_constrained_witnesses(&circuit).is_empty());
+    }
+
+    #[test]
+    fn findings_without_a_debug_location_are_reported() {
+        let circuit =
+            Circuit { opcodes: vec![brillig_call(vec![Witness(1)])], ..Circuit::default() };
+
+        let reports = audit_circuit(&circuit, &DebugInfo::default());
+        assert_eq!(reports.len(), 1);
+        let SsaReport::Warning(warning) = &reports[0];
+        assert_eq!(
+            warning,
+            &InternalWarning::UnconstrainedWitness {
+                witness: 1,
+                opcode_location: OpcodeLocation::Acir(0),
+                call_stack: Default::default(),
+            }
+        );
+    }
+}
@@ -18,6 +18,7 @@ use acvm::brillig_vm::brillig::HeapVector;
 use acvm::FieldElement;
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use iter_extended::vecmap;
+use noirc_errors::debug_info::DebugValueSource;
 use num_bigint::BigUint;
 
 use super::brillig_black_box::convert_black_box_call;
@@ -548,13 +549,32 @@ impl<'block> BrilligBlock<'block> {
                     dfg,
                 );
 
+                // The allocation of a constant array is shared by all its uses in the block,
+                // so it must never be updated in place.
+                let is_constant = matches!(dfg[dfg.resolve(*array)], Value::Array { .. });
+
                 self.convert_ssa_array_set(
                     source_variable,
                     destination_variable,
                     index_register,
                     value_variable,
+                    is_constant,
                 );
             }
+            Instruction::IncrementRc { value } => {
+                let array_pointer = self.convert_ssa_array_pointer(*value, dfg);
+                self.brillig_context.increment_reference_count(array_pointer);
+            }
+            Instruction::DecrementRc { value } => {
+                let array_pointer = self.convert_ssa_array_pointer(*value, dfg);
+                self.brillig_context.decrement_reference_count(array_pointer);
+            }
+            Instruction::DebugValue { variable, values } => {
+                let mut sources = Vec::new();
+                if values.iter().all(|value| self.debug_value_sources(*value, dfg, &mut sources)) {
+                    self.brillig_context.record_debug_value(*variable, sources);
+                }
+            }
             Instruction::RangeCheck { value, max_bit_size, assert_message } => {
                 let left = self.convert_ssa_register_value(*value, dfg);
                 let max = BigUint::from(2_u128).pow(*max_bit_size);
@@ -588,6 +608,44 @@ impl<'block> BrilligBlock<'block> {
         self.brillig_context.set_call_stack(CallStack::new());
     }
 
+    /// Appends where the fields of `value` can be read from by the debugger to `sources`.
+    ///
+    /// Returns false if the value is not available, or if it is held in a nested or
+    /// dynamically sized array.
+    fn debug_value_sources(
+        &self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+        sources: &mut Vec<DebugValueSource>,
+    ) -> bool {
+        let value_id = dfg.resolve(value_id);
+        if let Some(constant) = dfg.get_numeric_constant(value_id) {
+            sources.push(DebugValueSource::Expression(constant.into()));
+            return true;
+        }
+        if let Value::Array { array, .. } = &dfg[value_id] {
+            return array.iter().all(|element| self.debug_value_sources(*element, dfg, sources));
+        }
+
+        match self.variables.try_get_allocation(self.function_context, value_id, dfg) {
+            Some(RegisterOrMemory::RegisterIndex(register)) => {
+                sources.push(DebugValueSource::Register(register));
+                true
+            }
+            Some(RegisterOrMemory::HeapArray(HeapArray { pointer, size })) => {
+                let Type::Array(element_types, _) = dfg.type_of_value(value_id) else {
+                    return false;
+                };
+                if !element_types.iter().all(|typ| matches!(typ, Type::Numeric(_))) {
+                    return false;
+                }
+                sources.push(DebugValueSource::Array { pointer, size });
+                true
+            }
+            Some(RegisterOrMemory::HeapVector(_)) | None => false,
+        }
+    }
+
     fn convert_ssa_function_call(
         &mut self,
         func_id: FunctionId,
@@ -675,12 +733,16 @@ impl<'block> BrilligBlock<'block> {
 
     /// Array set operation in SSA returns a new array or slice that is a copy of the parameter array or slice
     /// With a specific value changed.
-    fn convert_ssa_array_set(
+    ///
+    /// If the reference count of the source array is one, nothing else can observe it anymore
+    /// and it is updated in place instead, unless `always_copy` is set.
+    pub(crate) fn convert_ssa_array_set(
         &mut self,
         source_variable: RegisterOrMemory,
         destination_variable: RegisterOrMemory,
         index_register: RegisterIndex,
         value_variable: RegisterOrMemory,
+        always_copy: bool,
     ) {
         let destination_pointer = match destination_variable {
             RegisterOrMemory::HeapArray(HeapArray { pointer, .. }) => pointer,
@@ -703,14 +765,32 @@ impl<'block> BrilligBlock<'block> {
             _ => unreachable!("ICE: array set on non-array"),
         };
 
-        self.brillig_context
-            .allocate_array_instruction(destination_pointer, source_size_as_register);
+        let copy_array = |ctx: &mut BrilligContext| {
+            ctx.allocate_array_instruction(destination_pointer, source_size_as_register);
+            ctx.copy_array_instruction(
+                source_pointer,
+                destination_pointer,
+                source_size_as_register,
+            );
+        };
 
-        self.brillig_context.copy_array_instruction(
-            source_pointer,
-            destination_pointer,
-            source_size_as_register,
-        );
+        if always_copy {
+            copy_array(self.brillig_context);
+        } else {
+            let reference_count = self.brillig_context.allocate_register();
+            self.brillig_context.load_reference_count(source_pointer, reference_count);
+            let is_unique = self.brillig_context.allocate_register();
+            self.brillig_context.usize_op(reference_count, is_unique, BinaryIntOp::Equals, 1);
+
+            self.brillig_context.branch_instruction(
+                is_unique,
+                |ctx| ctx.mov_instruction(destination_pointer, source_pointer),
+                copy_array,
+            );
+
+            self.brillig_context.deallocate_register(is_unique);
+            self.brillig_context.deallocate_register(reference_count);
+        }
 
         if let RegisterOrMemory::HeapVector(HeapVector { size: target_size, .. }) =
             destination_variable
@@ -1154,6 +1234,19 @@ impl<'block> BrilligBlock<'block> {
         extract_register(variable)
     }
 
+    /// Converts an array or slice value and returns the register holding its pointer.
+    fn convert_ssa_array_pointer(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> RegisterIndex {
+        match self.convert_ssa_value(value_id, dfg) {
+            RegisterOrMemory::HeapArray(HeapArray { pointer, .. })
+            | RegisterOrMemory::HeapVector(HeapVector { pointer, .. }) => pointer,
+            variable => unreachable!("ICE: Expected array or slice, got {variable:?}"),
+        }
+    }
+
     fn allocate_external_call_result(
         &mut self,
         result: ValueId,
@@ -1188,9 +1281,10 @@ impl<'block> BrilligBlock<'block> {
                 );
                 let vector = self.brillig_context.extract_heap_vector(variable);
 
-                // Set the pointer to the current stack frame
+                // Set the pointer to the current stack frame, right after the reference count
                 // The stack pointer will then be updated by the caller of this method
                 // once the external call is resolved and the array size is known
+                self.brillig_context.allocate_reference_count();
                 self.brillig_context.set_array_pointer(vector.pointer);
                 variable
             }
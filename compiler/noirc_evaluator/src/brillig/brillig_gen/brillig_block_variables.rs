@@ -105,6 +105,24 @@ impl BlockVariables {
         }
     }
 
+    /// For a given SSA value id, return the corresponding cached allocation if the value
+    /// is still available at this point.
+    pub(crate) fn try_get_allocation(
+        &self,
+        function_context: &FunctionContext,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> Option<RegisterOrMemory> {
+        let value_id = dfg.resolve(value_id);
+        if let Some(constant) = self.available_constants.get(&value_id) {
+            Some(*constant)
+        } else if self.available_variables.contains(&value_id) {
+            function_context.ssa_value_allocations.get(&value_id).copied()
+        } else {
+            None
+        }
+    }
+
     /// Creates a constant. Constants are a special case in SSA, since they are "defined" every time they are used.
     /// We keep constants block-local.
     pub(crate) fn allocate_constant(
@@ -410,7 +410,9 @@ mod tests {
 
             assert_eq!(vm.get_memory(), &expected_mem);
 
-            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(array.len()));
+            // The result is allocated after the calldata, the copy of the parameter made by the
+            // entry point and the reference counts of both allocations
+            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(2 * array.len() + 2));
             assert_eq!(vm.get_registers().get(RegisterIndex(1)), Value::from(array.len() + 1));
         }
 
@@ -422,13 +424,23 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(27_usize),
             ],
             Value::from(27_usize),
         );
-        test_case_push(true, vec![], vec![Value::from(27_usize)], Value::from(27_usize));
+        test_case_push(
+            true,
+            vec![],
+            vec![Value::from(1_usize), Value::from(1_usize), Value::from(27_usize)],
+            Value::from(27_usize),
+        );
         test_case_push(
             false,
             vec![Value::from(1_usize), Value::from(2_usize), Value::from(3_usize)],
@@ -436,6 +448,11 @@ mod tests {
                 Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
                 Value::from(27_usize),
                 Value::from(1_usize),
                 Value::from(2_usize),
@@ -443,7 +460,12 @@ mod tests {
             ],
             Value::from(27_usize),
         );
-        test_case_push(false, vec![], vec![Value::from(27_usize)], Value::from(27_usize));
+        test_case_push(
+            false,
+            vec![],
+            vec![Value::from(1_usize), Value::from(1_usize), Value::from(27_usize)],
+            Value::from(27_usize),
+        );
     }
 
     #[test]
@@ -499,7 +521,9 @@ mod tests {
 
             assert_eq!(vm.get_memory(), &expected_mem);
 
-            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(array.len()));
+            // The result is allocated after the calldata, the copy of the parameter made by the
+            // entry point and the reference counts of both allocations
+            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(2 * array.len() + 2));
             assert_eq!(vm.get_registers().get(RegisterIndex(1)), Value::from(array.len() - 1));
             assert_eq!(vm.get_registers().get(RegisterIndex(2)), expected_removed_item);
         }
@@ -512,6 +536,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
             ],
             Value::from(3_usize),
@@ -519,7 +548,12 @@ mod tests {
         test_case_pop(
             true,
             vec![Value::from(1_usize)],
-            vec![Value::from(1_usize)],
+            vec![
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+            ],
             Value::from(1_usize),
         );
         test_case_pop(
@@ -529,6 +563,11 @@ mod tests {
                 Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
             ],
@@ -589,7 +628,9 @@ mod tests {
 
             assert_eq!(vm.get_memory(), &expected_mem);
 
-            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(array.len()));
+            // The result is allocated after the calldata, the copy of the parameter made by the
+            // entry point and the reference counts of both allocations
+            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(2 * array.len() + 2));
             assert_eq!(vm.get_registers().get(RegisterIndex(1)), Value::from(array.len() + 1));
         }
 
@@ -600,6 +641,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(27_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
@@ -614,6 +660,11 @@ mod tests {
                 Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
                 Value::from(27_usize),
                 Value::from(1_usize),
                 Value::from(2_usize),
@@ -629,6 +680,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(27_usize),
                 Value::from(3_usize),
@@ -643,6 +699,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(27_usize),
@@ -652,7 +713,7 @@ mod tests {
         );
         test_case_insert(
             vec![],
-            vec![Value::from(27_usize)],
+            vec![Value::from(1_usize), Value::from(1_usize), Value::from(27_usize)],
             Value::from(27_usize),
             Value::from(0_usize),
         );
@@ -707,7 +768,9 @@ mod tests {
 
             assert_eq!(vm.get_memory(), &expected_mem);
 
-            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(array.len()));
+            // The result is allocated after the calldata, the copy of the parameter made by the
+            // entry point and the reference counts of both allocations
+            assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(2 * array.len() + 2));
             assert_eq!(vm.get_registers().get(RegisterIndex(1)), Value::from(array.len() - 1));
             assert_eq!(vm.get_registers().get(RegisterIndex(2)), expected_removed_item);
         }
@@ -718,6 +781,11 @@ mod tests {
                 Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
             ],
@@ -732,6 +800,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(3_usize),
             ],
             Value::from(1_usize),
@@ -745,6 +818,11 @@ mod tests {
                 Value::from(2_usize),
                 Value::from(3_usize),
                 Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(2_usize),
+                Value::from(3_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
                 Value::from(2_usize),
             ],
             Value::from(2_usize),
@@ -752,9 +830,80 @@ mod tests {
         );
         test_case_remove(
             vec![Value::from(1_usize)],
-            vec![Value::from(1_usize)],
+            vec![
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+                Value::from(1_usize),
+            ],
             Value::from(0_usize),
             Value::from(1_usize),
         );
     }
+
+    #[test]
+    fn test_slice_set_operation() {
+        fn test_case_set(always_copy: bool) -> (Vec<Value>, usize, usize) {
+            let array = vec![Value::from(1_usize), Value::from(2_usize), Value::from(3_usize)];
+            let arguments = vec![BrilligParameter::Array(vec![BrilligParameter::Simple], 3)];
+            let returns = vec![BrilligParameter::Simple, BrilligParameter::Simple];
+
+            let (_, mut function_context, mut context) = create_test_environment();
+
+            // Allocate the parameters
+            let array_pointer = context.allocate_register();
+            let array_size = context.make_constant(array.len().into());
+            let index = context.make_constant(1_usize.into());
+            let value = context.make_constant(27_usize.into());
+
+            // Allocate the results
+            let target_pointer = context.allocate_register();
+            let target_size = context.allocate_register();
+
+            let mut block = create_brillig_block(&mut function_context, &mut context);
+
+            block.convert_ssa_array_set(
+                RegisterOrMemory::HeapVector(HeapVector {
+                    pointer: array_pointer,
+                    size: array_size,
+                }),
+                RegisterOrMemory::HeapVector(HeapVector {
+                    pointer: target_pointer,
+                    size: target_size,
+                }),
+                index,
+                RegisterOrMemory::RegisterIndex(value),
+                always_copy,
+            );
+
+            context.return_instruction(&[array_pointer, target_pointer]);
+
+            let bytecode = create_entry_point_bytecode(context, arguments, returns).byte_code;
+            let vm = create_and_run_vm(array, vec![Value::from(0_usize)], &bytecode);
+
+            let source_pointer = vm.get_registers().get(RegisterIndex(0)).to_usize();
+            let target_pointer = vm.get_registers().get(RegisterIndex(1)).to_usize();
+            (vm.get_memory().clone(), source_pointer, target_pointer)
+        }
+
+        // The copy of the parameter made by the entry point is only referenced once,
+        // so it is updated in place.
+        let (memory, source_pointer, target_pointer) = test_case_set(false);
+        assert_eq!(target_pointer, source_pointer);
+        assert_eq!(
+            memory[source_pointer..source_pointer + 3],
+            [Value::from(1_usize), Value::from(27_usize), Value::from(3_usize)]
+        );
+
+        let (memory, source_pointer, target_pointer) = test_case_set(true);
+        assert_ne!(target_pointer, source_pointer);
+        assert_eq!(
+            memory[source_pointer..source_pointer + 3],
+            [Value::from(1_usize), Value::from(2_usize), Value::from(3_usize)]
+        );
+        assert_eq!(
+            memory[target_pointer..target_pointer + 3],
+            [Value::from(1_usize), Value::from(27_usize), Value::from(3_usize)]
+        );
+    }
 }
@@ -75,6 +75,12 @@ fn collect_variables_of_value(value_id: ValueId, dfg: &DataFlowGraph) -> Vec<Val
 fn variables_used_in_instruction(instruction: &Instruction, dfg: &DataFlowGraph) -> Vec<ValueId> {
     let mut used = Vec::new();
 
+    // Debug values do not extend the lifetime of their values: they are only recorded
+    // while their values are still alive.
+    if matches!(instruction, Instruction::DebugValue { .. }) {
+        return used;
+    }
+
     instruction.for_each_value(|value_id| {
         let underlying_ids = collect_variables_of_value(value_id, dfg);
         used.extend(underlying_ids);
@@ -24,6 +24,7 @@ use acvm::{
     FieldElement,
 };
 use debug_show::DebugShow;
+use noirc_errors::debug_info::DebugValueSource;
 
 /// Integer arithmetic in Brillig is limited to 127 bit
 /// integers.
@@ -132,16 +133,61 @@ impl BrilligContext {
 
     /// Allocates an array of size contained in size_register and stores the
     /// pointer to the array in `pointer_register`
+    ///
+    /// The array is preceded in memory by its reference count, which starts at one.
     pub(crate) fn allocate_array_instruction(
         &mut self,
         pointer_register: RegisterIndex,
         size_register: RegisterIndex,
     ) {
         self.debug_show.allocate_array_instruction(pointer_register, size_register);
+        self.allocate_reference_count();
         self.set_array_pointer(pointer_register);
         self.update_stack_pointer(size_register);
     }
 
+    /// Reserves the slot at the stack pointer for the reference count of the array
+    /// allocated right after it, and sets that count to one.
+    pub(crate) fn allocate_reference_count(&mut self) {
+        let one = self.make_constant(1_usize.into());
+        self.store_instruction(ReservedRegisters::stack_pointer(), one);
+        self.usize_op_in_place(ReservedRegisters::stack_pointer(), BinaryIntOp::Add, 1);
+        self.deallocate_register(one);
+    }
+
+    /// Loads the reference count of the array pointed by `array_pointer` into `result`
+    pub(crate) fn load_reference_count(
+        &mut self,
+        array_pointer: RegisterIndex,
+        result: RegisterIndex,
+    ) {
+        let reference_count_pointer = self.allocate_register();
+        self.usize_op(array_pointer, reference_count_pointer, BinaryIntOp::Sub, 1);
+        self.load_instruction(result, reference_count_pointer);
+        self.deallocate_register(reference_count_pointer);
+    }
+
+    /// Increments the reference count of the array pointed by `array_pointer`
+    pub(crate) fn increment_reference_count(&mut self, array_pointer: RegisterIndex) {
+        self.update_reference_count(array_pointer, BinaryIntOp::Add);
+    }
+
+    /// Decrements the reference count of the array pointed by `array_pointer`
+    pub(crate) fn decrement_reference_count(&mut self, array_pointer: RegisterIndex) {
+        self.update_reference_count(array_pointer, BinaryIntOp::Sub);
+    }
+
+    fn update_reference_count(&mut self, array_pointer: RegisterIndex, op: BinaryIntOp) {
+        let reference_count_pointer = self.allocate_register();
+        let reference_count = self.allocate_register();
+        self.usize_op(array_pointer, reference_count_pointer, BinaryIntOp::Sub, 1);
+        self.load_instruction(reference_count, reference_count_pointer);
+        self.usize_op_in_place(reference_count, op, 1);
+        self.store_instruction(reference_count_pointer, reference_count);
+        self.deallocate_register(reference_count);
+        self.deallocate_register(reference_count_pointer);
+    }
+
     pub(crate) fn set_array_pointer(&mut self, pointer_register: RegisterIndex) {
         self.debug_show.mov_instruction(pointer_register, ReservedRegisters::stack_pointer());
         self.push_opcode(BrilligOpcode::Mov {
@@ -287,6 +333,29 @@ impl BrilligContext {
         self.deallocate_register(iterator_register);
     }
 
+    /// This instruction will issue a branch that runs `f_if_true` if `condition` is true
+    /// and `f_if_false` otherwise.
+    pub(crate) fn branch_instruction(
+        &mut self,
+        condition: RegisterIndex,
+        f_if_true: impl FnOnce(&mut BrilligContext),
+        f_if_false: impl FnOnce(&mut BrilligContext),
+    ) {
+        // The branches may issue loops which enter new sections, so the labels of the branch
+        // are derived from its position rather than from the section counter.
+        let position = self.obj.index_of_next_opcode();
+        let then_label = format!("{}-if-{position}", self.context_label);
+        let end_label = format!("{}-end-if-{position}", self.context_label);
+
+        self.jump_if_instruction(condition, &then_label);
+        f_if_false(self);
+        self.jump_instruction(&end_label);
+
+        self.obj.add_label_at_position(then_label, self.obj.index_of_next_opcode());
+        f_if_true(self);
+        self.obj.add_label_at_position(end_label, self.obj.index_of_next_opcode());
+    }
+
     /// Adds a label to the next opcode
     pub(crate) fn enter_context<T: ToString>(&mut self, label: T) {
         self.debug_show.enter_context(label.to_string());
@@ -965,6 +1034,11 @@ impl BrilligContext {
     pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
         self.obj.set_call_stack(call_stack);
     }
+
+    /// Records where the debugger can read the new value of a source code variable from.
+    pub(crate) fn record_debug_value(&mut self, var_id: u32, sources: Vec<DebugValueSource>) {
+        self.obj.add_variable_assignment(var_id, sources);
+    }
 }
 
 pub(crate) fn extract_register(variable: RegisterOrMemory) -> RegisterIndex {
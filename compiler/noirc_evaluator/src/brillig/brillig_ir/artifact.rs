@@ -1,4 +1,5 @@
 use acvm::acir::brillig::Opcode as BrilligOpcode;
+use noirc_errors::debug_info::{DebugValueSource, DebugVarAssignment};
 use std::collections::{BTreeMap, HashMap};
 
 use crate::ssa::ir::dfg::CallStack;
@@ -18,6 +19,7 @@ pub(crate) struct GeneratedBrillig {
     pub(crate) byte_code: Vec<BrilligOpcode>,
     pub(crate) locations: BTreeMap<OpcodeLocation, CallStack>,
     pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+    pub(crate) variable_assignments: BTreeMap<OpcodeLocation, Vec<DebugVarAssignment>>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -41,6 +43,8 @@ pub(crate) struct BrilligArtifact {
     unresolved_external_call_labels: Vec<(JumpInstructionPosition, UnresolvedJumpLocation)>,
     /// Maps the opcodes that are associated with a callstack to it.
     locations: BTreeMap<OpcodeLocation, CallStack>,
+    /// The variables which have been assigned by the time execution reaches an opcode.
+    variable_assignments: BTreeMap<OpcodeLocation, Vec<DebugVarAssignment>>,
     /// The current call stack. All opcodes that are pushed will be associated with this call stack.
     call_stack: CallStack,
 }
@@ -75,6 +79,7 @@ impl BrilligArtifact {
             byte_code: self.byte_code,
             locations: self.locations,
             assert_messages: self.assert_messages,
+            variable_assignments: self.variable_assignments,
         }
     }
 
@@ -145,6 +150,10 @@ impl BrilligArtifact {
         for (position_in_bytecode, call_stack) in obj.locations.iter() {
             self.locations.insert(position_in_bytecode + offset, call_stack.clone());
         }
+
+        for (position_in_bytecode, assignments) in &obj.variable_assignments {
+            self.variable_assignments.insert(position_in_bytecode + offset, assignments.clone());
+        }
     }
 
     /// Adds a brillig instruction to the brillig byte code
@@ -254,6 +263,17 @@ impl BrilligArtifact {
         self.call_stack = call_stack;
     }
 
+    /// Records that the variable `var_id` can be read from `sources` once execution reaches
+    /// the next opcode.
+    pub(crate) fn add_variable_assignment(&mut self, var_id: u32, sources: Vec<DebugValueSource>) {
+        let mut call_sites: Vec<_> = self.call_stack.iter().copied().collect();
+        call_sites.pop();
+        self.variable_assignments
+            .entry(self.index_of_next_opcode())
+            .or_default()
+            .push(DebugVarAssignment { var_id, call_sites, sources });
+    }
+
     pub(crate) fn add_assert_message_to_last_opcode(&mut self, message: String) {
         let position = self.index_of_next_opcode() - 1;
         self.assert_messages.insert(position, message);
@@ -67,11 +67,21 @@ impl BrilligContext {
 
         for (index, parameter) in arguments.iter().enumerate() {
             if let BrilligParameter::Array(item_type, item_count) = parameter {
+                let pointer_register = ReservedRegisters::user_register_index(index);
                 if item_type.iter().any(|param| !matches!(param, BrilligParameter::Simple)) {
-                    let pointer_register = ReservedRegisters::user_register_index(index);
                     let deflattened_register =
                         self.deflatten_array(item_type, *item_count, pointer_register);
                     self.mov_instruction(pointer_register, deflattened_register);
+                } else {
+                    // The calldata has no room for a reference count, so the array is copied
+                    // into an allocation which has one.
+                    let copied_register = self.allocate_register();
+                    let size_register = self.make_constant((item_count * item_type.len()).into());
+                    self.allocate_array_instruction(copied_register, size_register);
+                    self.copy_array_instruction(pointer_register, copied_register, size_register);
+                    self.mov_instruction(pointer_register, copied_register);
+                    self.deallocate_register(size_register);
+                    self.deallocate_register(copied_register);
                 }
             }
         }
@@ -321,7 +331,11 @@ mod tests {
         let vm = create_and_run_vm(flattened_array.clone(), vec![Value::from(0_usize)], &bytecode);
         let memory = vm.get_memory();
 
-        assert_eq!(vm.get_registers().get(RegisterIndex(0)), Value::from(flattened_array.len()));
+        // The deflattened array starts after the original array and its own reference count
+        assert_eq!(
+            vm.get_registers().get(RegisterIndex(0)),
+            Value::from(flattened_array.len() + 1)
+        );
         assert_eq!(
             memory,
             &vec![
@@ -332,16 +346,20 @@ mod tests {
                 Value::from(4_usize),
                 Value::from(5_usize),
                 Value::from(6_usize),
+                // The reference count of the deflattened array
+                Value::from(1_usize),
                 // The pointer to the nested array of the first item
-                Value::from(10_usize),
+                Value::from(12_usize),
                 Value::from(3_usize),
                 // The pointer to the nested array of the second item
-                Value::from(12_usize),
+                Value::from(15_usize),
                 Value::from(6_usize),
-                // The nested array of the first item
+                // The nested array of the first item and its reference count
+                Value::from(1_usize),
                 Value::from(1_usize),
                 Value::from(2_usize),
-                // The nested array of the second item
+                // The nested array of the second item and its reference count
+                Value::from(1_usize),
                 Value::from(4_usize),
                 Value::from(5_usize),
             ]
@@ -383,8 +401,9 @@ mod tests {
 
         assert_eq!(
             vm.get_registers().get(RegisterIndex(0)),
-            // The returned value will be past the original array and the deflattened array
-            Value::from(flattened_array.len() + (flattened_array.len() + 2)),
+            // The returned value will be past the original array, the deflattened array
+            // and the reference counts of the four arrays allocated so far
+            Value::from(flattened_array.len() + (flattened_array.len() + 2) + 4),
         );
 
         assert_eq!(
@@ -397,19 +416,24 @@ mod tests {
                 Value::from(4_usize),
                 Value::from(5_usize),
                 Value::from(6_usize),
+                // The reference count of the deflattened array
+                Value::from(1_usize),
                 // The pointer to the nested array of the first item
                 Value::from(1_usize),
-                Value::from(10_usize),
+                Value::from(12_usize),
                 // The pointer to the nested array of the second item
                 Value::from(4_usize),
-                Value::from(12_usize),
-                // The nested array of the first item
+                Value::from(15_usize),
+                // The nested array of the first item and its reference count
+                Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
-                // The nested array of the second item
+                // The nested array of the second item and its reference count
+                Value::from(1_usize),
                 Value::from(5_usize),
                 Value::from(6_usize),
-                // The values flattened again
+                // The values flattened again and their reference count
+                Value::from(1_usize),
                 Value::from(1_usize),
                 Value::from(2_usize),
                 Value::from(3_usize),
@@ -7,7 +7,10 @@
 //! An Error of the former is a user Error
 //!
 //! An Error of the latter is an error in the implementation of the compiler
-use acvm::{acir::native_types::Expression, FieldElement};
+use acvm::{
+    acir::{circuit::OpcodeLocation, native_types::Expression},
+    FieldElement,
+};
 use iter_extended::vecmap;
 use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
 use thiserror::Error;
@@ -71,12 +74,18 @@ impl From<SsaReport> for FileDiagnostic {
                     InternalWarning::VerifyProof { call_stack } => {
                         ("verify_proof(...) aggregates data for the verifier, the actual verification will be done when the full proof is verified using nargo verify. nargo prove may generate an invalid proof if bad data is used as input to verify_proof".to_string(), call_stack)
                     },
+                    InternalWarning::UnconstrainedWitness { witness, opcode_location, call_stack } => {
+                        (format!("witness _{witness} written by opcode {opcode_location} can take any value chosen by the prover"), call_stack)
+                    },
+                    InternalWarning::RangeOnlyConstrainedWitness { witness, opcode_location, call_stack } => {
+                        (format!("witness _{witness} written by opcode {opcode_location} can take any value within its range chosen by the prover"), call_stack)
+                    },
                 };
                 let call_stack = vecmap(call_stack, |location| location);
                 let file_id = call_stack.last().map(|location| location.file).unwrap_or_default();
-                let location = call_stack.last().expect("Expected RuntimeError to have a location");
-                let diagnostic =
-                    Diagnostic::simple_warning(message, secondary_message, location.span);
+                // Warnings found by auditing the final circuit may not be attributable to any source code.
+                let span = call_stack.last().map(|location| location.span).unwrap_or_default();
+                let diagnostic = Diagnostic::simple_warning(message, secondary_message, span);
                 diagnostic.in_file(file_id).with_call_stack(call_stack)
             }
         }
@@ -89,6 +98,14 @@ pub enum InternalWarning {
     ReturnConstant { call_stack: CallStack },
     #[error("Calling std::verify_proof(...) does not verify a proof")]
     VerifyProof { call_stack: CallStack },
+    #[error("Value computed by unconstrained code is never constrained")]
+    UnconstrainedWitness { witness: u32, opcode_location: OpcodeLocation, call_stack: CallStack },
+    #[error("Value computed by unconstrained code is only range constrained")]
+    RangeOnlyConstrainedWitness {
+        witness: u32,
+        opcode_location: OpcodeLocation,
+        call_stack: CallStack,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Error)]
@@ -107,6 +124,8 @@ pub enum InternalError {
     UndeclaredAcirVar { call_stack: CallStack },
     #[error("ICE: Expected {expected:?}, found {found:?}")]
     UnExpected { expected: String, found: String, call_stack: CallStack },
+    #[error("ICE: SSA is invalid after {pass}: {message}")]
+    InvalidSsa { pass: String, message: String, call_stack: CallStack },
 }
 
 impl RuntimeError {
@@ -119,7 +138,8 @@ impl RuntimeError {
                 | InternalError::MissingArg { call_stack, .. }
                 | InternalError::NotAConstant { call_stack, .. }
                 | InternalError::UndeclaredAcirVar { call_stack }
-                | InternalError::UnExpected { call_stack, .. },
+                | InternalError::UnExpected { call_stack, .. }
+                | InternalError::InvalidSsa { call_stack, .. },
             )
             | RuntimeError::FailedConstraint { call_stack, .. }
             | RuntimeError::IndexOutOfBounds { call_stack, .. }
@@ -3,6 +3,7 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+pub mod audit;
 pub mod errors;
 
 // SSA code to create the SSA based IR
@@ -12,4 +13,4 @@ pub mod ssa;
 pub mod brillig;
 
 pub use ssa::abi_gen::into_abi_params;
-pub use ssa::create_circuit;
+pub use ssa::{create_circuit, run_passes_on_ssa, ssa_pass_names, SsaEvaluatorOptions};
@@ -10,16 +10,19 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     ops::Range,
+    path::PathBuf,
 };
 
 use crate::{
     brillig::Brillig,
-    errors::{RuntimeError, SsaReport},
+    errors::{InternalError, RuntimeError, SsaReport},
 };
 use acvm::acir::{
     circuit::{Circuit, PublicInputs},
     native_types::Witness,
 };
+use iter_extended::vecmap;
+use thiserror::Error;
 
 use noirc_errors::debug_info::DebugInfo;
 
@@ -34,45 +37,134 @@ mod acir_gen;
 pub(super) mod function_builder;
 pub mod ir;
 mod opt;
+pub mod parser;
 pub mod ssa_gen;
+mod validation;
 
-/// Optimize the given program by converting it into SSA
-/// form and performing optimizations there. When finished,
-/// convert the final SSA into ACIR and return it.
-pub(crate) fn optimize_into_acir(
-    program: Program,
-    print_ssa_passes: bool,
-    print_brillig_trace: bool,
-) -> Result<GeneratedAcir, RuntimeError> {
-    let abi_distinctness = program.return_distinctness;
+/// Options controlling the SSA pipeline run by [`create_circuit`] and its debugging output.
+#[derive(Debug, Clone, Default)]
+pub struct SsaEvaluatorOptions {
+    /// Print the SSA after every pass.
+    pub print_ssa_passes: bool,
+    /// Print the SSA after the passes with these names, in addition to `print_ssa_passes`.
+    pub print_ssa_pass_names: Vec<String>,
+    /// Write the SSA of every function after every pass to a file in this directory.
+    pub dump_ssa_dir: Option<PathBuf>,
+    /// Skip the passes with these names, e.g. to find the pass responsible for a miscompilation.
+    pub skipped_ssa_passes: Vec<String>,
+    /// Check the SSA for broken invariants after every pass.
+    pub validate_ssa: bool,
+    /// Print the Brillig bytecode generated for each unconstrained function.
+    pub print_brillig_trace: bool,
+}
 
-    let ssa_builder = SsaBuilder::new(program, print_ssa_passes)?
-        .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
-        .run_pass(Ssa::inline_functions, "After Inlining:")
+/// A pass of the SSA pipeline.
+struct SsaPass {
+    /// The name of the method of [`Ssa`] implementing the pass, used to refer to it from the
+    /// command line.
+    name: &'static str,
+    /// The header printed above the SSA output by the pass.
+    msg: &'static str,
+    run: fn(Ssa) -> Result<Ssa, RuntimeError>,
+    /// Whether ACIR functions must be free of memory operations once this pass has run.
+    removes_memory_operations: bool,
+}
+
+impl SsaPass {
+    fn new(
+        name: &'static str,
+        msg: &'static str,
+        run: fn(Ssa) -> Result<Ssa, RuntimeError>,
+    ) -> Self {
+        SsaPass { name, msg, run, removes_memory_operations: false }
+    }
+}
+
+/// The passes run on the SSA before it is converted into Brillig, in order.
+fn optimization_passes() -> Vec<SsaPass> {
+    vec![
+        SsaPass::new("defunctionalize", "After Defunctionalization:", |ssa| {
+            Ok(ssa.defunctionalize())
+        }),
+        SsaPass::new(
+            "remove_unreachable_functions",
+            "After Removing Unreachable Functions:",
+            |ssa| Ok(ssa.remove_unreachable_functions()),
+        ),
+        SsaPass::new("inline_functions", "After Inlining:", |ssa| Ok(ssa.inline_functions())),
         // Run mem2reg with the CFG separated into blocks
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .try_run_pass(Ssa::evaluate_assert_constant, "After Assert Constant:")?
-        .try_run_pass(Ssa::unroll_loops, "After Unrolling:")?
-        .run_pass(Ssa::simplify_cfg, "After Simplifying:")
+        SsaPass::new("mem2reg", "After Mem2Reg:", |ssa| Ok(ssa.mem2reg())),
+        SsaPass::new(
+            "evaluate_assert_constant",
+            "After Assert Constant:",
+            Ssa::evaluate_assert_constant,
+        ),
+        SsaPass::new("unroll_loops", "After Unrolling:", Ssa::unroll_loops),
+        SsaPass::new("simplify_cfg", "After Simplifying:", |ssa| Ok(ssa.simplify_cfg())),
         // Run mem2reg before flattening to handle any promotion
         // of values that can be accessed after loop unrolling.
         // If there are slice mergers uncovered by loop unrolling
         // and this pass is missed, slice merging will fail inside of flattening.
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .run_pass(Ssa::flatten_cfg, "After Flattening:")
+        SsaPass::new("mem2reg", "After Mem2Reg:", |ssa| Ok(ssa.mem2reg())),
+        SsaPass::new("flatten_cfg", "After Flattening:", |ssa| Ok(ssa.flatten_cfg())),
         // Run mem2reg once more with the flattened CFG to catch any remaining loads/stores
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .run_pass(Ssa::fold_constants, "After Constant Folding:")
-        .run_pass(Ssa::dead_instruction_elimination, "After Dead Instruction Elimination:");
+        SsaPass {
+            removes_memory_operations: true,
+            ..SsaPass::new("mem2reg", "After Mem2Reg:", |ssa| Ok(ssa.mem2reg()))
+        },
+        SsaPass::new("fold_constants", "After Constant Folding:", |ssa| Ok(ssa.fold_constants())),
+        SsaPass::new("loop_invariant_code_motion", "After Loop Invariant Code Motion:", |ssa| {
+            Ok(ssa.loop_invariant_code_motion())
+        }),
+        SsaPass::new(
+            "remove_redundant_range_checks",
+            "After Removing Redundant Range Checks:",
+            |ssa| Ok(ssa.remove_redundant_range_checks()),
+        ),
+        SsaPass::new(
+            "dead_instruction_elimination",
+            "After Dead Instruction Elimination:",
+            |ssa| Ok(ssa.dead_instruction_elimination()),
+        ),
+    ]
+}
+
+/// The passes which are not necessary for Brillig generation but are necessary for ACIR
+/// generation, run after Brillig generation.
+///
+/// We only need to fill out nested slices as we need to have a known length when dealing with
+/// memory operations in ACIR gen while this is not necessary in the Brillig IR.
+fn acir_passes() -> Vec<SsaPass> {
+    vec![SsaPass::new("fill_internal_slices", "After Fill Internal Slice Dummy Data:", |ssa| {
+        Ok(ssa.fill_internal_slices())
+    })]
+}
+
+/// Returns the names of the passes of the SSA pipeline, which may be used to select passes in
+/// [`SsaEvaluatorOptions`] and [`run_passes_on_ssa`].
+pub fn ssa_pass_names() -> Vec<&'static str> {
+    let mut names: Vec<_> =
+        optimization_passes().into_iter().chain(acir_passes()).map(|pass| pass.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Optimize the given program by converting it into SSA
+/// form and performing optimizations there. When finished,
+/// convert the final SSA into ACIR and return it along with
+/// the ACIR of each `#[fold]` function.
+pub(crate) fn optimize_into_acir(
+    program: Program,
+    options: &SsaEvaluatorOptions,
+) -> Result<(GeneratedAcir, Vec<GeneratedAcir>), RuntimeError> {
+    let abi_distinctness = program.return_distinctness;
+
+    let ssa_builder = SsaBuilder::new(program, options)?.run_passes(&optimization_passes())?;
 
-    let brillig = ssa_builder.to_brillig(print_brillig_trace);
+    let brillig = ssa_builder.to_brillig(options.print_brillig_trace);
 
-    // Split off any passes the are not necessary for Brillig generation but are necessary for ACIR generation.
-    // We only need to fill out nested slices as we need to have a known length when dealing with memory operations
-    // in ACIR gen while this is not necessary in the Brillig IR.
-    let ssa = ssa_builder
-        .run_pass(Ssa::fill_internal_slices, "After Fill Internal Slice Dummy Data:")
-        .finish();
+    let ssa = ssa_builder.run_passes(&acir_passes())?.finish();
 
     let last_array_uses = ssa.find_last_array_uses();
     ssa.into_acir(brillig, abi_distinctness, &last_array_uses)
@@ -81,15 +173,16 @@ pub(crate) fn optimize_into_acir(
 /// Compiles the [`Program`] into [`ACIR`][acvm::acir::circuit::Circuit].
 ///
 /// The output ACIR is is backend-agnostic and so must go through a transformation pass before usage in proof generation.
+///
+/// Each `#[fold]` function is compiled into a separate circuit, called from `main` by its index
+/// in the returned vector of circuits.
 pub fn create_circuit(
     context: &Context,
     program: Program,
-    enable_ssa_logging: bool,
-    enable_brillig_logging: bool,
-) -> Result<(Circuit, DebugInfo, Abi, Vec<SsaReport>), RuntimeError> {
+    options: &SsaEvaluatorOptions,
+) -> Result<(Circuit, Vec<Circuit>, DebugInfo, Abi, Vec<SsaReport>), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let mut generated_acir =
-        optimize_into_acir(program, enable_ssa_logging, enable_brillig_logging)?;
+    let (mut generated_acir, generated_functions) = optimize_into_acir(program, options)?;
     let opcodes = generated_acir.take_opcodes();
     let GeneratedAcir {
         current_witness_index,
@@ -97,10 +190,17 @@ pub fn create_circuit(
         locations,
         input_witnesses,
         assert_messages,
-        warnings,
+        variable_assignments,
+        mut warnings,
         ..
     } = generated_acir;
 
+    let functions = vecmap(generated_functions, |generated_function| {
+        let (function, function_warnings) = create_function_circuit(generated_function);
+        warnings.extend(function_warnings);
+        function
+    });
+
     let abi = gen_abi(context, func_sig, input_witnesses, return_witnesses.clone());
     let public_abi = abi.clone().public_abi();
 
@@ -127,55 +227,204 @@ pub fn create_circuit(
         .collect();
 
     let mut debug_info = DebugInfo::new(locations);
+    debug_info.variable_assignments = variable_assignments;
 
     // Perform any ACIR-level optimizations
     let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
     debug_info.update_acir(transformation_map);
 
-    Ok((optimized_circuit, debug_info, abi, warnings))
+    Ok((optimized_circuit, functions, debug_info, abi, warnings))
+}
+
+/// Builds the circuit of a `#[fold]` function, which takes all of its inputs as private parameters.
+///
+/// The debug information of the function is discarded as errors within it are reported at the
+/// location of the call.
+fn create_function_circuit(mut generated_acir: GeneratedAcir) -> (Circuit, Vec<SsaReport>) {
+    let opcodes = generated_acir.take_opcodes();
+    let GeneratedAcir {
+        current_witness_index,
+        return_witnesses,
+        input_witnesses,
+        assert_messages,
+        warnings,
+        ..
+    } = generated_acir;
+
+    let private_parameters = input_witnesses
+        .into_iter()
+        .flat_map(|range| (range.start.witness_index()..range.end.witness_index()).map(Witness))
+        .collect();
+
+    let circuit = Circuit {
+        current_witness_index,
+        opcodes,
+        private_parameters,
+        public_parameters: PublicInputs::default(),
+        return_values: PublicInputs(return_witnesses.into_iter().collect()),
+        assert_messages: assert_messages.into_iter().collect(),
+    };
+
+    let (optimized_circuit, _) = acvm::compiler::optimize(circuit);
+    (optimized_circuit, warnings)
+}
+
+#[derive(Debug, Error)]
+pub enum SsaPassError {
+    #[error(transparent)]
+    ParseError(#[from] parser::SsaParseError),
+    #[error("Unknown SSA pass `{0}`")]
+    UnknownPass(String),
+    #[error(transparent)]
+    RuntimeError(#[from] RuntimeError),
+}
+
+/// Parses SSA in the format printed by `--show-ssa`, runs the named passes on it in the given
+/// order and returns the resulting SSA in the same format.
+///
+/// Passes are named after the methods of [`Ssa`] which implement them, e.g. `mem2reg` or
+/// `fold_constants`. This allows a bug in a single pass to be reproduced from a small snippet.
+pub fn run_passes_on_ssa(ssa: &str, passes: &[&str]) -> Result<String, SsaPassError> {
+    let mut ssa: Ssa = ssa.parse()?;
+    for name in passes {
+        let pass = named_pass(name).ok_or_else(|| SsaPassError::UnknownPass(name.to_string()))?;
+        ssa = pass(ssa)?;
+    }
+    Ok(ssa.to_string())
+}
+
+/// Returns the SSA pass with the given name, or `None` if there is no such pass.
+fn named_pass(name: &str) -> Option<fn(Ssa) -> Result<Ssa, RuntimeError>> {
+    optimization_passes()
+        .into_iter()
+        .chain(acir_passes())
+        .find(|pass| pass.name == name)
+        .map(|pass| pass.run)
 }
 
-// This is just a convenience object to bundle the ssa with `print_ssa_passes` for debug printing.
-struct SsaBuilder {
+// This is just a convenience object to bundle the ssa with the options controlling
+// the pipeline and its debugging output.
+struct SsaBuilder<'a> {
     ssa: Ssa,
-    print_ssa_passes: bool,
+    options: &'a SsaEvaluatorOptions,
+    /// Whether ACIR functions are expected to be free of allocate, load and store instructions.
+    memory_operations_forbidden: bool,
+    /// The name of the last pass which was run, used to report which pass broke the SSA.
+    last_pass: &'static str,
+    /// The number of passes run so far, used to order the files written to `dump_ssa_dir`.
+    passes_run: usize,
 }
 
-impl SsaBuilder {
-    fn new(program: Program, print_ssa_passes: bool) -> Result<SsaBuilder, RuntimeError> {
+impl<'a> SsaBuilder<'a> {
+    fn new(program: Program, options: &'a SsaEvaluatorOptions) -> Result<Self, RuntimeError> {
         let ssa = ssa_gen::generate_ssa(program)?;
-        Ok(SsaBuilder { print_ssa_passes, ssa }.print("Initial SSA:"))
+        Self::from_ssa(ssa, options)
+    }
+
+    fn from_ssa(ssa: Ssa, options: &'a SsaEvaluatorOptions) -> Result<Self, RuntimeError> {
+        check_pass_names(options)?;
+        let builder = SsaBuilder {
+            ssa,
+            options,
+            memory_operations_forbidden: false,
+            last_pass: "SSA generation",
+            passes_run: 0,
+        };
+        if options.print_ssa_passes {
+            println!("Initial SSA:\n{}", builder.ssa);
+        }
+        builder.dump("initial");
+        builder.validate()?;
+        Ok(builder)
     }
 
     fn finish(self) -> Ssa {
         self.ssa
     }
 
-    /// Runs the given SSA pass and prints the SSA afterward if `print_ssa_passes` is true.
-    fn run_pass(mut self, pass: fn(Ssa) -> Ssa, msg: &str) -> Self {
-        self.ssa = pass(self.ssa);
-        self.print(msg)
+    /// Runs the given SSA passes in order, skipping those which are disabled in the options.
+    fn run_passes(mut self, passes: &[SsaPass]) -> Result<Self, RuntimeError> {
+        for pass in passes {
+            if !self.options.skipped_ssa_passes.iter().any(|name| name == pass.name) {
+                self = self.run_pass(pass)?;
+            }
+            if pass.removes_memory_operations {
+                self.memory_operations_forbidden = true;
+                self.validate()?;
+            }
+        }
+        Ok(self)
     }
 
-    /// The same as `run_pass` but for passes that may fail
-    fn try_run_pass(
-        mut self,
-        pass: fn(Ssa) -> Result<Ssa, RuntimeError>,
-        msg: &str,
-    ) -> Result<Self, RuntimeError> {
-        self.ssa = pass(self.ssa)?;
-        Ok(self.print(msg))
+    /// Runs the given SSA pass, printing, dumping and validating the SSA afterward as requested
+    /// by the options.
+    fn run_pass(mut self, pass: &SsaPass) -> Result<Self, RuntimeError> {
+        self.ssa = (pass.run)(self.ssa)?;
+        self.last_pass = pass.name;
+        self.passes_run += 1;
+
+        let options = self.options;
+        if options.print_ssa_passes
+            || options.print_ssa_pass_names.iter().any(|name| name == pass.name)
+        {
+            println!("{}\n{}", pass.msg, self.ssa);
+        }
+        self.dump(pass.name);
+        self.validate()?;
+        Ok(self)
     }
 
     fn to_brillig(&self, print_brillig_trace: bool) -> Brillig {
         self.ssa.to_brillig(print_brillig_trace)
     }
 
-    fn print(self, msg: &str) -> Self {
-        if self.print_ssa_passes {
-            println!("{msg}\n{}", self.ssa);
+    /// Writes the SSA of each function to `<dump_ssa_dir>/<main>/<index>_<pass>/<function>.ssa`
+    /// if `dump_ssa_dir` is set. Failing to do so is reported but does not stop compilation.
+    fn dump(&self, pass_name: &str) {
+        let Some(dump_ssa_dir) = &self.options.dump_ssa_dir else {
+            return;
+        };
+        let pass_dir = dump_ssa_dir
+            .join(self.ssa.main().name())
+            .join(format!("{:02}_{pass_name}", self.passes_run));
+        let result = std::fs::create_dir_all(&pass_dir).and_then(|_| {
+            self.ssa.functions.values().try_for_each(|function| {
+                let file_name = format!("{}_{}.ssa", function.name(), function.id());
+                std::fs::write(pass_dir.join(file_name), function.to_string())
+            })
+        });
+        if let Err(error) = result {
+            eprintln!("Could not write SSA to {}: {error}", pass_dir.display());
         }
-        self
+    }
+
+    /// Checks the SSA for broken invariants if `validate_ssa` is set.
+    fn validate(&self) -> Result<(), RuntimeError> {
+        if !self.options.validate_ssa {
+            return Ok(());
+        }
+        self.ssa.validate(self.memory_operations_forbidden).map_err(|error| {
+            InternalError::InvalidSsa {
+                pass: self.last_pass.to_owned(),
+                message: error.message,
+                call_stack: error.call_stack,
+            }
+            .into()
+        })
+    }
+}
+
+/// Rejects options referring to passes which do not exist, as they would otherwise be ignored.
+fn check_pass_names(options: &SsaEvaluatorOptions) -> Result<(), RuntimeError> {
+    let pass_names = ssa_pass_names();
+    let selected_passes = options.skipped_ssa_passes.iter().chain(&options.print_ssa_pass_names);
+    match selected_passes.find(|name| !pass_names.contains(&name.as_str())) {
+        Some(name) => Err(InternalError::General {
+            message: format!("Unknown SSA pass `{name}`"),
+            call_stack: Default::default(),
+        }
+        .into()),
+        None => Ok(()),
     }
 }
 
@@ -190,3 +439,94 @@ fn tree_to_set(input: &BTreeMap<String, Vec<Range<Witness>>>) -> BTreeSet<Witnes
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::errors::{InternalError, RuntimeError};
+
+    use super::{optimization_passes, ssa_gen::Ssa, SsaBuilder, SsaEvaluatorOptions};
+
+    const SRC: &str = "acir fn main f0 {
+  b0(v0: Field):
+    v1 = allocate
+    store v0 at v1
+    v2 = load v1 -> Field
+    v4 = call f1(v2) -> Field
+    return v4
+}
+brillig fn foo f1 {
+  b0(v0: Field):
+    return v0
+}";
+
+    fn run_optimization_passes(options: &SsaEvaluatorOptions) -> Result<String, RuntimeError> {
+        let ssa: Ssa = SRC.parse().unwrap();
+        let builder = SsaBuilder::from_ssa(ssa, options)?.run_passes(&optimization_passes())?;
+        Ok(builder.finish().to_string())
+    }
+
+    /// Returns the names of the entries of `dir`.
+    fn dir_entries(dir: &std::path::Path) -> BTreeSet<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn skipped_passes_are_not_run() {
+        let ssa = run_optimization_passes(&SsaEvaluatorOptions::default()).unwrap();
+        assert!(!ssa.contains("load"));
+
+        let dump_ssa_dir = tempfile::tempdir().unwrap();
+        let options = SsaEvaluatorOptions {
+            skipped_ssa_passes: vec!["mem2reg".to_string()],
+            dump_ssa_dir: Some(dump_ssa_dir.path().to_path_buf()),
+            ..SsaEvaluatorOptions::default()
+        };
+        let ssa = run_optimization_passes(&options).unwrap();
+        assert!(ssa.contains("load"));
+
+        let pass_dirs = dir_entries(&dump_ssa_dir.path().join("main"));
+        assert!(!pass_dirs.is_empty());
+        assert!(pass_dirs.iter().all(|pass_dir| !pass_dir.ends_with("_mem2reg")));
+    }
+
+    #[test]
+    fn unknown_passes_are_rejected() {
+        let options = SsaEvaluatorOptions {
+            skipped_ssa_passes: vec!["not_a_pass".to_string()],
+            ..SsaEvaluatorOptions::default()
+        };
+        let error = run_optimization_passes(&options).unwrap_err();
+        let RuntimeError::InternalError(InternalError::General { message, .. }) = error else {
+            panic!("expected an unknown SSA pass to be reported, got {error:?}");
+        };
+        assert_eq!(message, "Unknown SSA pass `not_a_pass`");
+    }
+
+    #[test]
+    fn ssa_is_dumped_after_each_pass_for_each_function() {
+        let dump_ssa_dir = tempfile::tempdir().unwrap();
+        let options = SsaEvaluatorOptions {
+            dump_ssa_dir: Some(dump_ssa_dir.path().to_path_buf()),
+            ..SsaEvaluatorOptions::default()
+        };
+        run_optimization_passes(&options).unwrap();
+
+        let main_dir = dump_ssa_dir.path().join("main");
+        let pass_dirs = dir_entries(&main_dir);
+        let mut expected_pass_dirs = BTreeSet::from(["00_initial".to_string()]);
+        for (index, pass) in optimization_passes().iter().enumerate() {
+            expected_pass_dirs.insert(format!("{:02}_{}", index + 1, pass.name));
+        }
+        assert_eq!(pass_dirs, expected_pass_dirs);
+
+        let expected_files = BTreeSet::from(["main_f0.ssa".to_string(), "foo_f1.ssa".to_string()]);
+        for pass_dir in pass_dirs {
+            assert_eq!(dir_entries(&main_dir.join(pass_dir)), expected_files);
+        }
+    }
+}
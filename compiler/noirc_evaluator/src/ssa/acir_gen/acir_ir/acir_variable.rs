@@ -22,6 +22,7 @@ use acvm::{
 use acvm::{BlackBoxFunctionSolver, BlackBoxResolutionError};
 use fxhash::FxHashMap as HashMap;
 use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::debug_info::DebugValueSource;
 use num_bigint::BigUint;
 use std::ops::RangeInclusive;
 use std::{borrow::Cow, hash::Hash};
@@ -131,6 +132,20 @@ impl AcirContext {
             .collect()
     }
 
+    /// Records that the source code variable `var_id` is held in `values`, so that the debugger
+    /// can read it once execution reaches the next opcode.
+    pub(crate) fn record_debug_value(
+        &mut self,
+        var_id: u32,
+        values: Vec<AcirVar>,
+    ) -> Result<(), InternalError> {
+        let sources = try_vecmap(values, |var| {
+            Ok(DebugValueSource::Expression(self.var_to_expression(var)?))
+        })?;
+        self.acir_ir.record_variable_assignment(var_id, sources);
+        Ok(())
+    }
+
     /// Adds a constant to the context and assigns a Variable to represent it
     pub(crate) fn add_constant(&mut self, constant: FieldElement) -> AcirVar {
         let constant_data = AcirVarData::Const(constant);
@@ -1270,6 +1285,43 @@ impl AcirContext {
         Ok(outputs_var)
     }
 
+    /// Calls the ACIR function `id` with `inputs`, returning values for its `outputs`.
+    ///
+    /// Arrays are passed to and returned from the callee as their flattened elements.
+    pub(crate) fn call_acir_function(
+        &mut self,
+        id: u32,
+        predicate: AcirVar,
+        inputs: Vec<AcirValue>,
+        outputs: Vec<AcirType>,
+    ) -> Result<Vec<AcirValue>, InternalError> {
+        let mut input_expressions = Vec::new();
+        for input in inputs {
+            self.brillig_array_input(&mut input_expressions, input)?;
+        }
+        let input_witnesses =
+            vecmap(input_expressions, |expression| self.acir_ir.get_or_create_witness(&expression));
+
+        let mut output_witnesses = Vec::new();
+        let outputs_var = vecmap(outputs, |output| match output {
+            AcirType::NumericType(_) => {
+                let witness_index = self.acir_ir.next_witness_index();
+                output_witnesses.push(witness_index);
+                let var = self.add_data(AcirVarData::Witness(witness_index));
+                AcirValue::Var(var, output.clone())
+            }
+            AcirType::Array(element_types, size) => {
+                let (acir_value, mut witnesses) = self.brillig_array_output(&element_types, size);
+                output_witnesses.append(&mut witnesses);
+                acir_value
+            }
+        });
+        let predicate = self.var_to_expression(predicate)?;
+        self.acir_ir.call(id, input_witnesses, output_witnesses, Some(predicate));
+
+        Ok(outputs_var)
+    }
+
     fn brillig_array_input(
         &mut self,
         var_expressions: &mut Vec<Expression>,
@@ -22,6 +22,7 @@ use acvm::{
     FieldElement,
 };
 use iter_extended::vecmap;
+use noirc_errors::debug_info::{DebugValueSource, DebugVarAssignment};
 use num_bigint::BigUint;
 use std::ops::Range;
 
@@ -55,6 +56,10 @@ pub(crate) struct GeneratedAcir {
     /// Correspondence between an opcode index and the error message associated with it.
     pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
 
+    /// The source code variables which have been assigned by the time execution reaches an opcode,
+    /// along with where the debugger can read their new values from.
+    pub(crate) variable_assignments: BTreeMap<OpcodeLocation, Vec<DebugVarAssignment>>,
+
     pub(crate) warnings: Vec<SsaReport>,
 }
 
@@ -72,6 +77,21 @@ impl GeneratedAcir {
         }
     }
 
+    /// Records that the variable `var_id` can be read from `sources` once execution reaches
+    /// the next opcode.
+    pub(crate) fn record_variable_assignment(
+        &mut self,
+        var_id: u32,
+        sources: Vec<DebugValueSource>,
+    ) {
+        let mut call_sites: Vec<_> = self.call_stack.iter().copied().collect();
+        call_sites.pop();
+        self.variable_assignments
+            .entry(OpcodeLocation::Acir(self.opcodes.len()))
+            .or_default()
+            .push(DebugVarAssignment { var_id, call_sites, sources });
+    }
+
     pub(crate) fn take_opcodes(&mut self) -> Vec<AcirOpcode> {
         std::mem::take(&mut self.opcodes)
     }
@@ -522,6 +542,24 @@ impl GeneratedAcir {
                 message,
             );
         }
+        for (brillig_index, assignments) in generated_brillig.variable_assignments {
+            self.variable_assignments.insert(
+                OpcodeLocation::Brillig { acir_index: self.opcodes.len() - 1, brillig_index },
+                assignments,
+            );
+        }
+    }
+
+    /// Calls the ACIR function `id`, binding `inputs` to its arguments and `outputs` to its
+    /// return values.
+    pub(crate) fn call(
+        &mut self,
+        id: u32,
+        inputs: Vec<Witness>,
+        outputs: Vec<Witness>,
+        predicate: Option<Expression>,
+    ) {
+        self.push_opcode(AcirOpcode::Call { id, inputs, outputs, predicate });
     }
 
     /// Generate gates and control bits witnesses which ensure that out_expr is a permutation of in_expr
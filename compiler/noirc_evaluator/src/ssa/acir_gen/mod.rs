@@ -10,7 +10,7 @@ use super::ir::dfg::CallStack;
 use super::{
     ir::{
         dfg::DataFlowGraph,
-        function::{Function, RuntimeType},
+        function::{Function, FunctionId, RuntimeType},
         instruction::{
             Binary, BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
         },
@@ -90,6 +90,9 @@ struct Context {
     /// Maps SSA array values to their slice size and any nested slices internal to the parent slice.
     /// This enables us to maintain the slice structure of a slice when performing an array get.
     slice_sizes: HashMap<Id<Value>, Vec<usize>>,
+
+    /// Maps each `#[fold]` function to the id by which `Opcode::Call` refers to it.
+    acir_function_ids: HashMap<FunctionId, u32>,
 }
 
 #[derive(Clone)]
@@ -138,42 +141,84 @@ impl AcirValue {
             AcirValue::DynamicArray(_) => unimplemented!("Cannot flatten a dynamic array"),
         }
     }
+
+    /// Flattens the value as [`AcirValue::flatten`] does, returning `None` if it contains a
+    /// dynamic array.
+    fn try_flatten(self) -> Option<Vec<(AcirVar, AcirType)>> {
+        match self {
+            AcirValue::Var(var, typ) => Some(vec![(var, typ)]),
+            AcirValue::Array(array) => {
+                let mut flattened = Vec::new();
+                for value in array {
+                    flattened.extend(value.try_flatten()?);
+                }
+                Some(flattened)
+            }
+            AcirValue::DynamicArray(_) => None,
+        }
+    }
 }
 
 impl Ssa {
+    /// Converts the SSA into ACIR, returning the ACIR for `main` along with the ACIR of each
+    /// `#[fold]` function it may call. `Opcode::Call` refers to these by their index in the
+    /// returned vector.
     pub(crate) fn into_acir(
         self,
         brillig: Brillig,
         abi_distinctness: Distinctness,
         last_array_uses: &HashMap<ValueId, InstructionId>,
-    ) -> Result<GeneratedAcir, RuntimeError> {
-        let context = Context::new();
-        let mut generated_acir = context.convert_ssa(self, brillig, last_array_uses)?;
+    ) -> Result<(GeneratedAcir, Vec<GeneratedAcir>), RuntimeError> {
+        let acir_function_ids: HashMap<FunctionId, u32> = self
+            .functions
+            .values()
+            .filter(|function| function.id() != self.main_id && function.is_fold())
+            .enumerate()
+            .map(|(index, function)| (function.id(), index as u32))
+            .collect();
 
-        match abi_distinctness {
-            Distinctness::Distinct => {
-                // Create a witness for each return witness we have
-                // to guarantee that the return witnesses are distinct
-                let distinct_return_witness: Vec<_> = generated_acir
-                    .return_witnesses
-                    .clone()
-                    .into_iter()
-                    .map(|return_witness| {
-                        generated_acir
-                            .create_witness_for_expression(&Expression::from(return_witness))
-                    })
-                    .collect();
+        let context = Context::new(acir_function_ids.clone());
+        let mut generated_acir = context.convert_ssa(&self, &brillig, last_array_uses)?;
 
-                generated_acir.return_witnesses = distinct_return_witness;
-                Ok(generated_acir)
-            }
-            Distinctness::DuplicationAllowed => Ok(generated_acir),
+        match abi_distinctness {
+            Distinctness::Distinct => make_return_witnesses_distinct(&mut generated_acir),
+            Distinctness::DuplicationAllowed => (),
         }
+
+        let mut fold_functions: Vec<_> = acir_function_ids.iter().collect();
+        fold_functions.sort_unstable_by_key(|(_, index)| **index);
+        let functions = try_vecmap(fold_functions, |(function_id, _)| {
+            let context = Context::new(acir_function_ids.clone());
+            let function = &self.functions[function_id];
+            let mut generated_acir =
+                context.convert_acir_main(function, &self, &brillig, last_array_uses)?;
+            // The caller binds the return values of a function to witnesses in order, so
+            // they must not share witnesses with each other or with the arguments.
+            make_return_witnesses_distinct(&mut generated_acir);
+            Ok::<_, RuntimeError>(generated_acir)
+        })?;
+
+        Ok((generated_acir, functions))
     }
 }
 
+/// Creates a witness for each return witness of `generated_acir`
+/// to guarantee that the return witnesses are distinct.
+fn make_return_witnesses_distinct(generated_acir: &mut GeneratedAcir) {
+    let distinct_return_witness: Vec<_> = generated_acir
+        .return_witnesses
+        .clone()
+        .into_iter()
+        .map(|return_witness| {
+            generated_acir.create_witness_for_expression(&Expression::from(return_witness))
+        })
+        .collect();
+
+    generated_acir.return_witnesses = distinct_return_witness;
+}
+
 impl Context {
-    fn new() -> Context {
+    fn new(acir_function_ids: HashMap<FunctionId, u32>) -> Context {
         let mut acir_context = AcirContext::default();
         let current_side_effects_enabled_var = acir_context.add_constant(FieldElement::one());
 
@@ -187,19 +232,20 @@ impl Context {
             internal_mem_block_lengths: HashMap::default(),
             max_block_id: 0,
             slice_sizes: HashMap::default(),
+            acir_function_ids,
         }
     }
 
     /// Converts SSA into ACIR
     fn convert_ssa(
         self,
-        ssa: Ssa,
-        brillig: Brillig,
+        ssa: &Ssa,
+        brillig: &Brillig,
         last_array_uses: &HashMap<ValueId, InstructionId>,
     ) -> Result<GeneratedAcir, RuntimeError> {
         let main_func = ssa.main();
         match main_func.runtime() {
-            RuntimeType::Acir => self.convert_acir_main(main_func, &ssa, brillig, last_array_uses),
+            RuntimeType::Acir => self.convert_acir_main(main_func, ssa, brillig, last_array_uses),
             RuntimeType::Brillig => self.convert_brillig_main(main_func, brillig),
         }
     }
@@ -208,7 +254,7 @@ impl Context {
         mut self,
         main_func: &Function,
         ssa: &Ssa,
-        brillig: Brillig,
+        brillig: &Brillig,
         last_array_uses: &HashMap<ValueId, InstructionId>,
     ) -> Result<GeneratedAcir, RuntimeError> {
         let dfg = &main_func.dfg;
@@ -220,7 +266,7 @@ impl Context {
                 *instruction_id,
                 dfg,
                 ssa,
-                &brillig,
+                brillig,
                 last_array_uses,
             )?);
         }
@@ -232,7 +278,7 @@ impl Context {
     fn convert_brillig_main(
         mut self,
         main_func: &Function,
-        brillig: Brillig,
+        brillig: &Brillig,
     ) -> Result<GeneratedAcir, RuntimeError> {
         let dfg = &main_func.dfg;
 
@@ -245,7 +291,7 @@ impl Context {
         let outputs: Vec<AcirType> =
             vecmap(main_func.returns(), |result_id| dfg.type_of_value(*result_id).into());
 
-        let code = self.gen_brillig_for(main_func, &brillig)?;
+        let code = self.gen_brillig_for(main_func, brillig)?;
 
         let output_values = self.acir_context.brillig(
             self.current_side_effects_enabled_var,
@@ -461,30 +507,50 @@ impl Context {
                     Value::Function(id) => {
                         let func = &ssa.functions[id];
                         match func.runtime() {
-                            RuntimeType::Acir => unimplemented!(
-                                "expected an intrinsic/brillig call, but found {func:?}. All ACIR methods should be inlined"
-                            ),
+                            RuntimeType::Acir => {
+                                let Some(acir_function_id) = self.acir_function_ids.get(id).copied() else {
+                                    unimplemented!(
+                                        "expected an intrinsic/brillig call, but found {func:?}. All non-fold ACIR methods should be inlined"
+                                    )
+                                };
+                                let inputs = vecmap(arguments, |arg| self.convert_value(*arg, dfg));
+
+                                let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| {
+                                    dfg.type_of_value(*result_id).into()
+                                });
+
+                                let output_values = self.acir_context.call_acir_function(
+                                    acir_function_id,
+                                    self.current_side_effects_enabled_var,
+                                    inputs,
+                                    outputs,
+                                )?;
+
+                                // Compiler sanity check
+                                assert_eq!(result_ids.len(), output_values.len(), "ICE: The number of ACIR call output values should match the result ids in SSA");
+
+                                self.define_call_results(result_ids, output_values, dfg)?;
+                            }
                             RuntimeType::Brillig => {
                                 let inputs = vecmap(arguments, |arg| self.convert_value(*arg, dfg));
 
                                 let code = self.gen_brillig_for(func, brillig)?;
 
-                                let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| dfg.type_of_value(*result_id).into());
+                                let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| {
+                                    dfg.type_of_value(*result_id).into()
+                                });
 
-                                let output_values = self.acir_context.brillig(self.current_side_effects_enabled_var, code, inputs, outputs)?;
+                                let output_values = self.acir_context.brillig(
+                                    self.current_side_effects_enabled_var,
+                                    code,
+                                    inputs,
+                                    outputs,
+                                )?;
 
                                 // Compiler sanity check
                                 assert_eq!(result_ids.len(), output_values.len(), "ICE: The number of Brillig output values should match the result ids in SSA");
 
-                                for result in result_ids.iter().zip(output_values) {
-                                    if let AcirValue::Array(_) = &result.1 {
-                                        let array_id = dfg.resolve(*result.0);
-                                        let block_id = self.block_id(&array_id);
-                                        let array_typ = dfg.type_of_value(array_id);
-                                        self.initialize_array(block_id, array_typ.flattened_size(), Some(result.1.clone()))?;
-                                    }
-                                    self.ssa_values.insert(*result.0, result.1);
-                                }
+                                self.define_call_results(result_ids, output_values, dfg)?;
                             }
                         }
                     }
@@ -571,11 +637,43 @@ impl Context {
                     assert_message.clone(),
                 )?;
             }
+            Instruction::IncrementRc { .. } | Instruction::DecrementRc { .. } => {
+                // Only Brillig mutates arrays in place, so ACIR has no reference counts to track.
+            }
+            Instruction::DebugValue { variable, values } => {
+                if let Some(vars) = self.debug_value_vars(values, dfg) {
+                    self.acir_context.record_debug_value(*variable, vars)?;
+                }
+            }
         }
         self.acir_context.set_call_stack(CallStack::new());
         Ok(warnings)
     }
 
+    /// Binds the values returned by a Brillig or ACIR call to the call's `result_ids`,
+    /// initializing the memory of any returned arrays so that they may be read with a dynamic index.
+    fn define_call_results(
+        &mut self,
+        result_ids: &[ValueId],
+        output_values: Vec<AcirValue>,
+        dfg: &DataFlowGraph,
+    ) -> Result<(), RuntimeError> {
+        for (result_id, output_value) in result_ids.iter().zip(output_values) {
+            if let AcirValue::Array(_) = &output_value {
+                let array_id = dfg.resolve(*result_id);
+                let block_id = self.block_id(&array_id);
+                let array_typ = dfg.type_of_value(array_id);
+                self.initialize_array(
+                    block_id,
+                    array_typ.flattened_size(),
+                    Some(output_value.clone()),
+                )?;
+            }
+            self.ssa_values.insert(*result_id, output_value);
+        }
+        Ok(())
+    }
+
     fn gen_brillig_for(
         &self,
         func: &Function,
@@ -1459,6 +1557,33 @@ impl Context {
         acir_value
     }
 
+    /// Flattens the values of an [`Instruction::DebugValue`] into the variables holding them.
+    ///
+    /// Returns `None` if any of the values has not been converted, or if it is held in a dynamic
+    /// array which could only be read by generating additional opcodes.
+    fn debug_value_vars(
+        &mut self,
+        values: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) -> Option<Vec<AcirVar>> {
+        let mut vars = Vec::new();
+        for value_id in values {
+            let value_id = dfg.resolve(*value_id);
+            let is_converted = match &dfg[value_id] {
+                Value::Instruction { .. } | Value::Param { .. } => {
+                    self.ssa_values.contains_key(&value_id)
+                }
+                _ => true,
+            };
+            if !is_converted {
+                return None;
+            }
+            let flattened = self.convert_value(value_id, dfg).try_flatten()?;
+            vars.extend(flattened.into_iter().map(|(var, _)| var));
+        }
+        Some(vars)
+    }
+
     fn convert_numeric_value(
         &mut self,
         value_id: ValueId,
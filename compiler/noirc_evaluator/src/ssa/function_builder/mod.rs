@@ -401,6 +401,27 @@ impl FunctionBuilder {
         self.insert_instruction(Instruction::ArraySet { array, index, value }, None).first()
     }
 
+    /// Insert an instruction to increment the reference count of an array or slice.
+    /// This is a no-op for values of any other type.
+    pub(crate) fn increment_array_reference_count(&mut self, value: ValueId) {
+        if matches!(self.type_of_value(value), Type::Array(..) | Type::Slice(..)) {
+            self.insert_instruction(Instruction::IncrementRc { value }, None);
+        }
+    }
+
+    /// Insert an instruction to decrement the reference count of an array or slice.
+    /// This is a no-op for values of any other type.
+    pub(crate) fn decrement_array_reference_count(&mut self, value: ValueId) {
+        if matches!(self.type_of_value(value), Type::Array(..) | Type::Slice(..)) {
+            self.insert_instruction(Instruction::DecrementRc { value }, None);
+        }
+    }
+
+    /// Insert an instruction recording that the source code variable `variable` now holds `values`.
+    pub(crate) fn insert_debug_value(&mut self, variable: u32, values: Vec<ValueId>) {
+        self.insert_instruction(Instruction::DebugValue { variable, values }, None);
+    }
+
     /// Terminates the current block with the given terminator instruction
     fn terminate_block_with(&mut self, terminator: TerminatorInstruction) {
         self.current_function.dfg.set_block_terminator(self.current_block, terminator);
@@ -23,7 +23,7 @@ pub(crate) enum RuntimeType {
 /// All functions outside of the current function are seen as external.
 /// To reference external functions its FunctionId can be used but this
 /// cannot be checked for correctness until inlining is performed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Function {
     /// The first basic block in the function
     entry_block: BasicBlockId,
@@ -35,6 +35,10 @@ pub(crate) struct Function {
 
     runtime: RuntimeType,
 
+    /// True if this function is compiled into its own circuit and called rather than inlined.
+    /// Only applies to ACIR functions.
+    fold: bool,
+
     /// The DataFlowGraph holds the majority of data pertaining to the function
     /// including its blocks, instructions, and values.
     pub(crate) dfg: DataFlowGraph,
@@ -47,7 +51,12 @@ impl Function {
     pub(crate) fn new(name: String, id: FunctionId) -> Self {
         let mut dfg = DataFlowGraph::default();
         let entry_block = dfg.make_block();
-        Self { name, id, entry_block, dfg, runtime: RuntimeType::Acir }
+        Self { name, id, entry_block, dfg, runtime: RuntimeType::Acir, fold: false }
+    }
+
+    /// Creates a copy of this function with the given id.
+    pub(crate) fn clone_with_id(&self, id: FunctionId) -> Self {
+        Self { id, ..self.clone() }
     }
 
     /// The name of the function.
@@ -71,6 +80,17 @@ impl Function {
         self.runtime = runtime;
     }
 
+    /// True if this is an ACIR function which is kept out of line and called through
+    /// its own circuit rather than being inlined into its callers.
+    pub(crate) fn is_fold(&self) -> bool {
+        self.fold && self.runtime == RuntimeType::Acir
+    }
+
+    /// Marks whether the function should be kept out of line, see [`Function::is_fold`].
+    pub(crate) fn set_fold(&mut self, fold: bool) {
+        self.fold = fold;
+    }
+
     /// Retrieves the entry block of a function.
     ///
     /// A function's entry block contains the instructions
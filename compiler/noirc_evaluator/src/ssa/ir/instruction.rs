@@ -186,6 +186,27 @@ pub(crate) enum Instruction {
     /// Creates a new array with the new value at the given index. All other elements are identical
     /// to those in the given array. This will not modify the original array.
     ArraySet { array: ValueId, index: ValueId, value: ValueId },
+
+    /// An instruction to increment the reference count of a value.
+    ///
+    /// This currently only has an effect in Brillig code where array sharing and copy on write is
+    /// implemented via reference counting. In ACIR code this is done with im::Vector and these
+    /// IncrementRc instructions are ignored.
+    IncrementRc { value: ValueId },
+
+    /// An instruction to decrement the reference count of a value.
+    ///
+    /// This currently only has an effect in Brillig code where array sharing and copy on write is
+    /// implemented via reference counting. In ACIR code this is done with im::Vector and these
+    /// DecrementRc instructions are ignored.
+    DecrementRc { value: ValueId },
+
+    /// Records that the source code variable `variable` holds `values` from this point on, so
+    /// that the debugger can find where to read the variable from.
+    ///
+    /// These instructions do not generate any code and do not count as a use of their values:
+    /// they are removed if the instructions defining their values are removed.
+    DebugValue { variable: u32, values: Vec<ValueId> },
 }
 
 impl Instruction {
@@ -207,6 +228,9 @@ impl Instruction {
             Instruction::Constrain(..)
             | Instruction::Store { .. }
             | Instruction::EnableSideEffects { .. }
+            | Instruction::IncrementRc { .. }
+            | Instruction::DecrementRc { .. }
+            | Instruction::DebugValue { .. }
             | Instruction::RangeCheck { .. } => InstructionResultType::None,
             Instruction::Load { .. } | Instruction::ArrayGet { .. } | Instruction::Call { .. } => {
                 InstructionResultType::Unknown
@@ -239,6 +263,9 @@ impl Instruction {
             | Allocate
             | Load { .. }
             | Store { .. }
+            | IncrementRc { .. }
+            | DecrementRc { .. }
+            | DebugValue { .. }
             | RangeCheck { .. } => false,
 
             Call { func, .. } => match dfg[*func] {
@@ -270,7 +297,13 @@ impl Instruction {
             | ArrayGet { .. }
             | ArraySet { .. } => false,
 
-            Constrain(..) | Store { .. } | EnableSideEffects { .. } | RangeCheck { .. } => true,
+            Constrain(..)
+            | Store { .. }
+            | EnableSideEffects { .. }
+            | IncrementRc { .. }
+            | DecrementRc { .. }
+            | DebugValue { .. }
+            | RangeCheck { .. } => true,
 
             // Some `Intrinsic`s have side effects so we must check what kind of `Call` this is.
             Call { func, .. } => match dfg[*func] {
@@ -289,6 +322,39 @@ impl Instruction {
         }
     }
 
+    /// If true the instruction will depend on the `enable_side_effects` predicate during
+    /// acir-gen, so two copies of it executed under different predicates may differ.
+    pub(crate) fn requires_acir_gen_predicate(&self, dfg: &DataFlowGraph) -> bool {
+        use Instruction::*;
+        match self {
+            Binary(binary) => {
+                matches!(binary.operator, BinaryOp::Div | BinaryOp::Mod | BinaryOp::Lt)
+            }
+
+            // Out of bounds indices are replaced when the predicate is false.
+            ArrayGet { .. } | ArraySet { .. } => true,
+
+            Call { func, .. } => match dfg[*func] {
+                Value::Intrinsic(intrinsic) => matches!(intrinsic, Intrinsic::Sort),
+                // Calls to other functions, both ACIR and Brillig, are predicated.
+                _ => true,
+            },
+
+            Cast(_, _)
+            | Not(_)
+            | Truncate { .. }
+            | Constrain(..)
+            | Allocate
+            | Load { .. }
+            | Store { .. }
+            | EnableSideEffects { .. }
+            | IncrementRc { .. }
+            | DecrementRc { .. }
+            | DebugValue { .. }
+            | RangeCheck { .. } => false,
+        }
+    }
+
     /// Maps each ValueId inside this instruction to a new ValueId, returning the new instruction.
     /// Note that the returned instruction is fresh and will not have an assigned InstructionId
     /// until it is manually inserted in a DataFlowGraph later.
@@ -327,6 +393,12 @@ impl Instruction {
             Instruction::ArraySet { array, index, value } => {
                 Instruction::ArraySet { array: f(*array), index: f(*index), value: f(*value) }
             }
+            Instruction::IncrementRc { value } => Instruction::IncrementRc { value: f(*value) },
+            Instruction::DecrementRc { value } => Instruction::DecrementRc { value: f(*value) },
+            Instruction::DebugValue { variable, values } => Instruction::DebugValue {
+                variable: *variable,
+                values: vecmap(values.iter().copied(), f),
+            },
             Instruction::RangeCheck { value, max_bit_size, assert_message } => {
                 Instruction::RangeCheck {
                     value: f(*value),
@@ -353,6 +425,8 @@ impl Instruction {
             Instruction::Cast(value, _)
             | Instruction::Not(value)
             | Instruction::Truncate { value, .. }
+            | Instruction::IncrementRc { value }
+            | Instruction::DecrementRc { value }
             | Instruction::Load { address: value } => {
                 f(*value);
             }
@@ -381,6 +455,11 @@ impl Instruction {
             Instruction::RangeCheck { value, .. } => {
                 f(*value);
             }
+            Instruction::DebugValue { values, .. } => {
+                for value in values {
+                    f(*value);
+                }
+            }
         }
     }
 
@@ -478,6 +557,9 @@ impl Instruction {
             Instruction::Allocate { .. } => None,
             Instruction::Load { .. } => None,
             Instruction::Store { .. } => None,
+            Instruction::IncrementRc { .. } => None,
+            Instruction::DecrementRc { .. } => None,
+            Instruction::DebugValue { .. } => None,
             Instruction::RangeCheck { value, max_bit_size, .. } => {
                 if let Some(numeric_constant) = dfg.get_numeric_constant(*value) {
                     if numeric_constant.num_bits() < *max_bit_size {
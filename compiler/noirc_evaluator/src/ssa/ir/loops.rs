@@ -0,0 +1,149 @@
+//! This module finds the natural loops of a function from its control flow graph
+//! and dominator tree. It is shared by loop unrolling, which removes loops from
+//! ACIR functions, and loop-invariant code motion, which optimizes the loops that
+//! Brillig functions keep.
+use std::collections::HashSet;
+
+use super::{
+    basic_block::BasicBlockId, cfg::ControlFlowGraph, dom::DominatorTree, function::Function,
+    post_order::PostOrder,
+};
+
+pub(crate) struct Loop {
+    /// The header block of a loop is the block which dominates all the
+    /// other blocks in the loop.
+    pub(crate) header: BasicBlockId,
+
+    /// The start of the back_edge n -> d is the block n at the end of
+    /// the loop that jumps back to the header block d which restarts the loop.
+    pub(crate) back_edge_start: BasicBlockId,
+
+    /// All the blocks contained within the loop, including `header` and `back_edge_start`.
+    pub(crate) blocks: HashSet<BasicBlockId>,
+}
+
+/// The loops of a function along with the analyses used to find them.
+pub(crate) struct LoopAnalysis {
+    /// Every loop in the function, sorted by the number of blocks they contain.
+    /// Inner loops of a nested loop therefore always come before their outer loops.
+    pub(crate) loops: Vec<Loop>,
+    pub(crate) cfg: ControlFlowGraph,
+    pub(crate) dom_tree: DominatorTree,
+}
+
+impl LoopAnalysis {
+    /// Find each loop in the function by finding a node that dominates any predecessor node.
+    /// The edge where this happens will be the back-edge of the loop.
+    pub(crate) fn with_function(function: &Function) -> Self {
+        let cfg = ControlFlowGraph::with_function(function);
+        let post_order = PostOrder::with_function(function);
+        let mut dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+        let mut loops = vec![];
+
+        for (block, _) in function.dfg.basic_blocks_iter() {
+            // These reachable checks wouldn't be needed if we only iterated over reachable blocks
+            if dom_tree.is_reachable(block) {
+                for predecessor in cfg.predecessors(block) {
+                    if dom_tree.is_reachable(predecessor) && dom_tree.dominates(block, predecessor)
+                    {
+                        // predecessor -> block is the back-edge of a loop
+                        loops.push(Loop::find_blocks_in_loop(block, predecessor, &cfg));
+                    }
+                }
+            }
+        }
+
+        loops.sort_by_key(|loop_| loop_.blocks.len());
+        LoopAnalysis { loops, cfg, dom_tree }
+    }
+}
+
+impl Loop {
+    /// Return each block that is in a loop starting in the given header block.
+    /// Expects back_edge_start -> header to be the back edge of the loop.
+    fn find_blocks_in_loop(
+        header: BasicBlockId,
+        back_edge_start: BasicBlockId,
+        cfg: &ControlFlowGraph,
+    ) -> Self {
+        let mut blocks = HashSet::new();
+        blocks.insert(header);
+
+        let mut insert = |block, stack: &mut Vec<BasicBlockId>| {
+            if !blocks.contains(&block) {
+                blocks.insert(block);
+                stack.push(block);
+            }
+        };
+
+        // Starting from the back edge of the loop, each predecessor of this block until
+        // the header is within the loop.
+        let mut stack = vec![];
+        insert(back_edge_start, &mut stack);
+
+        while let Some(block) = stack.pop() {
+            for predecessor in cfg.predecessors(block) {
+                insert(predecessor, &mut stack);
+            }
+        }
+
+        Loop { header, back_edge_start, blocks }
+    }
+
+    /// The loop pre-header is the block that comes before the loop begins. Generally a header
+    /// block is expected to have 2 predecessors: the pre-header and the final block of the loop
+    /// which jumps back to the beginning.
+    ///
+    /// Returns None if the header has any other number of predecessors from outside the loop.
+    pub(crate) fn pre_header(&self, cfg: &ControlFlowGraph) -> Option<BasicBlockId> {
+        let mut pre_headers =
+            cfg.predecessors(self.header).filter(|predecessor| !self.blocks.contains(predecessor));
+
+        let pre_header = pre_headers.next()?;
+        pre_headers.next().is_none().then_some(pre_header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::ssa_gen::Ssa;
+
+    use super::LoopAnalysis;
+
+    #[test]
+    fn finds_nested_loops_inner_first() {
+        let src = "brillig fn main f0 {
+  b0(v0: u32):
+    jmp b1(u32 0)
+  b1(v1: u32):
+    v2 = lt v1, v0
+    jmpif v2 then: b2, else: b3
+  b2():
+    jmp b4(u32 0)
+  b3():
+    return
+  b4(v3: u32):
+    v4 = lt v3, v0
+    jmpif v4 then: b5, else: b6
+  b5():
+    v5 = add v3, u32 1
+    jmp b4(v5)
+  b6():
+    v6 = add v1, u32 1
+    jmp b1(v6)
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let main = ssa.main();
+        let analysis = LoopAnalysis::with_function(main);
+        assert_eq!(analysis.loops.len(), 2);
+
+        let (inner, outer) = (&analysis.loops[0], &analysis.loops[1]);
+        assert_eq!(inner.blocks.len(), 2);
+        assert_eq!(outer.blocks.len(), 5);
+        assert!(inner.blocks.iter().all(|block| outer.blocks.contains(block)));
+        assert!(outer.blocks.contains(&inner.pre_header(&analysis.cfg).unwrap()));
+        assert_eq!(outer.pre_header(&analysis.cfg), Some(main.entry_block()));
+    }
+}
@@ -8,6 +8,7 @@ use iter_extended::vecmap;
 
 use super::{
     basic_block::BasicBlockId,
+    dfg::CallStack,
     function::Function,
     instruction::{Instruction, InstructionId, TerminatorInstruction},
     value::ValueId,
@@ -15,7 +16,8 @@ use super::{
 
 /// Helper function for Function's Display impl to pretty-print the function with the given formatter.
 pub(crate) fn display_function(function: &Function, f: &mut Formatter) -> Result {
-    writeln!(f, "{} fn {} {} {{", function.runtime(), function.name(), function.id())?;
+    let fold = if function.is_fold() { "(fold)" } else { "" };
+    writeln!(f, "{}{fold} fn {} {} {{", function.runtime(), function.name(), function.id())?;
     display_block_with_successors(function, function.entry_block(), &mut HashSet::new(), f)?;
     write!(f, "}}")
 }
@@ -68,13 +70,12 @@ fn value(function: &Function, id: ValueId) -> String {
         }
         Value::Function(id) => id.to_string(),
         Value::Intrinsic(intrinsic) => intrinsic.to_string(),
-        Value::Array { array, .. } => {
+        Value::Array { array, typ } => {
             let elements = vecmap(array, |element| value(function, *element));
-            format!("[{}]", elements.join(", "))
-        }
-        Value::Param { .. } | Value::Instruction { .. } | Value::ForeignFunction(_) => {
-            id.to_string()
+            format!("[{}] of {typ}", elements.join(", "))
         }
+        Value::ForeignFunction(name) => name.clone(),
+        Value::Param { .. } | Value::Instruction { .. } => id.to_string(),
     }
 }
 
@@ -93,6 +94,29 @@ fn value_list(function: &Function, values: &[ValueId]) -> String {
     vecmap(values, |id| value(function, *id)).join(", ")
 }
 
+/// Display the types of the given values, for instructions whose result types cannot be
+/// inferred from their operands. E.g. ` -> Field, u32`
+fn result_types(function: &Function, results: &[ValueId]) -> String {
+    if results.is_empty() {
+        return String::new();
+    }
+    let types = vecmap(results, |id| function.dfg.type_of_value(*id).to_string());
+    format!(" -> {}", types.join(", "))
+}
+
+/// Display the source locations of an instruction as a trailing comment,
+/// e.g. ` // loc 0:10..25, 1:3..8`, from the outermost to the innermost call.
+fn call_stack(call_stack: &CallStack) -> String {
+    if call_stack.is_empty() {
+        return String::new();
+    }
+    let locations = vecmap(call_stack, |location| {
+        let span = location.span;
+        format!("{}:{}..{}", location.file.as_usize(), span.start(), span.end())
+    });
+    format!(" // loc {}", locations.join(", "))
+}
+
 /// Display a terminator instruction
 pub(crate) fn display_terminator(
     function: &Function,
@@ -100,8 +124,9 @@ pub(crate) fn display_terminator(
     f: &mut Formatter,
 ) -> Result {
     match terminator {
-        Some(TerminatorInstruction::Jmp { destination, arguments, call_stack: _ }) => {
-            writeln!(f, "    jmp {}({})", destination, value_list(function, arguments))
+        Some(TerminatorInstruction::Jmp { destination, arguments, call_stack: stack }) => {
+            let arguments = value_list(function, arguments);
+            writeln!(f, "    jmp {destination}({arguments}){}", call_stack(stack))
         }
         Some(TerminatorInstruction::JmpIf { condition, then_destination, else_destination }) => {
             writeln!(
@@ -112,8 +137,9 @@ pub(crate) fn display_terminator(
                 else_destination
             )
         }
-        Some(TerminatorInstruction::Return { return_values, .. }) => {
-            writeln!(f, "    return {}", value_list(function, return_values))
+        Some(TerminatorInstruction::Return { return_values, call_stack: stack }) => {
+            let return_values = value_list(function, return_values);
+            writeln!(f, "    return {return_values}{}", call_stack(stack))
         }
         None => writeln!(f, "    (no terminator instruction)"),
     }
@@ -137,43 +163,57 @@ pub(crate) fn display_instruction(
 
     match &function.dfg[instruction] {
         Instruction::Binary(binary) => {
-            writeln!(f, "{} {}, {}", binary.operator, show(binary.lhs), show(binary.rhs))
+            write!(f, "{} {}, {}", binary.operator, show(binary.lhs), show(binary.rhs))
         }
-        Instruction::Cast(lhs, typ) => writeln!(f, "cast {} as {typ}", show(*lhs)),
-        Instruction::Not(rhs) => writeln!(f, "not {}", show(*rhs)),
+        Instruction::Cast(lhs, typ) => write!(f, "cast {} as {typ}", show(*lhs)),
+        Instruction::Not(rhs) => write!(f, "not {}", show(*rhs)),
         Instruction::Truncate { value, bit_size, max_bit_size } => {
             let value = show(*value);
-            writeln!(f, "truncate {value} to {bit_size} bits, max_bit_size: {max_bit_size}",)
+            write!(f, "truncate {value} to {bit_size} bits, max_bit_size: {max_bit_size}",)
         }
         Instruction::Constrain(lhs, rhs, message) => match message {
-            Some(message) => writeln!(f, "constrain {} == {} '{message}'", show(*lhs), show(*rhs)),
-            None => writeln!(f, "constrain {} == {}", show(*lhs), show(*rhs)),
+            Some(message) => write!(f, "constrain {} == {} '{message}'", show(*lhs), show(*rhs)),
+            None => write!(f, "constrain {} == {}", show(*lhs), show(*rhs)),
         },
         Instruction::Call { func, arguments } => {
-            writeln!(f, "call {}({})", show(*func), value_list(function, arguments))
+            let arguments = value_list(function, arguments);
+            write!(f, "call {}({arguments}){}", show(*func), result_types(function, results))
+        }
+        Instruction::Allocate => write!(f, "allocate"),
+        Instruction::Load { address } => {
+            write!(f, "load {}{}", show(*address), result_types(function, results))
         }
-        Instruction::Allocate => writeln!(f, "allocate"),
-        Instruction::Load { address } => writeln!(f, "load {}", show(*address)),
         Instruction::Store { address, value } => {
-            writeln!(f, "store {} at {}", show(*value), show(*address))
+            write!(f, "store {} at {}", show(*value), show(*address))
         }
         Instruction::EnableSideEffects { condition } => {
-            writeln!(f, "enable_side_effects {}", show(*condition))
+            write!(f, "enable_side_effects {}", show(*condition))
         }
         Instruction::ArrayGet { array, index } => {
-            writeln!(f, "array_get {}, index {}", show(*array), show(*index))
+            let results = result_types(function, results);
+            write!(f, "array_get {}, index {}{results}", show(*array), show(*index))
         }
         Instruction::ArraySet { array, index, value } => {
-            writeln!(
-                f,
-                "array_set {}, index {}, value {}",
-                show(*array),
-                show(*index),
-                show(*value)
-            )
+            write!(f, "array_set {}, index {}, value {}", show(*array), show(*index), show(*value))
         }
-        Instruction::RangeCheck { value, max_bit_size, .. } => {
-            write!(f, "range_check {} to {} bits", show(*value), *max_bit_size,)
+        Instruction::IncrementRc { value } => write!(f, "inc_rc {}", show(*value)),
+        Instruction::DecrementRc { value } => write!(f, "dec_rc {}", show(*value)),
+        Instruction::DebugValue { variable, values } => {
+            write!(f, "debug_value var {variable}")?;
+            for value in values {
+                write!(f, ", {}", show(*value))?;
+            }
+            Ok(())
         }
-    }
+        Instruction::RangeCheck { value, max_bit_size, assert_message } => {
+            let value = show(*value);
+            match assert_message {
+                Some(message) => {
+                    write!(f, "range_check {value} to {max_bit_size} bits '{message}'")
+                }
+                None => write!(f, "range_check {value} to {max_bit_size} bits"),
+            }
+        }
+    }?;
+    writeln!(f, "{}", call_stack(&function.dfg.get_call_stack(instruction)))
 }
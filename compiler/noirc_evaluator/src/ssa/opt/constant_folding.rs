@@ -6,9 +6,11 @@
 //!   by the [`DataFlowGraph`] automatically as new instructions are pushed.
 //! - Check whether any input values have been constrained to be equal to a value of a simpler form
 //!   by a [constrain instruction][Instruction::Constrain]. If so, replace the input value with the simpler form.
-//! - Check whether the instruction is [pure][Instruction::is_pure()]
-//!   and there exists a duplicate instruction earlier in the same block.
-//!   If so, the instruction can be replaced with the results of this previous instruction.
+//! - Check whether the instruction is [pure][Instruction::is_pure()] or a range check
+//!   and there exists a duplicate instruction earlier in the same block or in a block which
+//!   dominates it. If so, the instruction can be replaced with the results of this previous instruction.
+//!   Instructions which [depend on the predicate][Instruction::requires_acir_gen_predicate()]
+//!   set by `enable_side_effects` are only deduplicated when executed under the same predicate.
 //!
 //! These operations are done in parallel so that they can each benefit from each other
 //! without the need for multiple passes.
@@ -20,16 +22,19 @@
 //!
 //! This is the only pass which removes duplicated pure [`Instruction`]s however and so is needed when
 //! different blocks are merged, i.e. after the [`flatten_cfg`][super::flatten_cfg] pass.
-use std::collections::HashSet;
-
+use acvm::FieldElement;
 use iter_extended::vecmap;
 
 use crate::ssa::{
     ir::{
         basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
         dfg::{DataFlowGraph, InsertInstructionResult},
-        function::Function,
+        dom::DominatorTree,
+        function::{Function, RuntimeType},
         instruction::{Instruction, InstructionId},
+        post_order::PostOrder,
+        types::Type,
         value::ValueId,
     },
     ssa_gen::Ssa,
@@ -51,57 +56,93 @@ impl Ssa {
 /// The structure of this pass is simple:
 /// Go through each block and re-insert all instructions.
 fn constant_fold(function: &mut Function) {
-    let mut context = Context::default();
-    context.block_queue.push(function.entry_block());
-
-    while let Some(block) = context.block_queue.pop() {
-        if context.visited_blocks.contains(&block) {
-            continue;
-        }
+    let cfg = ControlFlowGraph::with_function(function);
+    let post_order = PostOrder::with_function(function);
+    let mut context = Context {
+        dom_tree: DominatorTree::with_cfg_and_post_order(&cfg, &post_order),
+        cached_instruction_results: HashMap::default(),
+    };
 
-        context.visited_blocks.insert(block);
-        context.fold_constants_in_block(function, block);
+    // Blocks are visited in reverse post order so that each block is visited after every
+    // block which dominates it, and so after any instruction it may reuse the results of.
+    for block in post_order.as_slice().iter().rev() {
+        context.fold_constants_in_block(function, *block);
     }
 }
 
-#[derive(Default)]
 struct Context {
-    /// Maps pre-folded ValueIds to the new ValueIds obtained by re-inserting the instruction.
-    visited_blocks: HashSet<BasicBlockId>,
-    block_queue: Vec<BasicBlockId>,
+    dom_tree: DominatorTree,
+
+    /// Cache of instructions without any side-effects along with their outputs.
+    ///
+    /// Instructions which depend on the `enable_side_effects` predicate in ACIR are cached
+    /// separately for each predicate they were executed under.
+    cached_instruction_results: HashMap<Instruction, HashMap<Option<ValueId>, ResultCache>>,
+}
+
+/// The results of a cached instruction along with the block they were computed in.
+#[derive(Default)]
+struct ResultCache {
+    results: Vec<(BasicBlockId, Vec<ValueId>)>,
+}
+
+impl ResultCache {
+    /// Records the results of an instruction computed in the given block.
+    fn cache(&mut self, block: BasicBlockId, results: Vec<ValueId>) {
+        self.results.push((block, results));
+    }
+
+    /// Returns a set of results computed in a block which dominates the given block, if any.
+    /// These results are available in the given block, so they may replace those of a copy
+    /// of the instruction there.
+    fn get(&self, block: BasicBlockId, dom_tree: &mut DominatorTree) -> Option<&[ValueId]> {
+        self.results
+            .iter()
+            .find(|(origin_block, _)| dom_tree.dominates(*origin_block, block))
+            .map(|(_, results)| results.as_slice())
+    }
 }
 
 impl Context {
     fn fold_constants_in_block(&mut self, function: &mut Function, block: BasicBlockId) {
         let instructions = function.dfg[block].take_instructions();
 
-        // Cache of instructions without any side-effects along with their outputs.
-        let mut cached_instruction_results: HashMap<Instruction, Vec<ValueId>> = HashMap::default();
+        // Side effects are always enabled at the start of a block.
+        let mut side_effects_enabled_var =
+            function.dfg.make_constant(FieldElement::one(), Type::bool());
 
+        let runtime = function.runtime();
         for instruction_id in instructions {
-            Self::fold_constants_into_instruction(
+            side_effects_enabled_var = self.fold_constants_into_instruction(
                 &mut function.dfg,
+                runtime,
                 block,
                 instruction_id,
-                &mut cached_instruction_results,
+                side_effects_enabled_var,
             );
         }
-        self.block_queue.extend(function.dfg[block].successors());
     }
 
+    /// Folds constants into a single instruction, returning the predicate in effect for the
+    /// instructions following it.
     fn fold_constants_into_instruction(
+        &mut self,
         dfg: &mut DataFlowGraph,
+        runtime: RuntimeType,
         block: BasicBlockId,
         id: InstructionId,
-        instruction_result_cache: &mut HashMap<Instruction, Vec<ValueId>>,
-    ) {
+        side_effects_enabled_var: ValueId,
+    ) -> ValueId {
         let instruction = Self::resolve_instruction(id, dfg);
         let old_results = dfg.instruction_results(id).to_vec();
 
-        // If a copy of this instruction exists earlier in the block, then reuse the previous results.
-        if let Some(cached_results) = instruction_result_cache.get(&instruction) {
-            Self::replace_result_ids(dfg, &old_results, cached_results);
-            return;
+        // If a copy of this instruction exists earlier in the block or in a dominating block,
+        // then reuse the previous results.
+        let predicate =
+            instruction.requires_acir_gen_predicate(dfg).then_some(side_effects_enabled_var);
+        if let Some(cached_results) = self.get_cached(&instruction, predicate, block) {
+            Self::replace_result_ids(dfg, &old_results, &cached_results);
+            return side_effects_enabled_var;
         }
 
         // Otherwise, try inserting the instruction again to apply any optimizations using the newly resolved inputs.
@@ -109,7 +150,26 @@ impl Context {
 
         Self::replace_result_ids(dfg, &old_results, &new_results);
 
-        Self::cache_instruction(instruction, new_results, dfg, instruction_result_cache);
+        let side_effects_enabled_var = match instruction {
+            Instruction::EnableSideEffects { condition } => condition,
+            _ => side_effects_enabled_var,
+        };
+
+        self.cache_instruction(instruction, new_results, dfg, runtime, block, predicate);
+        side_effects_enabled_var
+    }
+
+    /// Returns the results of a copy of the given instruction executed under the same predicate
+    /// in the given block or one of its dominators.
+    fn get_cached(
+        &mut self,
+        instruction: &Instruction,
+        predicate: Option<ValueId>,
+        block: BasicBlockId,
+    ) -> Option<Vec<ValueId>> {
+        let results_for_predicate = self.cached_instruction_results.get(instruction)?;
+        let cached = results_for_predicate.get(&predicate)?;
+        cached.get(block, &mut self.dom_tree).map(<[ValueId]>::to_vec)
     }
 
     /// Fetches an [`Instruction`] by its [`InstructionId`] and fully resolves its inputs.
@@ -151,15 +211,30 @@ impl Context {
     }
 
     fn cache_instruction(
+        &mut self,
         instruction: Instruction,
         instruction_results: Vec<ValueId>,
         dfg: &DataFlowGraph,
-        instruction_result_cache: &mut HashMap<Instruction, Vec<ValueId>>,
+        runtime: RuntimeType,
+        block: BasicBlockId,
+        predicate: Option<ValueId>,
     ) {
+        // Brillig may perform an array set in place, so two identical array sets must each
+        // produce their own array rather than sharing the result of the first.
+        if runtime == RuntimeType::Brillig && matches!(instruction, Instruction::ArraySet { .. }) {
+            return;
+        }
+
         // If the instruction doesn't have side-effects, cache the results so we can reuse them if
-        // the same instruction appears again later in the block.
-        if instruction.is_pure(dfg) {
-            instruction_result_cache.insert(instruction, instruction_results);
+        // the same instruction appears again later in the block or in a block it dominates.
+        // Range checks have no results, so a later copy of one can simply be removed.
+        if instruction.is_pure(dfg) || matches!(instruction, Instruction::RangeCheck { .. }) {
+            self.cached_instruction_results
+                .entry(instruction)
+                .or_default()
+                .entry(predicate)
+                .or_default()
+                .cache(block, instruction_results);
         }
     }
 
@@ -179,15 +254,19 @@ impl Context {
 mod test {
     use std::rc::Rc;
 
+    use iter_extended::vecmap;
+
     use crate::ssa::{
         function_builder::FunctionBuilder,
         ir::{
+            basic_block::BasicBlockId,
             function::RuntimeType,
             instruction::{BinaryOp, Instruction, TerminatorInstruction},
             map::Id,
             types::Type,
             value::{Value, ValueId},
         },
+        ssa_gen::Ssa,
     };
 
     #[test]
@@ -333,4 +412,80 @@ mod test {
 
         assert_eq!(instruction, &Instruction::Cast(ValueId::test_new(0), Type::unsigned(32)));
     }
+
+    #[test]
+    fn deduplicates_instructions_from_dominating_blocks() {
+        // The multiplication in b1 is a copy of the one in its dominator b0, but the additions
+        // in b1 and b2 must both remain as neither block dominates the other.
+        let src = "brillig fn main f0 {
+  b0(v0: Field, v1: u1):
+    v2 = mul v0, v0
+    jmpif v1 then: b1, else: b2
+  b1():
+    v3 = mul v0, v0
+    v4 = add v0, Field 1
+    jmp b3(v3)
+  b2():
+    v5 = add v0, Field 1
+    jmp b3(v5)
+  b3(v6: Field):
+    return v2, v6
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.fold_constants();
+        let main = ssa.main();
+
+        let entry = &main.dfg[main.entry_block()];
+        let square = main.dfg.instruction_results(entry.instructions()[0])[0];
+
+        let then_block = &main.dfg[BasicBlockId::test_new(1)];
+        assert_eq!(then_block.instructions().len(), 1);
+        let jump_argument = then_block.terminator_arguments()[0];
+        assert_eq!(main.dfg.resolve(jump_argument), main.dfg.resolve(square));
+
+        let else_block = &main.dfg[BasicBlockId::test_new(2)];
+        assert_eq!(else_block.instructions().len(), 1);
+    }
+
+    #[test]
+    fn deduplicates_instructions_under_different_predicates_only_if_unpredicated() {
+        // The hash and the range check do not depend on the predicate, so their second copies
+        // are removed. The array reads do, so each predicate keeps its own.
+        let src = "acir fn main f0 {
+  b0(v0: [Field; 2], v1: u1, v2: u32, v3: Field):
+    enable_side_effects v1
+    v4 = call pedersen_hash(v0, u32 0) -> Field
+    v5 = array_get v0, index v2 -> Field
+    range_check v3 to 8 bits
+    v6 = not v1
+    enable_side_effects v6
+    v7 = call pedersen_hash(v0, u32 0) -> Field
+    v8 = array_get v0, index v2 -> Field
+    range_check v3 to 8 bits
+    enable_side_effects u1 1
+    return v4, v5, v7, v8
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.fold_constants();
+        let main = ssa.main();
+
+        let entry = &main.dfg[main.entry_block()];
+        assert_eq!(entry.instructions().len(), 8);
+
+        let range_checks = entry
+            .instructions()
+            .iter()
+            .filter(|instruction| matches!(main.dfg[**instruction], Instruction::RangeCheck { .. }))
+            .count();
+        assert_eq!(range_checks, 1);
+
+        let Some(TerminatorInstruction::Return { return_values, .. }) = entry.terminator() else {
+            panic!("Expected main to return");
+        };
+        let returned = vecmap(return_values, |value| main.dfg.resolve(*value));
+        assert_eq!(returned[0], returned[2]);
+        assert_ne!(returned[1], returned[3]);
+    }
 }
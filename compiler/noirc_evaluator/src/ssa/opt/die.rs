@@ -6,8 +6,8 @@ use crate::ssa::{
     ir::{
         basic_block::{BasicBlock, BasicBlockId},
         dfg::DataFlowGraph,
-        function::Function,
-        instruction::InstructionId,
+        function::{Function, RuntimeType},
+        instruction::{Instruction, InstructionId},
         post_order::PostOrder,
         value::{Value, ValueId},
     },
@@ -38,6 +38,10 @@ fn dead_instruction_elimination(function: &mut Function) {
     for block in blocks.as_slice() {
         context.remove_unused_instructions_in_block(function, *block);
     }
+
+    for block in blocks.as_slice() {
+        context.remove_dangling_debug_values(function, *block);
+    }
 }
 
 /// Per function context for tracking unused values and which instructions to remove.
@@ -68,6 +72,11 @@ impl Context {
         self.mark_terminator_values_as_used(function, block);
 
         for instruction in block.instructions().iter().rev() {
+            if matches!(function.dfg[*instruction], Instruction::DebugValue { .. }) {
+                // Debug values only describe the program to the debugger so they must not keep
+                // their values alive. They are removed along with their values instead.
+                continue;
+            }
             if self.is_unused(*instruction, function) {
                 self.instructions_to_remove.insert(*instruction);
             } else {
@@ -83,6 +92,43 @@ impl Context {
             .retain(|instruction| !self.instructions_to_remove.contains(instruction));
     }
 
+    /// Removes the [`Instruction::DebugValue`]s of the given block which refer to the result of
+    /// an instruction which has been removed.
+    ///
+    /// This must happen once all blocks have been processed as debug values may refer to
+    /// the results of instructions in other blocks.
+    fn remove_dangling_debug_values(&self, function: &mut Function, block_id: BasicBlockId) {
+        let dfg = &function.dfg;
+        let dangling: HashSet<InstructionId> = dfg[block_id]
+            .instructions()
+            .iter()
+            .copied()
+            .filter(|instruction| {
+                let Instruction::DebugValue { values, .. } = &dfg[*instruction] else {
+                    return false;
+                };
+                values.iter().any(|value| self.refers_to_removed_instruction(dfg, *value))
+            })
+            .collect();
+
+        function.dfg[block_id]
+            .instructions_mut()
+            .retain(|instruction| !dangling.contains(instruction));
+    }
+
+    /// Inspects a value recursively (as it could be an array) for the results of removed instructions.
+    fn refers_to_removed_instruction(&self, dfg: &DataFlowGraph, value_id: ValueId) -> bool {
+        match &dfg[dfg.resolve(value_id)] {
+            Value::Instruction { instruction, .. } => {
+                self.instructions_to_remove.contains(instruction)
+            }
+            Value::Array { array, .. } => {
+                array.iter().any(|elem| self.refers_to_removed_instruction(dfg, *elem))
+            }
+            _ => false,
+        }
+    }
+
     /// Returns true if an instruction can be removed.
     ///
     /// An instruction can be removed as long as it has no side-effects, and none of its result
@@ -90,7 +136,12 @@ impl Context {
     fn is_unused(&self, instruction_id: InstructionId, function: &Function) -> bool {
         let instruction = &function.dfg[instruction_id];
 
-        if instruction.has_side_effects(&function.dfg) {
+        if matches!(instruction, Instruction::IncrementRc { .. } | Instruction::DecrementRc { .. })
+        {
+            // Reference counts are only used by Brillig to decide when arrays can be mutated
+            // in place, so they can be dropped from functions which are compiled to ACIR.
+            function.runtime() == RuntimeType::Acir
+        } else if instruction.has_side_effects(&function.dfg) {
             // If the instruction has side effects we should never remove it.
             false
         } else {
@@ -132,10 +183,11 @@ mod test {
         function_builder::FunctionBuilder,
         ir::{
             function::RuntimeType,
-            instruction::{BinaryOp, Intrinsic},
+            instruction::{BinaryOp, Instruction, Intrinsic},
             map::Id,
             types::Type,
         },
+        ssa_gen::Ssa,
     };
 
     #[test]
@@ -219,4 +271,49 @@ mod test {
         assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
         assert_eq!(main.dfg[b1].instructions().len(), 6);
     }
+    #[test]
+    fn removes_reference_counts_from_acir_functions_only() {
+        let src = "acir fn main f0 {
+  b0(v0: [Field; 2]):
+    inc_rc v0
+    dec_rc v0
+    return v0
+}
+brillig fn foo f1 {
+  b0(v0: [Field; 2]):
+    inc_rc v0
+    dec_rc v0
+    return v0
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.dead_instruction_elimination();
+
+        let main = ssa.main();
+        assert!(main.dfg[main.entry_block()].instructions().is_empty());
+
+        let foo = &ssa.functions[&Id::test_new(1)];
+        assert_eq!(foo.dfg[foo.entry_block()].instructions().len(), 2);
+    }
+
+    #[test]
+    fn debug_values_do_not_keep_values_alive() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v1 = add v0, Field 1
+    v2 = mul v0, Field 2
+    debug_value var 1, v0, v1
+    debug_value var 2, v2
+    return v1
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.dead_instruction_elimination();
+
+        // `v2` is only used by the debug value of variable 2, so both are removed.
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 2);
+        assert!(matches!(main.dfg[instructions[1]], Instruction::DebugValue { variable: 1, .. }));
+    }
 }
@@ -2,6 +2,13 @@
 //! The purpose of this pass is to inline the instructions of each function call
 //! within the function caller. If all function calls are known, there will only
 //! be a single function remaining when the pass finishes.
+//!
+//! Unconstrained functions are the exception: calls to them from constrained code are never
+//! inlined, and calls between them are only inlined if the callee is small, not called too
+//! often and not recursive. The remaining calls become real calls in the Brillig bytecode.
+//!
+//! Constrained functions marked `#[fold]` are never inlined either. Each is compiled into its
+//! own circuit which its callers call through an ACIR call opcode.
 use std::collections::{BTreeSet, HashSet};
 
 use iter_extended::{btree_map, vecmap};
@@ -12,7 +19,7 @@ use crate::ssa::{
         basic_block::BasicBlockId,
         dfg::{CallStack, InsertInstructionResult},
         function::{Function, FunctionId, RuntimeType},
-        instruction::{Instruction, InstructionId, TerminatorInstruction},
+        instruction::{Instruction, InstructionId, Intrinsic, TerminatorInstruction},
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
@@ -23,6 +30,11 @@ use fxhash::FxHashMap as HashMap;
 /// frames at any point in time.
 const RECURSION_LIMIT: u32 = 1000;
 
+/// An unconstrained function is only inlined into its unconstrained callers if its number of
+/// instructions multiplied by its number of call sites is at most this limit, which bounds the
+/// growth of the Brillig bytecode caused by inlining it.
+const BRILLIG_INLINING_BUDGET: usize = 100;
+
 impl Ssa {
     /// Inline all functions within the IR.
     ///
@@ -36,14 +48,164 @@ impl Ssa {
     /// changes. This is because if the function's id later becomes known by a later
     /// pass, we would need to re-run all of inlining anyway to inline it, so we might
     /// as well save the work for later instead of performing it twice.
+    ///
+    /// Unconstrained functions are inlined into unconstrained callers according to
+    /// [`BrilligInliningHeuristics`]. Constrained functions called from unconstrained code are
+    /// first copied into unconstrained functions so that the same heuristics apply to them.
     pub(crate) fn inline_functions(mut self) -> Ssa {
+        self.call_unconstrained_copies();
+        let heuristics = BrilligInliningHeuristics::new(&self);
+
         self.functions = btree_map(get_entry_point_functions(&self), |entry_point| {
-            let new_function = InlineContext::new(&self, entry_point).inline_all(&self);
+            let new_function =
+                InlineContext::new(&self, entry_point, &heuristics).inline_all(&self);
             (entry_point, new_function)
         });
 
         self
     }
+
+    /// Replaces each call from an unconstrained function to a constrained function with a call
+    /// to an unconstrained copy of the latter, so that all code reachable from unconstrained
+    /// functions is itself unconstrained.
+    fn call_unconstrained_copies(&mut self) {
+        let mut copies: HashMap<FunctionId, FunctionId> = HashMap::default();
+        let mut queue = self
+            .functions
+            .iter()
+            .filter(|(_, function)| function.runtime() == RuntimeType::Brillig)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        while let Some(caller_id) = queue.pop() {
+            let caller = &self.functions[&caller_id];
+            let calls = vecmap(function_calls(caller), |(instruction, callee)| {
+                (instruction, callee, self.functions[&callee].runtime())
+            });
+
+            for (instruction, callee, runtime) in calls {
+                if runtime == RuntimeType::Brillig {
+                    continue;
+                }
+                let copy = *copies.entry(callee).or_insert_with(|| {
+                    let copy_id = self.next_id.next();
+                    let mut copy = self.functions[&callee].clone_with_id(copy_id);
+                    copy.set_runtime(RuntimeType::Brillig);
+                    self.functions.insert(copy_id, copy);
+                    queue.push(copy_id);
+                    copy_id
+                });
+
+                let dfg = &mut self.functions.get_mut(&caller_id).unwrap().dfg;
+                let func = dfg.import_function(copy);
+                let Instruction::Call { arguments, .. } = &dfg[instruction] else {
+                    unreachable!("Expected {instruction} to be a call")
+                };
+                let arguments = arguments.clone();
+                dfg[instruction] = Instruction::Call { func, arguments };
+            }
+        }
+    }
+}
+
+/// Returns each call instruction in the reachable blocks of `function` whose target is known,
+/// along with the function it calls.
+fn function_calls(function: &Function) -> Vec<(InstructionId, FunctionId)> {
+    let mut calls = Vec::new();
+    for block in function.reachable_blocks() {
+        for instruction in function.dfg[block].instructions() {
+            if let Instruction::Call { func, .. } = &function.dfg[*instruction] {
+                if let Value::Function(callee) = function.dfg[function.dfg.resolve(*func)] {
+                    calls.push((*instruction, callee));
+                }
+            }
+        }
+    }
+    calls
+}
+
+/// Decides which calls between unconstrained functions are inlined.
+///
+/// Inlining every call into an unconstrained entry point grows its Brillig bytecode by the size
+/// of each callee for every time it is called, so only small functions which are not called too
+/// often are inlined, as measured by [`BRILLIG_INLINING_BUDGET`]. Recursive functions are never
+/// inlined so that recursion in unconstrained code goes through real calls rather than being
+/// unrolled up to the [`RECURSION_LIMIT`]. Functions asserting that a value is constant are
+/// always inlined, as the value usually only becomes constant once inlined into the caller.
+struct BrilligInliningHeuristics {
+    /// The unconstrained functions which should be inlined into their unconstrained callers.
+    inlined_functions: HashSet<FunctionId>,
+}
+
+impl BrilligInliningHeuristics {
+    fn new(ssa: &Ssa) -> Self {
+        let brillig_functions = ssa
+            .functions
+            .values()
+            .filter(|function| function.runtime() == RuntimeType::Brillig)
+            .map(|function| (function.id(), function_calls(function)))
+            .collect::<HashMap<_, _>>();
+
+        let mut call_sites: HashMap<FunctionId, usize> = HashMap::default();
+        for calls in brillig_functions.values() {
+            for (_, callee) in calls {
+                *call_sites.entry(*callee).or_default() += 1;
+            }
+        }
+
+        let inlined_functions = brillig_functions
+            .keys()
+            .copied()
+            .filter(|id| {
+                let function = &ssa.functions[id];
+                let instructions = function
+                    .reachable_blocks()
+                    .into_iter()
+                    .flat_map(|block| function.dfg[block].instructions())
+                    .filter(|instruction| {
+                        !matches!(function.dfg[**instruction], Instruction::DebugValue { .. })
+                    })
+                    .collect::<Vec<_>>();
+                let asserts_constant = instructions.iter().any(|instruction| {
+                    let Instruction::Call { func, .. } = &function.dfg[**instruction] else {
+                        return false;
+                    };
+                    let func = function.dfg.resolve(*func);
+                    matches!(function.dfg[func], Value::Intrinsic(Intrinsic::AssertConstant))
+                });
+                let call_sites = call_sites.get(id).copied().unwrap_or_default();
+                let within_budget =
+                    instructions.len().saturating_mul(call_sites) <= BRILLIG_INLINING_BUDGET;
+                (asserts_constant || within_budget) && !Self::is_recursive(*id, &brillig_functions)
+            })
+            .collect();
+
+        Self { inlined_functions }
+    }
+
+    /// Returns true if `function` may call itself, either directly or through other functions.
+    fn is_recursive(
+        function: FunctionId,
+        calls: &HashMap<FunctionId, Vec<(InstructionId, FunctionId)>>,
+    ) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![function];
+        while let Some(caller) = stack.pop() {
+            for (_, callee) in calls.get(&caller).into_iter().flatten() {
+                if *callee == function {
+                    return true;
+                }
+                if visited.insert(*callee) {
+                    stack.push(*callee);
+                }
+            }
+        }
+        false
+    }
+
+    fn should_inline(&self, function: FunctionId) -> bool {
+        self.inlined_functions.contains(&function)
+    }
 }
 
 /// The context for the function inlining pass.
@@ -51,7 +213,7 @@ impl Ssa {
 /// This works using an internal FunctionBuilder to build a new main function from scratch.
 /// Doing it this way properly handles importing instructions between functions and lets us
 /// reuse the existing API at the cost of essentially cloning each of main's instructions.
-struct InlineContext {
+struct InlineContext<'heuristics> {
     recursion_level: u32,
     builder: FunctionBuilder,
 
@@ -59,6 +221,8 @@ struct InlineContext {
 
     // The FunctionId of the entry point function we're inlining into in the old, unmodified Ssa.
     entry_point: FunctionId,
+
+    brillig_heuristics: &'heuristics BrilligInliningHeuristics,
 }
 
 /// The per-function inlining context contains information that is only valid for one function.
@@ -66,13 +230,13 @@ struct InlineContext {
 /// layer to translate between BlockId to BlockId for the current function and the function to
 /// inline into. The same goes for ValueIds, InstructionIds, and for storing other data like
 /// parameter to argument mappings.
-struct PerFunctionContext<'function> {
+struct PerFunctionContext<'function, 'heuristics> {
     /// The source function is the function we're currently inlining into the function being built.
     source_function: &'function Function,
 
     /// The shared inlining context for all functions. This notably contains the FunctionBuilder used
     /// to build the function we're inlining into.
-    context: &'function mut InlineContext,
+    context: &'function mut InlineContext<'heuristics>,
 
     /// Maps ValueIds in the function being inlined to the new ValueIds to use in the function
     /// being inlined into. This mapping also contains the mapping from parameter values to
@@ -94,11 +258,11 @@ struct PerFunctionContext<'function> {
 
 /// The entry point functions are each function we should inline into - and each function that
 /// should be left in the final program. This is usually just `main` but also includes any
-/// brillig functions used.
+/// brillig functions and folded functions used.
 fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
     let functions = ssa.functions.iter();
     let mut entry_points = functions
-        .filter(|(_, function)| function.runtime() == RuntimeType::Brillig)
+        .filter(|(_, function)| function.runtime() == RuntimeType::Brillig || function.is_fold())
         .map(|(id, _)| *id)
         .collect::<BTreeSet<_>>();
 
@@ -106,16 +270,42 @@ fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
     entry_points
 }
 
-impl InlineContext {
+impl<'heuristics> InlineContext<'heuristics> {
     /// Create a new context object for the function inlining pass.
     /// This starts off with an empty mapping of instructions for main's parameters.
     /// The function being inlined into will always be the main function, although it is
     /// actually a copy that is created in case the original main is still needed from a function
     /// that could not be inlined calling it.
-    fn new(ssa: &Ssa, entry_point: FunctionId) -> InlineContext {
+    fn new(
+        ssa: &Ssa,
+        entry_point: FunctionId,
+        brillig_heuristics: &'heuristics BrilligInliningHeuristics,
+    ) -> Self {
         let source = &ssa.functions[&entry_point];
-        let builder = FunctionBuilder::new(source.name().to_owned(), entry_point, source.runtime());
-        Self { builder, recursion_level: 0, entry_point, call_stack: CallStack::new() }
+        let mut builder =
+            FunctionBuilder::new(source.name().to_owned(), entry_point, source.runtime());
+        builder.current_function.set_fold(source.is_fold());
+        Self {
+            builder,
+            recursion_level: 0,
+            entry_point,
+            call_stack: CallStack::new(),
+            brillig_heuristics,
+        }
+    }
+
+    /// Returns true if a call to `callee` from the function being built should be inlined.
+    fn should_inline_call(&self, ssa: &Ssa, callee: FunctionId) -> bool {
+        let callee_function = &ssa.functions[&callee];
+        match (self.builder.current_function.runtime(), callee_function.runtime()) {
+            // Folded functions are called through their own circuit.
+            (_, RuntimeType::Acir) => !callee_function.is_fold(),
+            // Unconstrained code must never become part of the circuit.
+            (RuntimeType::Acir, RuntimeType::Brillig) => false,
+            (RuntimeType::Brillig, RuntimeType::Brillig) => {
+                self.brillig_heuristics.should_inline(callee)
+            }
+        }
     }
 
     /// Start inlining the entry point function and all functions reachable from it.
@@ -178,12 +368,15 @@ impl InlineContext {
     }
 }
 
-impl<'function> PerFunctionContext<'function> {
+impl<'function, 'heuristics> PerFunctionContext<'function, 'heuristics> {
     /// Create a new PerFunctionContext from the source function.
     /// The value and block mappings for this context are initially empty except
     /// for containing the mapping between parameters in the source_function and
     /// the arguments of the destination function.
-    fn new(context: &'function mut InlineContext, source_function: &'function Function) -> Self {
+    fn new(
+        context: &'function mut InlineContext<'heuristics>,
+        source_function: &'function Function,
+    ) -> Self {
         Self {
             context,
             source_function,
@@ -344,11 +537,10 @@ impl<'function> PerFunctionContext<'function> {
         for id in block.instructions() {
             match &self.source_function.dfg[*id] {
                 Instruction::Call { func, arguments } => match self.get_function(*func) {
-                    Some(function) => match ssa.functions[&function].runtime() {
-                        RuntimeType::Acir => self.inline_function(ssa, *id, function, arguments),
-                        RuntimeType::Brillig => self.push_instruction(*id),
-                    },
-                    None => self.push_instruction(*id),
+                    Some(function) if self.context.should_inline_call(ssa, function) => {
+                        self.inline_function(ssa, *id, function, arguments);
+                    }
+                    _ => self.push_instruction(*id),
                 },
                 _ => self.push_instruction(*id),
             }
@@ -514,8 +706,11 @@ mod test {
             map::Id,
             types::Type,
         },
+        ssa_gen::Ssa,
     };
 
+    use super::function_calls;
+
     #[test]
     fn basic_inlining() {
         // fn foo {
@@ -782,4 +977,87 @@ mod test {
         let main = ssa.main();
         assert_eq!(main.reachable_blocks().len(), 4);
     }
+
+    #[test]
+    fn keeps_recursive_calls_in_unconstrained_code() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v2 = call f1(v0) -> Field
+    return v2
+}
+brillig fn entry f1 {
+  b0(v0: Field):
+    v3 = call f2(v0) -> Field
+    v5 = call f3(v3) -> Field
+    return v5
+}
+brillig fn double f2 {
+  b0(v0: Field):
+    v1 = add v0, v0
+    return v1
+}
+acir fn countdown f3 {
+  b0(v0: Field):
+    v2 = eq v0, Field 0
+    jmpif v2 then: b1, else: b2
+  b1():
+    return v0
+  b2():
+    v4 = sub v0, Field 1
+    v6 = call f3(v4) -> Field
+    return v6
+}
+";
+        let ssa = src.parse::<Ssa>().unwrap().inline_functions();
+        let calls = |id: usize| {
+            let function = &ssa.functions[&Id::test_new(id)];
+            function_calls(function).into_iter().map(|(_, callee)| callee).collect::<Vec<_>>()
+        };
+
+        // `double` is small enough to be inlined, while `countdown` is recursive so it is
+        // copied into an unconstrained function which calls itself.
+        let countdown_copy = Id::test_new(4);
+        assert_eq!(calls(1), vec![countdown_copy]);
+        assert_eq!(calls(4), vec![countdown_copy]);
+        assert_eq!(ssa.functions[&countdown_copy].runtime(), RuntimeType::Brillig);
+        assert!(!ssa.functions.contains_key(&Id::test_new(3)));
+    }
+
+    #[test]
+    fn keeps_calls_to_folded_functions() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v2 = call f1(v0) -> Field
+    v3 = call f2(v2) -> Field
+    return v3
+}
+acir fn square f1 {
+  b0(v0: Field):
+    v1 = mul v0, v0
+    return v1
+}
+acir(fold) fn increment f2 {
+  b0(v0: Field):
+    v2 = call f3(v0) -> Field
+    return v2
+}
+acir fn add_one f3 {
+  b0(v0: Field):
+    v2 = add v0, Field 1
+    return v2
+}
+";
+        let ssa = src.parse::<Ssa>().unwrap().inline_functions();
+        let calls = |id: usize| {
+            let function = &ssa.functions[&Id::test_new(id)];
+            function_calls(function).into_iter().map(|(_, callee)| callee).collect::<Vec<_>>()
+        };
+
+        // `square` is inlined into main while the folded `increment` is kept as a call,
+        // with `add_one` inlined into it.
+        assert_eq!(calls(0), vec![Id::test_new(2)]);
+        assert!(calls(2).is_empty());
+        assert!(ssa.functions[&Id::test_new(2)].is_fold());
+        assert_eq!(ssa.functions.len(), 2);
+    }
 }
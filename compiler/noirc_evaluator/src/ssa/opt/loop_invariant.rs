@@ -0,0 +1,456 @@
+//! Loop-invariant code motion (LICM) pass for Brillig functions.
+//!
+//! ACIR functions have all of their loops unrolled, but Brillig functions keep them, so any
+//! computation inside a loop body is repeated on every iteration. For each loop, innermost first,
+//! this pass:
+//! 1. Hoists instructions whose operands are all defined outside of the loop into the loop's
+//!    pre-header. Only pure instructions which can never fail are hoisted, since a hoisted
+//!    instruction is executed even when the loop body is not.
+//! 2. Replaces multiplications of the loop's induction variable by a constant with a new
+//!    induction variable which is incremented by the scaled step on each iteration. This turns
+//!    the index arithmetic `i * c` of a loop into a single addition.
+//!
+//! Hoisting from an inner loop moves instructions into the body of its outer loop, where they
+//! may be hoisted again when the outer loop is processed.
+use std::collections::HashSet;
+
+use acvm::FieldElement;
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction},
+        loops::{Loop, LoopAnalysis},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Hoists loop-invariant instructions out of the loops of each Brillig function and
+    /// strength-reduces multiplications of their induction variables.
+    pub(crate) fn loop_invariant_code_motion(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            // ACIR functions have no loops left by the time this pass runs.
+            if function.runtime() == RuntimeType::Brillig {
+                loop_invariant_code_motion(function);
+            }
+        }
+        self
+    }
+}
+
+fn loop_invariant_code_motion(function: &mut Function) {
+    // Hoisting only moves instructions between existing blocks, so the loops and control
+    // flow graph of the function remain valid for the whole pass.
+    let LoopAnalysis { loops, cfg, .. } = LoopAnalysis::with_function(function);
+
+    let mut reverse_post_order = PostOrder::with_function(function).into_vec();
+    reverse_post_order.reverse();
+
+    for loop_ in &loops {
+        let Some(pre_header) = loop_.pre_header(&cfg) else { continue };
+        if !jumps_to(&function.dfg, pre_header, loop_.header) {
+            continue;
+        }
+
+        // Visiting the blocks in reverse post order ensures an instruction is always visited
+        // after the instructions defining its operands.
+        let blocks = blocks_in_order(&reverse_post_order, &loop_.blocks);
+
+        let mut context = LoopContext::new(&function.dfg, &blocks, pre_header);
+        context.hoist_loop_invariants(&mut function.dfg);
+        context.reduce_induction_variable_multiplications(&mut function.dfg, loop_, &cfg);
+    }
+}
+
+/// Returns the blocks of `order` which are also in `blocks`, preserving their order.
+fn blocks_in_order(order: &[BasicBlockId], blocks: &HashSet<BasicBlockId>) -> Vec<BasicBlockId> {
+    order.iter().filter(|block| blocks.contains(block)).copied().collect()
+}
+
+/// True if the given block ends in an unconditional jump to `destination`.
+fn jumps_to(dfg: &DataFlowGraph, block: BasicBlockId, destination: BasicBlockId) -> bool {
+    matches!(
+        dfg[block].terminator(),
+        Some(TerminatorInstruction::Jmp { destination: target, .. }) if *target == destination
+    )
+}
+
+struct LoopContext<'a> {
+    /// The blocks of the loop, in reverse post order.
+    blocks: &'a [BasicBlockId],
+
+    /// The single block outside of the loop which jumps to the loop header.
+    pre_header: BasicBlockId,
+
+    /// Every block parameter and instruction result defined within the loop which
+    /// has not been hoisted out of it.
+    defined_in_loop: HashSet<ValueId>,
+}
+
+impl<'a> LoopContext<'a> {
+    fn new(dfg: &DataFlowGraph, blocks: &'a [BasicBlockId], pre_header: BasicBlockId) -> Self {
+        let mut defined_in_loop = HashSet::new();
+        for block in blocks {
+            defined_in_loop.extend(dfg[*block].parameters());
+            for instruction in dfg[*block].instructions() {
+                defined_in_loop.extend(dfg.instruction_results(*instruction));
+            }
+        }
+        Self { blocks, pre_header, defined_in_loop }
+    }
+
+    /// Moves each loop-invariant instruction of the loop to the end of the pre-header block.
+    fn hoist_loop_invariants(&mut self, dfg: &mut DataFlowGraph) {
+        for block in self.blocks {
+            let instructions = dfg[*block].take_instructions();
+            let mut remaining = Vec::with_capacity(instructions.len());
+
+            for instruction in instructions {
+                if self.can_hoist(dfg, instruction) {
+                    for result in dfg.instruction_results(instruction) {
+                        self.defined_in_loop.remove(result);
+                    }
+                    dfg[self.pre_header].insert_instruction(instruction);
+                } else {
+                    remaining.push(instruction);
+                }
+            }
+
+            *dfg[*block].instructions_mut() = remaining;
+        }
+    }
+
+    fn can_hoist(&self, dfg: &DataFlowGraph, instruction_id: InstructionId) -> bool {
+        let instruction = &dfg[instruction_id];
+        let mut operands_are_invariant = true;
+        instruction.for_each_value(|value| {
+            operands_are_invariant &= self.is_loop_invariant(dfg, value);
+        });
+
+        operands_are_invariant && can_be_speculated(dfg, instruction_id)
+    }
+
+    fn is_loop_invariant(&self, dfg: &DataFlowGraph, value: ValueId) -> bool {
+        let value = dfg.resolve(value);
+        match &dfg[value] {
+            Value::Instruction { .. } | Value::Param { .. } => {
+                !self.defined_in_loop.contains(&value)
+            }
+            Value::Array { array, .. } => {
+                array.iter().all(|element| self.is_loop_invariant(dfg, *element))
+            }
+            Value::NumericConstant { .. }
+            | Value::Function(_)
+            | Value::Intrinsic(_)
+            | Value::ForeignFunction(_) => true,
+        }
+    }
+
+    /// Replaces each `mul i, c` in the loop, where `i` is an induction variable of the loop
+    /// and `c` is a constant, with a new induction variable starting at `start * c` and
+    /// incremented by `step * c` on each iteration.
+    fn reduce_induction_variable_multiplications(
+        &self,
+        dfg: &mut DataFlowGraph,
+        loop_: &Loop,
+        cfg: &ControlFlowGraph,
+    ) {
+        // The header must only be entered from the pre-header and the end of the loop body
+        // so that the new induction variables are given a value along every edge.
+        if cfg.predecessors(loop_.header).len() != 2
+            || !jumps_to(dfg, loop_.back_edge_start, loop_.header)
+        {
+            return;
+        }
+
+        let parameters = dfg[loop_.header].parameters().to_vec();
+        for (position, parameter) in parameters.into_iter().enumerate() {
+            let start = dfg[self.pre_header].terminator_arguments()[position];
+            let next = dfg[loop_.back_edge_start].terminator_arguments()[position];
+            let Some(step) = induction_step(dfg, parameter, next) else { continue };
+            let Type::Numeric(numeric_type) = dfg.type_of_value(parameter) else { continue };
+
+            // Each multiplication by the same constant can share a single induction variable.
+            let mut scaled_variables: HashMap<FieldElement, ValueId> = HashMap::default();
+
+            for block in self.blocks {
+                for instruction in dfg[*block].instructions().to_vec() {
+                    let Some(factor) = multiplication_factor(dfg, instruction, parameter) else {
+                        continue;
+                    };
+                    let scaled_step = step * factor;
+
+                    // Steps which do not fit in an unsigned type would need to be wrapped, so
+                    // these multiplications are left as they are.
+                    let step_fits = match numeric_type {
+                        NumericType::NativeField => true,
+                        NumericType::Unsigned { .. } => {
+                            numeric_type.value_is_within_limits(scaled_step)
+                        }
+                        NumericType::Signed { .. } => false,
+                    };
+                    if !step_fits {
+                        continue;
+                    }
+
+                    let scaled = match scaled_variables.get(&factor) {
+                        Some(scaled) => *scaled,
+                        None => {
+                            let scaled = self.add_scaled_induction_variable(
+                                dfg,
+                                loop_,
+                                instruction,
+                                start,
+                                factor,
+                                scaled_step,
+                            );
+                            scaled_variables.insert(factor, scaled);
+                            scaled
+                        }
+                    };
+
+                    let result = dfg.instruction_results(instruction)[0];
+                    dfg.set_value_from_id(result, scaled);
+                    dfg[*block].instructions_mut().retain(|id| *id != instruction);
+                }
+            }
+        }
+    }
+
+    /// Adds a new parameter to the loop header which always holds the result of the given
+    /// multiplication of an induction variable starting at `start` by `factor`, returning
+    /// the new parameter.
+    fn add_scaled_induction_variable(
+        &self,
+        dfg: &mut DataFlowGraph,
+        loop_: &Loop,
+        multiplication: InstructionId,
+        start: ValueId,
+        factor: FieldElement,
+        scaled_step: FieldElement,
+    ) -> ValueId {
+        let typ = dfg.type_of_value(dfg.instruction_results(multiplication)[0]);
+        let call_stack = dfg.get_call_stack(multiplication);
+        let scaled = dfg.add_block_parameter(loop_.header, typ.clone());
+
+        let factor = dfg.make_constant(factor, typ.clone());
+        let initial = Instruction::binary(BinaryOp::Mul, start, factor);
+        let initial = dfg
+            .insert_instruction_and_results(initial, self.pre_header, None, call_stack.clone())
+            .first();
+
+        let scaled_step = dfg.make_constant(scaled_step, typ);
+        let increment = Instruction::binary(BinaryOp::Add, scaled, scaled_step);
+        let next = dfg
+            .insert_instruction_and_results(increment, loop_.back_edge_start, None, call_stack)
+            .first();
+
+        push_jump_argument(dfg, self.pre_header, initial);
+        push_jump_argument(dfg, loop_.back_edge_start, next);
+        scaled
+    }
+}
+
+/// Returns the constant step of an induction variable if `next`, the value it is given at the
+/// end of each iteration, is the variable plus a constant.
+fn induction_step(dfg: &DataFlowGraph, variable: ValueId, next: ValueId) -> Option<FieldElement> {
+    let Value::Instruction { instruction, .. } = &dfg[dfg.resolve(next)] else { return None };
+    match &dfg[*instruction] {
+        Instruction::Binary(binary) if binary.operator == BinaryOp::Add => {
+            if dfg.resolve(binary.lhs) == variable {
+                dfg.get_numeric_constant(binary.rhs)
+            } else if dfg.resolve(binary.rhs) == variable {
+                dfg.get_numeric_constant(binary.lhs)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the constant factor if the given instruction multiplies `variable` by a constant.
+fn multiplication_factor(
+    dfg: &DataFlowGraph,
+    instruction: InstructionId,
+    variable: ValueId,
+) -> Option<FieldElement> {
+    match &dfg[instruction] {
+        Instruction::Binary(binary) if binary.operator == BinaryOp::Mul => {
+            if dfg.resolve(binary.lhs) == variable {
+                dfg.get_numeric_constant(binary.rhs)
+            } else if dfg.resolve(binary.rhs) == variable {
+                dfg.get_numeric_constant(binary.lhs)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Appends an argument to the jmp terminating the given block.
+fn push_jump_argument(dfg: &mut DataFlowGraph, block: BasicBlockId, argument: ValueId) {
+    match dfg[block].unwrap_terminator_mut() {
+        TerminatorInstruction::Jmp { arguments, .. } => arguments.push(argument),
+        other => unreachable!("ICE: Expected a jmp to the loop header, found {other:?}"),
+    }
+}
+
+/// True if the instruction can be executed even on paths where it was not originally executed.
+///
+/// This is the case for pure instructions which can never fail at runtime. Array reads are only
+/// included when their index is known to be in bounds, and instructions creating new arrays are
+/// excluded as they would share a single, reference counted, array between iterations.
+fn can_be_speculated(dfg: &DataFlowGraph, instruction_id: InstructionId) -> bool {
+    let instruction = &dfg[instruction_id];
+    if !instruction.is_pure(dfg) || instruction.has_side_effects(dfg) {
+        return false;
+    }
+
+    let returns_array = dfg.instruction_results(instruction_id).iter().any(|result| {
+        matches!(dfg.type_of_value(*result), Type::Array(..) | Type::Slice(_) | Type::Reference)
+    });
+    if returns_array {
+        return false;
+    }
+
+    match instruction {
+        Instruction::Binary(_) | Instruction::Cast(..) | Instruction::Not(_) => true,
+        Instruction::ArrayGet { array, index } => {
+            match (dfg.type_of_value(*array), dfg.get_numeric_constant(*index)) {
+                (Type::Array(elements, length), Some(index)) => index
+                    .try_to_u64()
+                    .map_or(false, |index| (index as usize) < elements.len() * length),
+                _ => false,
+            }
+        }
+        Instruction::Call { func, .. } => {
+            matches!(dfg[*func], Value::Intrinsic(Intrinsic::ArrayLen))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{
+        ir::{
+            basic_block::BasicBlockId,
+            instruction::{BinaryOp, Instruction},
+        },
+        ssa_gen::Ssa,
+    };
+
+    #[test]
+    fn hoists_invariant_instructions_into_the_pre_header() {
+        let src = "brillig fn main f0 {
+  b0(v0: u32, v1: u32, v2: [Field; 3]):
+    jmp b1(u32 0)
+  b1(v3: u32):
+    v4 = lt v3, v0
+    jmpif v4 then: b2, else: b3
+  b2():
+    v5 = add v0, v1
+    v6 = array_get v2, index u32 1 -> Field
+    v7 = array_get v2, index v3 -> Field
+    v8 = add v3, v5
+    v9 = add v3, u32 1
+    jmp b1(v9)
+  b3():
+    return
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.loop_invariant_code_motion();
+        let main = ssa.main();
+
+        // The add of the parameters and the array read with a constant index are hoisted,
+        // while everything depending on the induction variable stays in the loop body.
+        let entry = &main.dfg[main.entry_block()];
+        assert_eq!(entry.instructions().len(), 2);
+
+        let body = &main.dfg[BasicBlockId::test_new(2)];
+        assert_eq!(body.instructions().len(), 3);
+        assert!(body.instructions().iter().all(|instruction| {
+            !matches!(&main.dfg[*instruction], Instruction::ArrayGet { index, .. }
+                if main.dfg.get_numeric_constant(*index).is_some())
+        }));
+    }
+
+    #[test]
+    fn does_not_hoist_out_of_acir_functions() {
+        let src = "acir fn main f0 {
+  b0(v0: u32, v1: u32):
+    jmp b1(u32 0)
+  b1(v3: u32):
+    v4 = lt v3, v0
+    jmpif v4 then: b2, else: b3
+  b2():
+    v5 = add v0, v1
+    v6 = add v3, u32 1
+    jmp b1(v6)
+  b3():
+    return
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.loop_invariant_code_motion();
+        let main = ssa.main();
+        assert!(main.dfg[main.entry_block()].instructions().is_empty());
+    }
+
+    #[test]
+    fn strength_reduces_induction_variable_multiplications() {
+        let src = "brillig fn main f0 {
+  b0(v0: u32, v1: [Field; 30]):
+    jmp b1(u32 0)
+  b1(v2: u32):
+    v3 = lt v2, u32 10
+    jmpif v3 then: b2, else: b3
+  b2():
+    v4 = mul v2, u32 3
+    v5 = array_get v1, index v4 -> Field
+    v6 = mul v2, u32 3
+    v7 = add v6, u32 1
+    v8 = array_get v1, index v7 -> Field
+    v9 = add v2, u32 1
+    jmp b1(v9)
+  b3():
+    return
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.loop_invariant_code_motion();
+        let main = ssa.main();
+
+        // Both multiplications now use a single new header parameter starting at 0 * 3,
+        // which is incremented by 1 * 3 at the end of each iteration.
+        let header = &main.dfg[BasicBlockId::test_new(1)];
+        assert_eq!(header.parameters().len(), 2);
+        let scaled = header.parameters()[1];
+
+        let body = &main.dfg[BasicBlockId::test_new(2)];
+        let has_multiplication = body.instructions().iter().any(|instruction| {
+            matches!(&main.dfg[*instruction], Instruction::Binary(binary)
+                if binary.operator == BinaryOp::Mul)
+        });
+        assert!(!has_multiplication);
+
+        let entry_arguments = main.dfg[main.entry_block()].terminator_arguments();
+        assert_eq!(main.dfg.get_numeric_constant(entry_arguments[1]), Some(0_u128.into()));
+
+        let Instruction::ArrayGet { index, .. } = &main.dfg[body.instructions()[0]] else {
+            panic!("Expected the first array read to remain in the loop body");
+        };
+        assert_eq!(main.dfg.resolve(*index), scaled);
+    }
+}
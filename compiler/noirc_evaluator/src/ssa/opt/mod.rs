@@ -11,6 +11,9 @@ mod die;
 mod fill_internal_slices;
 pub(crate) mod flatten_cfg;
 mod inlining;
+mod loop_invariant;
 mod mem2reg;
+mod range_analysis;
+mod remove_unreachable;
 mod simplify_cfg;
 mod unrolling;
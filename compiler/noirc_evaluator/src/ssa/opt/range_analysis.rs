@@ -0,0 +1,350 @@
+//! This pass removes [truncations][Instruction::Truncate] and [range checks][Instruction::RangeCheck]
+//! on values which are already known to fit within the required number of bits.
+//!
+//! SSA generation inserts these instructions conservatively after integer arithmetic, each of
+//! which costs a range constraint in ACIR. The pass tracks an upper bound on the number of bits
+//! of each value, taken from:
+//! - Numeric constants, which include the induction variables of loops after unrolling.
+//! - The integer parameters of `main`, which are range constrained as inputs to the program.
+//! - Casts, truncations and arithmetic, bounding each result by the bounds of its operands.
+//! - Range checks and constraints on comparisons executed earlier in the same block, which
+//!   assert a bound on a value for every instruction following them.
+//!
+//! Truncating a value which fits within the truncated bit size leaves it unchanged, so the
+//! truncation is replaced by the value itself. Range checks on such values are removed.
+//!
+//! Brillig functions are left unchanged, since range checks are cheap to execute there.
+use acvm::FieldElement;
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Removes each truncation and range check of an ACIR function on a value which is
+    /// already known to fit within the required number of bits.
+    ///
+    /// See [`range_analysis`][self] module for more information.
+    pub(crate) fn remove_redundant_range_checks(mut self) -> Ssa {
+        let main_id = self.main_id;
+        for function in self.functions.values_mut() {
+            if function.runtime() == RuntimeType::Acir {
+                let is_main = function.id() == main_id;
+                remove_redundant_range_checks(function, is_main);
+            }
+        }
+        self
+    }
+}
+
+fn remove_redundant_range_checks(function: &mut Function, is_main: bool) {
+    let mut context = Context::default();
+
+    if is_main {
+        for parameter in function.dfg[function.entry_block()].parameters() {
+            let typ = function.dfg.type_of_value(*parameter);
+            if let Some(bit_size) = integer_bit_size(&typ) {
+                context.bit_sizes.insert(*parameter, bit_size);
+            }
+        }
+    }
+
+    // Visiting blocks in reverse post order ensures the bounds of each instruction's operands
+    // are known before the instruction itself is visited.
+    let post_order = PostOrder::with_function(function);
+    for block in post_order.as_slice().iter().rev() {
+        context.asserted_bit_sizes.clear();
+        context.simplify_block(&mut function.dfg, *block);
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    /// Upper bounds on the number of bits of each value, known from the instruction
+    /// or parameter defining the value. These hold wherever the value is used.
+    bit_sizes: HashMap<ValueId, u32>,
+
+    /// Upper bounds on the number of bits of values asserted by range checks and constraints
+    /// earlier in the current block. These only hold for the rest of the block.
+    asserted_bit_sizes: HashMap<ValueId, u32>,
+}
+
+impl Context {
+    fn simplify_block(&mut self, dfg: &mut DataFlowGraph, block: BasicBlockId) {
+        let instructions = dfg[block].take_instructions();
+        let mut remaining = Vec::with_capacity(instructions.len());
+
+        for instruction in instructions {
+            if !self.remove_if_redundant(dfg, instruction) {
+                self.record_bit_sizes(dfg, instruction);
+                remaining.push(instruction);
+            }
+        }
+
+        *dfg[block].instructions_mut() = remaining;
+    }
+
+    /// Returns true if the given instruction is a truncation or range check which can be
+    /// removed, replacing the result of a removed truncation with the truncated value.
+    fn remove_if_redundant(&self, dfg: &mut DataFlowGraph, instruction: InstructionId) -> bool {
+        let (value, bit_size) = match dfg[instruction] {
+            Instruction::Truncate { value, bit_size, .. } => (value, bit_size),
+            Instruction::RangeCheck { value, max_bit_size, .. } => {
+                return self.fits(dfg, value, max_bit_size);
+            }
+            _ => return false,
+        };
+
+        if !self.fits(dfg, value, bit_size) {
+            return false;
+        }
+        let result = dfg.instruction_results(instruction)[0];
+        dfg.set_value_from_id(result, value);
+        true
+    }
+
+    /// Records any bounds known from the given instruction, either on its result or on the
+    /// values it asserts facts about.
+    fn record_bit_sizes(&mut self, dfg: &DataFlowGraph, instruction_id: InstructionId) {
+        let instruction = &dfg[instruction_id];
+        match instruction {
+            Instruction::RangeCheck { value, max_bit_size, .. } => {
+                self.assert_bit_size(dfg, *value, *max_bit_size);
+            }
+            Instruction::Constrain(lhs, rhs, _) => {
+                if let Some(constant) = dfg.get_numeric_constant(*rhs) {
+                    self.record_constraint(dfg, *lhs, constant);
+                } else if let Some(constant) = dfg.get_numeric_constant(*lhs) {
+                    self.record_constraint(dfg, *rhs, constant);
+                }
+            }
+            _ => {
+                if let [result] = dfg.instruction_results(instruction_id) {
+                    if let Some(bit_size) = self.result_bit_size(dfg, instruction) {
+                        self.bit_sizes.insert(*result, bit_size);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records the bounds asserted by constraining `value` to equal the given constant.
+    fn record_constraint(&mut self, dfg: &DataFlowGraph, value: ValueId, constant: FieldElement) {
+        self.assert_bit_size(dfg, value, constant.num_bits());
+
+        // Asserting the result of a comparison also bounds the values being compared.
+        let Value::Instruction { instruction, .. } = &dfg[dfg.resolve(value)] else { return };
+        let Instruction::Binary(Binary { lhs, rhs, operator: BinaryOp::Lt }) = &dfg[*instruction]
+        else {
+            return;
+        };
+        if !dfg.type_of_value(*lhs).is_unsigned() {
+            return;
+        }
+
+        if constant.is_one() {
+            // lhs < rhs, so lhs fits within the bits of rhs - 1.
+            let bound = match dfg.get_numeric_constant(*rhs) {
+                Some(rhs) if rhs.is_zero() => None,
+                Some(rhs) => Some((rhs - FieldElement::one()).num_bits()),
+                None => self.bit_size(dfg, *rhs),
+            };
+            if let Some(bound) = bound {
+                self.assert_bit_size(dfg, *lhs, bound);
+            }
+        } else if constant.is_zero() {
+            // rhs <= lhs, so rhs fits within the bits of lhs.
+            if let Some(bound) = self.bit_size(dfg, *lhs) {
+                self.assert_bit_size(dfg, *rhs, bound);
+            }
+        }
+    }
+
+    fn assert_bit_size(&mut self, dfg: &DataFlowGraph, value: ValueId, bit_size: u32) {
+        let value = dfg.resolve(value);
+        if dfg.is_constant(value) {
+            return;
+        }
+        let asserted = self.asserted_bit_sizes.entry(value).or_insert(bit_size);
+        *asserted = (*asserted).min(bit_size);
+    }
+
+    /// Returns an upper bound on the number of bits of the given value, if one is known.
+    fn bit_size(&self, dfg: &DataFlowGraph, value: ValueId) -> Option<u32> {
+        let value = dfg.resolve(value);
+        if let Some(constant) = dfg.get_numeric_constant(value) {
+            return Some(constant.num_bits());
+        }
+
+        let defined = self.bit_sizes.get(&value).copied();
+        let asserted = self.asserted_bit_sizes.get(&value).copied();
+        match (defined, asserted) {
+            (Some(defined), Some(asserted)) => Some(defined.min(asserted)),
+            (defined, asserted) => defined.or(asserted),
+        }
+    }
+
+    /// True if the given value is known to fit within `bit_size` bits.
+    fn fits(&self, dfg: &DataFlowGraph, value: ValueId, bit_size: u32) -> bool {
+        self.bit_size(dfg, value).map_or(false, |value_bit_size| value_bit_size <= bit_size)
+    }
+
+    /// Returns an upper bound on the number of bits of the result of the given instruction.
+    fn result_bit_size(&self, dfg: &DataFlowGraph, instruction: &Instruction) -> Option<u32> {
+        match instruction {
+            Instruction::Truncate { value, bit_size, .. } => {
+                Some(self.bit_size(dfg, *value).map_or(*bit_size, |bits| bits.min(*bit_size)))
+            }
+            Instruction::Cast(value, typ) => {
+                let value_bit_size = self.bit_size(dfg, *value);
+                let Some(target_bit_size) = integer_bit_size(typ) else {
+                    // Casting to a field does not change the value.
+                    return value_bit_size;
+                };
+
+                // Casting to a smaller integer type truncates the value.
+                let incoming_type = dfg.type_of_value(*value);
+                let incoming_bit_size =
+                    integer_bit_size(&incoming_type).unwrap_or(FieldElement::max_num_bits());
+                if incoming_bit_size <= target_bit_size {
+                    value_bit_size
+                } else {
+                    Some(value_bit_size.map_or(target_bit_size, |bits| bits.min(target_bit_size)))
+                }
+            }
+            Instruction::Not(value) => {
+                // Not computes `2^bit_size - 1 - value`, which only fits if the value does.
+                let bit_size = integer_bit_size(&dfg.type_of_value(*value))?;
+                self.fits(dfg, *value, bit_size).then_some(bit_size)
+            }
+            Instruction::Binary(binary) => self.binary_bit_size(dfg, binary),
+            _ => None,
+        }
+    }
+
+    fn binary_bit_size(&self, dfg: &DataFlowGraph, binary: &Binary) -> Option<u32> {
+        let lhs = self.bit_size(dfg, binary.lhs);
+        let rhs = self.bit_size(dfg, binary.rhs);
+        let is_unsigned = dfg.type_of_value(binary.lhs).is_unsigned();
+
+        match binary.operator {
+            BinaryOp::Add => Some(lhs?.max(rhs?) + 1),
+            BinaryOp::Mul => Some(lhs? + rhs?),
+            BinaryOp::Eq | BinaryOp::Lt => Some(1),
+
+            // Subtraction may underflow and wrap around the field.
+            BinaryOp::Sub => None,
+
+            // Only integer division by a known non-zero divisor is bounded by the dividend.
+            BinaryOp::Div | BinaryOp::Mod if !is_unsigned => None,
+            BinaryOp::Div => {
+                let divisor = dfg.get_numeric_constant(binary.rhs)?;
+                (!divisor.is_zero()).then_some(lhs?)
+            }
+            BinaryOp::Mod => {
+                let divisor = dfg.get_numeric_constant(binary.rhs)?;
+                if divisor.is_zero() {
+                    return None;
+                }
+                let remainder_bits = (divisor - FieldElement::one()).num_bits();
+                Some(lhs.map_or(remainder_bits, |lhs| lhs.min(remainder_bits)))
+            }
+
+            BinaryOp::And if is_unsigned => match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+                (lhs, rhs) => lhs.or(rhs),
+            },
+            BinaryOp::Or | BinaryOp::Xor if is_unsigned => Some(lhs?.max(rhs?)),
+            BinaryOp::And | BinaryOp::Or | BinaryOp::Xor => None,
+        }
+    }
+}
+
+/// Returns the bit size of an integer type, or None for any other type.
+fn integer_bit_size(typ: &Type) -> Option<u32> {
+    match typ {
+        Type::Numeric(NumericType::Signed { bit_size } | NumericType::Unsigned { bit_size }) => {
+            Some(*bit_size)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{
+        ir::{instruction::TerminatorInstruction, map::Id},
+        ssa_gen::Ssa,
+    };
+
+    #[test]
+    fn removes_checks_on_values_bounded_by_their_operands() {
+        let src = "acir fn main f0 {
+  b0(v0: u8, v1: u8):
+    v2 = cast v0 as u16
+    v3 = cast v1 as u16
+    v4 = add v2, v3
+    range_check v4 to 16 bits
+    v5 = truncate v4 to 16 bits, max_bit_size: 17
+    v6 = mul v4, v4
+    range_check v6 to 16 bits
+    return v5, v6
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.remove_redundant_range_checks();
+        let main = ssa.main();
+
+        // The sum of two bytes fits in 9 bits, but its square may need 18.
+        let entry = &main.dfg[main.entry_block()];
+        assert_eq!(entry.instructions().len(), 5);
+
+        let Some(TerminatorInstruction::Return { return_values, .. }) = entry.terminator() else {
+            panic!("Expected main to return");
+        };
+        let sum = main.dfg.instruction_results(entry.instructions()[2])[0];
+        assert_eq!(main.dfg.resolve(return_values[0]), sum);
+    }
+
+    #[test]
+    fn uses_bounds_asserted_by_comparisons() {
+        let src = "acir fn main f0 {
+  b0(v0: u8):
+    v1 = lt v0, u8 100
+    constrain v1 == u1 1
+    v2 = add v0, u8 1
+    range_check v2 to 8 bits
+    v3 = add v0, u8 200
+    range_check v3 to 8 bits
+    return v2, v3
+}
+acir fn foo f1 {
+  b0(v0: u8):
+    v1 = add v0, u8 1
+    range_check v1 to 8 bits
+    return v1
+}
+";
+        let ssa: Ssa = src.parse().unwrap();
+        let ssa = ssa.remove_redundant_range_checks();
+
+        // v0 is less than 100 so fits in 7 bits, and v0 + 1 fits in 8 bits. The second
+        // addition may need 9 bits so its check is kept.
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 5);
+
+        // The parameters of other functions are not range constrained as program inputs.
+        let foo = &ssa.functions[&Id::test_new(1)];
+        assert_eq!(foo.dfg[foo.entry_block()].instructions().len(), 2);
+    }
+}
@@ -0,0 +1,92 @@
+//! This module defines a pass which removes each function that can never be called from `main`.
+//!
+//! Inlining keeps every unconstrained and folded function as an entry point of its own, so
+//! without this pass functions which are never called would still be inlined into, optimized
+//! and compiled to bytecode.
+use std::collections::BTreeSet;
+
+use crate::ssa::{
+    ir::{
+        function::{Function, FunctionId},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Removes each function which is not reachable from `main` by following
+    /// the function values referenced by the reachable blocks of each function.
+    pub(crate) fn remove_unreachable_functions(mut self) -> Ssa {
+        let mut reachable = BTreeSet::new();
+        let mut queue = vec![self.main_id];
+
+        while let Some(function_id) = queue.pop() {
+            if reachable.insert(function_id) {
+                queue.extend(used_functions(&self.functions[&function_id]));
+            }
+        }
+
+        self.functions.retain(|function_id, _| reachable.contains(function_id));
+        self
+    }
+}
+
+/// Returns each function referenced by a value within the reachable blocks of the given
+/// function, whether it is called directly or passed around as a value.
+fn used_functions(function: &Function) -> BTreeSet<FunctionId> {
+    let mut used = BTreeSet::new();
+
+    let mut find_functions = |value: ValueId| {
+        if let Value::Function(id) = &function.dfg[function.dfg.resolve(value)] {
+            used.insert(*id);
+        }
+    };
+
+    for block_id in function.reachable_blocks() {
+        let block = &function.dfg[block_id];
+        for instruction in block.instructions() {
+            function.dfg[*instruction].for_each_value(&mut find_functions);
+        }
+        block.unwrap_terminator().for_each_value(&mut find_functions);
+    }
+
+    used
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{ir::map::Id, ssa_gen::Ssa};
+
+    #[test]
+    fn removes_functions_unreachable_from_main() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v2 = call f1(v0) -> Field
+    return v2
+}
+brillig fn square f1 {
+  b0(v0: Field):
+    v2 = call f2(v0) -> Field
+    return v2
+}
+brillig fn multiply f2 {
+  b0(v0: Field):
+    v1 = mul v0, v0
+    return v1
+}
+brillig fn unused f3 {
+  b0(v0: Field):
+    v2 = call f4(v0) -> Field
+    return v2
+}
+acir fn only_called_by_unused f4 {
+  b0(v0: Field):
+    return v0
+}
+";
+        let ssa = src.parse::<Ssa>().unwrap().remove_unreachable_functions();
+
+        let remaining: Vec<_> = ssa.functions.keys().copied().collect();
+        assert_eq!(remaining, vec![Id::test_new(0), Id::test_new(1), Id::test_new(2)]);
+    }
+}
@@ -21,11 +21,10 @@ use crate::{
             basic_block::BasicBlockId,
             cfg::ControlFlowGraph,
             dfg::{CallStack, DataFlowGraph},
-            dom::DominatorTree,
             function::{Function, RuntimeType},
             function_inserter::FunctionInserter,
             instruction::TerminatorInstruction,
-            post_order::PostOrder,
+            loops::{Loop, LoopAnalysis},
             value::ValueId,
         },
         ssa_gen::Ssa,
@@ -54,19 +53,6 @@ impl Ssa {
     }
 }
 
-struct Loop {
-    /// The header block of a loop is the block which dominates all the
-    /// other blocks in the loop.
-    header: BasicBlockId,
-
-    /// The start of the back_edge n -> d is the block n at the end of
-    /// the loop that jumps back to the header block d which restarts the loop.
-    back_edge_start: BasicBlockId,
-
-    /// All the blocks contained within the loop, including `header` and `back_edge_start`.
-    pub(crate) blocks: HashSet<BasicBlockId>,
-}
-
 struct Loops {
     /// The loops that failed to be unrolled so that we do not try to unroll them again.
     /// Each loop is identified by its header block id.
@@ -77,31 +63,12 @@ struct Loops {
     cfg: ControlFlowGraph,
 }
 
-/// Find a loop in the program by finding a node that dominates any predecessor node.
-/// The edge where this happens will be the back-edge of the loop.
+/// Find all loops in the function, along with the control flow graph used to find them.
 fn find_all_loops(function: &Function) -> Loops {
-    let cfg = ControlFlowGraph::with_function(function);
-    let post_order = PostOrder::with_function(function);
-    let mut dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
-
-    let mut loops = vec![];
-
-    for (block, _) in function.dfg.basic_blocks_iter() {
-        // These reachable checks wouldn't be needed if we only iterated over reachable blocks
-        if dom_tree.is_reachable(block) {
-            for predecessor in cfg.predecessors(block) {
-                if dom_tree.is_reachable(predecessor) && dom_tree.dominates(block, predecessor) {
-                    // predecessor -> block is the back-edge of a loop
-                    loops.push(find_blocks_in_loop(block, predecessor, &cfg));
-                }
-            }
-        }
-    }
-
-    // Sort loops by block size so that we unroll the larger, outer loops of nested loops first.
-    // This is needed because inner loops may use the induction variable from their outer loops in
-    // their loop range.
-    loops.sort_by_key(|loop_| loop_.blocks.len());
+    // Loops are sorted by block size, so popping from the end of the list unrolls the larger,
+    // outer loops of nested loops first. This is needed because inner loops may use the
+    // induction variable from their outer loops in their loop range.
+    let LoopAnalysis { loops, cfg, .. } = LoopAnalysis::with_function(function);
 
     Loops {
         failed_to_unroll: HashSet::new(),
@@ -145,37 +112,6 @@ impl Loops {
     }
 }
 
-/// Return each block that is in a loop starting in the given header block.
-/// Expects back_edge_start -> header to be the back edge of the loop.
-fn find_blocks_in_loop(
-    header: BasicBlockId,
-    back_edge_start: BasicBlockId,
-    cfg: &ControlFlowGraph,
-) -> Loop {
-    let mut blocks = HashSet::new();
-    blocks.insert(header);
-
-    let mut insert = |block, stack: &mut Vec<BasicBlockId>| {
-        if !blocks.contains(&block) {
-            blocks.insert(block);
-            stack.push(block);
-        }
-    };
-
-    // Starting from the back edge of the loop, each predecessor of this block until
-    // the header is within the loop.
-    let mut stack = vec![];
-    insert(back_edge_start, &mut stack);
-
-    while let Some(block) = stack.pop() {
-        for predecessor in cfg.predecessors(block) {
-            insert(predecessor, &mut stack);
-        }
-    }
-
-    Loop { header, back_edge_start, blocks }
-}
-
 /// Unroll a single loop in the function.
 /// Returns Err(()) if it failed to unroll and Ok(()) otherwise.
 fn unroll_loop(
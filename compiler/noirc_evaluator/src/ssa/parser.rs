@@ -0,0 +1,972 @@
+//! Parses SSA in the format printed by `--show-ssa` back into an [`Ssa`].
+//!
+//! The call stack of an instruction is read from its trailing `// loc` comment, and is empty if
+//! the comment is omitted. Instructions are inserted exactly as written, without the
+//! simplifications which the [`FunctionBuilder`][super::function_builder::FunctionBuilder]
+//! applies, so that a pass sees the same SSA which was printed.
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    str::FromStr,
+};
+
+use acvm::FieldElement;
+use noirc_errors::{Location, Span};
+use num_bigint::BigUint;
+use thiserror::Error;
+
+use super::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::CallStack,
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{Binary, BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
+        map::AtomicCounter,
+        types::{NumericType, Type},
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+/// The digits used by [`FieldElement`]'s `Display` implementation for powers of two, e.g. `2⁶⁴`.
+const SUPERSCRIPT_DIGITS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹";
+
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("Failed to parse SSA on line {line}: {message}")]
+pub struct SsaParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn error(line: usize, message: impl Into<String>) -> SsaParseError {
+    SsaParseError { line, message: message.into() }
+}
+
+impl FromStr for Ssa {
+    type Err = SsaParseError;
+
+    fn from_str(src: &str) -> Result<Ssa, SsaParseError> {
+        let lines: Vec<(usize, &str)> = src
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+
+        // Functions may be called before they are defined, so their ids are assigned up front.
+        let function_ids = AtomicCounter::<Function>::default();
+        let mut headers = Vec::new();
+        let mut functions_by_name = HashMap::new();
+        for (line_number, line) in &lines {
+            if let Some(header) = FunctionHeader::parse(*line_number, line)? {
+                let id = function_ids.next();
+                if functions_by_name.insert(header.printed_id.clone(), id).is_some() {
+                    let message =
+                        format!("function {} is defined more than once", header.printed_id);
+                    return Err(error(*line_number, message));
+                }
+                headers.push((header, id));
+            }
+        }
+
+        let mut functions = Vec::new();
+        let mut headers = headers.into_iter();
+        let mut current_function: Option<FunctionParser> = None;
+        for (line_number, line) in lines {
+            if line == "}" {
+                let Some(function) = current_function.take() else {
+                    return Err(error(line_number, "unexpected `}`"));
+                };
+                function.check_blocks_are_defined(line_number)?;
+                functions.push(function.function);
+                continue;
+            }
+
+            match &mut current_function {
+                Some(function) => function.parse_line(line_number, line)?,
+                None => {
+                    if FunctionHeader::parse(line_number, line)?.is_none() {
+                        return Err(error(line_number, "expected a function"));
+                    }
+                    let (header, id) = headers.next().expect("each header was parsed up front");
+                    let mut function = Function::new(header.name, id);
+                    function.set_runtime(header.runtime);
+                    function.set_fold(header.fold);
+                    current_function = Some(FunctionParser::new(function, &functions_by_name));
+                }
+            }
+        }
+
+        if current_function.is_some() {
+            return Err(error(src.lines().count(), "expected `}` at the end of the function"));
+        }
+        if functions.is_empty() {
+            return Err(error(1, "expected at least one function"));
+        }
+        Ok(Ssa::new(functions))
+    }
+}
+
+/// The first line of a printed function, e.g. `acir fn main f0 {` or `acir(fold) fn foo f1 {`
+struct FunctionHeader {
+    runtime: RuntimeType,
+    fold: bool,
+    name: String,
+    printed_id: String,
+}
+
+impl FunctionHeader {
+    /// Parses `line` as a function header, returning `None` if it is not one.
+    fn parse(line_number: usize, line: &str) -> Result<Option<FunctionHeader>, SsaParseError> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [runtime, "fn", name @ .., printed_id, "{"] = words.as_slice() else {
+            return Ok(None);
+        };
+        let (runtime, fold) = match *runtime {
+            "acir" => (RuntimeType::Acir, false),
+            "acir(fold)" => (RuntimeType::Acir, true),
+            "brillig" => (RuntimeType::Brillig, false),
+            other => return Err(error(line_number, format!("unknown runtime `{other}`"))),
+        };
+        if name.is_empty() || !is_id(printed_id, 'f') {
+            return Err(error(line_number, "expected a function name followed by its id"));
+        }
+        Ok(Some(FunctionHeader {
+            runtime,
+            fold,
+            name: name.join(" "),
+            printed_id: printed_id.to_string(),
+        }))
+    }
+}
+
+/// Parses the blocks of a single function, one line at a time.
+struct FunctionParser<'a> {
+    function: Function,
+    functions: &'a HashMap<String, FunctionId>,
+    values: HashMap<String, ValueId>,
+    blocks: HashMap<String, BasicBlockId>,
+    defined_blocks: HashSet<BasicBlockId>,
+    /// The block which instructions are currently inserted into, or `None` if the last block
+    /// has been terminated.
+    current_block: Option<BasicBlockId>,
+}
+
+impl<'a> FunctionParser<'a> {
+    fn new(function: Function, functions: &'a HashMap<String, FunctionId>) -> Self {
+        Self {
+            function,
+            functions,
+            values: HashMap::new(),
+            blocks: HashMap::new(),
+            defined_blocks: HashSet::new(),
+            current_block: None,
+        }
+    }
+
+    fn parse_line(&mut self, line_number: usize, line: &str) -> Result<(), SsaParseError> {
+        if line == "(no terminator instruction)" {
+            self.current_block = None;
+            return Ok(());
+        }
+
+        let (line, call_stack) = match line.rsplit_once("// loc ") {
+            Some((line, locations)) => (line.trim_end(), parse_call_stack(line_number, locations)?),
+            None => (line, CallStack::new()),
+        };
+
+        let mut tokens = Tokens::lex(line_number, line)?;
+        if line.ends_with(':') && matches!(tokens.peek_at(1), Some(Token::LeftParen)) {
+            if !call_stack.is_empty() {
+                return Err(tokens.error("blocks do not have a location"));
+            }
+            return self.parse_block_header(&mut tokens);
+        }
+
+        let Some(block) = self.current_block else {
+            return Err(tokens.error("expected a block"));
+        };
+
+        let mut results = Vec::new();
+        if matches!(tokens.peek_at(1), Some(Token::Comma | Token::Equal)) {
+            loop {
+                results.push(tokens.expect_ident()?);
+                if tokens.eat(&Token::Equal) {
+                    break;
+                }
+                tokens.expect(Token::Comma)?;
+            }
+        }
+
+        let opcode = tokens.expect_ident()?;
+        if let Some(terminator) = self.parse_terminator(&opcode, &mut tokens, call_stack.clone())? {
+            if !results.is_empty() {
+                return Err(tokens.error(format!("`{opcode}` does not have any results")));
+            }
+            tokens.finish()?;
+            self.function.dfg.set_block_terminator(block, terminator);
+            self.current_block = None;
+            return Ok(());
+        }
+
+        let (instruction, result_types) = self.parse_instruction(&opcode, &mut tokens)?;
+        tokens.finish()?;
+        self.insert_instruction(block, instruction, result_types, &results, call_stack, &tokens)
+    }
+
+    /// Parses a block's name and parameters, e.g. `b1(v0: Field, v1: u32):`
+    fn parse_block_header(&mut self, tokens: &mut Tokens) -> Result<(), SsaParseError> {
+        let name = tokens.expect_ident()?;
+        // The entry block is always printed first.
+        let block = if self.defined_blocks.is_empty() && self.blocks.is_empty() {
+            let entry_block = self.function.entry_block();
+            self.blocks.insert(name, entry_block);
+            entry_block
+        } else {
+            self.block(&name, tokens)?
+        };
+        if !self.defined_blocks.insert(block) {
+            return Err(tokens.error(format!("block {name} is defined more than once")));
+        }
+
+        tokens.expect(Token::LeftParen)?;
+        if !tokens.eat(&Token::RightParen) {
+            loop {
+                let name = tokens.expect_ident()?;
+                tokens.expect(Token::Colon)?;
+                let typ = tokens.parse_type()?;
+                let parameter = self.function.dfg.add_block_parameter(block, typ);
+                self.define_value(name, parameter, tokens)?;
+                if tokens.eat(&Token::RightParen) {
+                    break;
+                }
+                tokens.expect(Token::Comma)?;
+            }
+        }
+        tokens.expect(Token::Colon)?;
+        tokens.finish()?;
+
+        self.current_block = Some(block);
+        Ok(())
+    }
+
+    /// Parses the terminator starting with `opcode`, returning `None` if `opcode` is not the
+    /// name of a terminator.
+    fn parse_terminator(
+        &mut self,
+        opcode: &str,
+        tokens: &mut Tokens,
+        call_stack: CallStack,
+    ) -> Result<Option<TerminatorInstruction>, SsaParseError> {
+        let terminator = match opcode {
+            "jmp" => {
+                let destination = tokens.expect_ident()?;
+                let destination = self.block(&destination, tokens)?;
+                tokens.expect(Token::LeftParen)?;
+                let arguments = self.parse_arguments(tokens)?;
+                TerminatorInstruction::Jmp { destination, arguments, call_stack }
+            }
+            "jmpif" => {
+                if !call_stack.is_empty() {
+                    return Err(tokens.error("`jmpif` does not have a location"));
+                }
+                let condition = self.parse_value(tokens)?;
+                tokens.expect_keyword("then")?;
+                tokens.expect(Token::Colon)?;
+                let then_destination = tokens.expect_ident()?;
+                let then_destination = self.block(&then_destination, tokens)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("else")?;
+                tokens.expect(Token::Colon)?;
+                let else_destination = tokens.expect_ident()?;
+                let else_destination = self.block(&else_destination, tokens)?;
+                TerminatorInstruction::JmpIf { condition, then_destination, else_destination }
+            }
+            "return" => {
+                let mut return_values = Vec::new();
+                while !tokens.is_empty() {
+                    if !return_values.is_empty() {
+                        tokens.expect(Token::Comma)?;
+                    }
+                    return_values.push(self.parse_value(tokens)?);
+                }
+                TerminatorInstruction::Return { return_values, call_stack }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(terminator))
+    }
+
+    /// Parses the instruction starting with `opcode` along with the types of its results,
+    /// which are only given for instructions whose result types cannot be inferred.
+    fn parse_instruction(
+        &mut self,
+        opcode: &str,
+        tokens: &mut Tokens,
+    ) -> Result<(Instruction, Vec<Type>), SsaParseError> {
+        let instruction = match opcode {
+            "add" | "sub" | "mul" | "div" | "eq" | "mod" | "lt" | "and" | "or" | "xor" => {
+                let operator = match opcode {
+                    "add" => BinaryOp::Add,
+                    "sub" => BinaryOp::Sub,
+                    "mul" => BinaryOp::Mul,
+                    "div" => BinaryOp::Div,
+                    "eq" => BinaryOp::Eq,
+                    "mod" => BinaryOp::Mod,
+                    "lt" => BinaryOp::Lt,
+                    "and" => BinaryOp::And,
+                    "or" => BinaryOp::Or,
+                    _ => BinaryOp::Xor,
+                };
+                let lhs = self.parse_value(tokens)?;
+                tokens.expect(Token::Comma)?;
+                let rhs = self.parse_value(tokens)?;
+                Instruction::Binary(Binary { lhs, rhs, operator })
+            }
+            "cast" => {
+                let value = self.parse_value(tokens)?;
+                tokens.expect_keyword("as")?;
+                Instruction::Cast(value, tokens.parse_type()?)
+            }
+            "not" => Instruction::Not(self.parse_value(tokens)?),
+            "truncate" => {
+                let value = self.parse_value(tokens)?;
+                tokens.expect_keyword("to")?;
+                let bit_size = tokens.expect_number()?;
+                tokens.expect_keyword("bits")?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("max_bit_size")?;
+                tokens.expect(Token::Colon)?;
+                let max_bit_size = tokens.expect_number()?;
+                Instruction::Truncate { value, bit_size, max_bit_size }
+            }
+            "constrain" => {
+                let lhs = self.parse_value(tokens)?;
+                tokens.expect(Token::DoubleEqual)?;
+                let rhs = self.parse_value(tokens)?;
+                Instruction::Constrain(lhs, rhs, tokens.eat_message())
+            }
+            "call" => {
+                let func = self.parse_value(tokens)?;
+                tokens.expect(Token::LeftParen)?;
+                let arguments = self.parse_arguments(tokens)?;
+                Instruction::Call { func, arguments }
+            }
+            "allocate" => Instruction::Allocate,
+            "load" => Instruction::Load { address: self.parse_value(tokens)? },
+            "store" => {
+                let value = self.parse_value(tokens)?;
+                tokens.expect_keyword("at")?;
+                let address = self.parse_value(tokens)?;
+                Instruction::Store { address, value }
+            }
+            "enable_side_effects" => {
+                Instruction::EnableSideEffects { condition: self.parse_value(tokens)? }
+            }
+            "array_get" => {
+                let array = self.parse_value(tokens)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("index")?;
+                let index = self.parse_value(tokens)?;
+                Instruction::ArrayGet { array, index }
+            }
+            "array_set" => {
+                let array = self.parse_value(tokens)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("index")?;
+                let index = self.parse_value(tokens)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("value")?;
+                let value = self.parse_value(tokens)?;
+                Instruction::ArraySet { array, index, value }
+            }
+            "inc_rc" => Instruction::IncrementRc { value: self.parse_value(tokens)? },
+            "dec_rc" => Instruction::DecrementRc { value: self.parse_value(tokens)? },
+            "debug_value" => {
+                tokens.expect_keyword("var")?;
+                let variable = tokens.expect_number()?;
+                let mut values = Vec::new();
+                while tokens.eat(&Token::Comma) {
+                    values.push(self.parse_value(tokens)?);
+                }
+                Instruction::DebugValue { variable, values }
+            }
+            "range_check" => {
+                let value = self.parse_value(tokens)?;
+                tokens.expect_keyword("to")?;
+                let max_bit_size = tokens.expect_number()?;
+                tokens.expect_keyword("bits")?;
+                Instruction::RangeCheck {
+                    value,
+                    max_bit_size,
+                    assert_message: tokens.eat_message(),
+                }
+            }
+            other => return Err(tokens.error(format!("unknown instruction `{other}`"))),
+        };
+
+        let mut result_types = Vec::new();
+        if tokens.eat(&Token::Arrow) {
+            loop {
+                result_types.push(tokens.parse_type()?);
+                if !tokens.eat(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        Ok((instruction, result_types))
+    }
+
+    fn insert_instruction(
+        &mut self,
+        block: BasicBlockId,
+        instruction: Instruction,
+        result_types: Vec<Type>,
+        results: &[String],
+        call_stack: CallStack,
+        tokens: &Tokens,
+    ) -> Result<(), SsaParseError> {
+        let ctrl_typevars = if instruction.requires_ctrl_typevars() {
+            if result_types.len() != results.len() {
+                let message = "expected the type of each result to be given, e.g. `-> Field`";
+                return Err(tokens.error(message));
+            }
+            Some(result_types)
+        } else if !result_types.is_empty() {
+            return Err(tokens.error("the result types of this instruction cannot be given"));
+        } else {
+            None
+        };
+
+        let dfg = &mut self.function.dfg;
+        let instruction = dfg.make_instruction(instruction, ctrl_typevars);
+        dfg[block].insert_instruction(instruction);
+        for location in call_stack {
+            dfg.add_location(instruction, location);
+        }
+
+        let values = dfg.instruction_results(instruction).to_vec();
+        if values.len() != results.len() {
+            let message = format!("expected {} results, found {}", values.len(), results.len());
+            return Err(tokens.error(message));
+        }
+        for (name, value) in results.iter().zip(values) {
+            self.define_value(name.clone(), value, tokens)?;
+        }
+        Ok(())
+    }
+
+    /// Parses a comma separated list of values following a `(`, up to and including the `)`.
+    fn parse_arguments(&mut self, tokens: &mut Tokens) -> Result<Vec<ValueId>, SsaParseError> {
+        let mut arguments = Vec::new();
+        if tokens.eat(&Token::RightParen) {
+            return Ok(arguments);
+        }
+        loop {
+            arguments.push(self.parse_value(tokens)?);
+            if tokens.eat(&Token::RightParen) {
+                return Ok(arguments);
+            }
+            tokens.expect(Token::Comma)?;
+        }
+    }
+
+    /// Parses a value: a previously defined value such as `v3`, a numeric constant such as
+    /// `u32 5`, an array constant such as `[Field 1, v2] of [Field; 2]`, or a function.
+    fn parse_value(&mut self, tokens: &mut Tokens) -> Result<ValueId, SsaParseError> {
+        match tokens.next() {
+            Some(Token::LeftBracket) => {
+                let mut elements = im::Vector::new();
+                if !tokens.eat(&Token::RightBracket) {
+                    loop {
+                        elements.push_back(self.parse_value(tokens)?);
+                        if tokens.eat(&Token::RightBracket) {
+                            break;
+                        }
+                        tokens.expect(Token::Comma)?;
+                    }
+                }
+                tokens.expect_keyword("of")?;
+                let typ = tokens.parse_type()?;
+                if !matches!(typ, Type::Array(..) | Type::Slice(_)) {
+                    return Err(tokens.error(format!("expected an array type, found {typ}")));
+                }
+                Ok(self.function.dfg.make_array(elements, typ))
+            }
+            Some(Token::Ident(name)) => {
+                let is_constant = matches!(tokens.peek(), Some(Token::Number(_)));
+                if let (Some(typ), true) = (numeric_type(&name), is_constant) {
+                    let constant = tokens.expect_field()?;
+                    return Ok(self.function.dfg.make_constant(constant, Type::Numeric(typ)));
+                }
+                self.resolve_value(&name, tokens)
+            }
+            other => Err(tokens.unexpected(other, "a value")),
+        }
+    }
+
+    fn resolve_value(&mut self, name: &str, tokens: &Tokens) -> Result<ValueId, SsaParseError> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(*value);
+        }
+        if let Some(function) = self.functions.get(name) {
+            return Ok(self.function.dfg.import_function(*function));
+        }
+        if is_id(name, 'v') || is_id(name, 'f') {
+            return Err(tokens.error(format!("{name} is not defined")));
+        }
+        Ok(match Intrinsic::lookup(name) {
+            Some(intrinsic) => self.function.dfg.import_intrinsic(intrinsic),
+            None => self.function.dfg.import_foreign_function(name),
+        })
+    }
+
+    fn define_value(
+        &mut self,
+        name: String,
+        value: ValueId,
+        tokens: &Tokens,
+    ) -> Result<(), SsaParseError> {
+        if !is_id(&name, 'v') {
+            return Err(tokens.error(format!("expected a value name such as `v0`, found {name}")));
+        }
+        if self.values.contains_key(&name) {
+            return Err(tokens.error(format!("{name} is defined more than once")));
+        }
+        self.values.insert(name, value);
+        Ok(())
+    }
+
+    /// Returns the block with the given name, creating it if it has not been seen before.
+    fn block(&mut self, name: &str, tokens: &Tokens) -> Result<BasicBlockId, SsaParseError> {
+        if !is_id(name, 'b') {
+            return Err(tokens.error(format!("expected a block name such as `b0`, found {name}")));
+        }
+        if let Some(block) = self.blocks.get(name) {
+            return Ok(*block);
+        }
+        let block = self.function.dfg.make_block();
+        self.blocks.insert(name.to_string(), block);
+        Ok(block)
+    }
+
+    fn check_blocks_are_defined(&self, line_number: usize) -> Result<(), SsaParseError> {
+        let mut undefined_blocks: Vec<&str> = self
+            .blocks
+            .iter()
+            .filter(|(_, block)| !self.defined_blocks.contains(*block))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        undefined_blocks.sort_unstable();
+        match undefined_blocks.first() {
+            Some(name) => Err(error(line_number, format!("block {name} is never defined"))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    /// An assertion message, e.g. `'overflow'`. This always ends the line.
+    Message(String),
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Colon,
+    Semicolon,
+    Equal,
+    DoubleEqual,
+    Arrow,
+}
+
+/// The tokens of a single line.
+struct Tokens {
+    tokens: Vec<Token>,
+    position: usize,
+    line: usize,
+}
+
+impl Tokens {
+    fn lex(line: usize, src: &str) -> Result<Tokens, SsaParseError> {
+        let mut tokens = Vec::new();
+        let mut chars = src.char_indices().peekable();
+        while let Some((start, char)) = chars.next() {
+            let token = match char {
+                ' ' | '\t' => continue,
+                '(' => Token::LeftParen,
+                ')' => Token::RightParen,
+                '[' => Token::LeftBracket,
+                ']' => Token::RightBracket,
+                ',' => Token::Comma,
+                ':' => Token::Colon,
+                ';' => Token::Semicolon,
+                '=' if chars.next_if(|(_, char)| *char == '=').is_some() => Token::DoubleEqual,
+                '=' => Token::Equal,
+                '-' if chars.next_if(|(_, char)| *char == '>').is_some() => Token::Arrow,
+                '\'' => {
+                    // Messages may contain quotes themselves, so the message extends to the
+                    // last quote of the line.
+                    let end = src.rfind('\'').filter(|end| *end > start);
+                    let end = end.ok_or_else(|| error(line, "unterminated message"))?;
+                    tokens.push(Token::Message(src[start + 1..end].to_string()));
+                    if !src[end + 1..].trim().is_empty() {
+                        return Err(error(line, "expected the message to end the line"));
+                    }
+                    break;
+                }
+                '-' | '0'..='9' => {
+                    let mut number = char.to_string();
+                    while let Some((_, char)) = chars.next_if(|(_, char)| is_number_char(*char)) {
+                        number.push(char);
+                    }
+                    Token::Number(number)
+                }
+                char if char.is_alphanumeric() || char == '_' => {
+                    let mut ident = char.to_string();
+                    while let Some((_, char)) =
+                        chars.next_if(|(_, char)| char.is_alphanumeric() || *char == '_')
+                    {
+                        ident.push(char);
+                    }
+                    Token::Ident(ident)
+                }
+                other => return Err(error(line, format!("unexpected character `{other}`"))),
+            };
+            tokens.push(token);
+        }
+        Ok(Tokens { tokens, position: 0, line })
+    }
+
+    fn error(&self, message: impl Into<String>) -> SsaParseError {
+        error(self.line, message)
+    }
+
+    fn unexpected(&self, token: Option<Token>, expected: &str) -> SsaParseError {
+        match token {
+            Some(token) => self.error(format!("expected {expected}, found {token:?}")),
+            None => self.error(format!("expected {expected}, found the end of the line")),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes the next token if it is `token`, returning whether it was consumed.
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn eat_message(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Message(message)) => {
+                let message = message.clone();
+                self.position += 1;
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), SsaParseError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            let found = self.next();
+            Err(self.unexpected(found, &format!("{token:?}")))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, SsaParseError> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            other => Err(self.unexpected(other, "a name")),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SsaParseError> {
+        match self.next() {
+            Some(Token::Ident(ident)) if ident == keyword => Ok(()),
+            other => Err(self.unexpected(other, &format!("`{keyword}`"))),
+        }
+    }
+
+    fn expect_number<T: FromStr>(&mut self) -> Result<T, SsaParseError> {
+        match self.next() {
+            Some(Token::Number(number)) => {
+                number.parse().map_err(|_| self.error(format!("invalid number {number}")))
+            }
+            other => Err(self.unexpected(other, "a number")),
+        }
+    }
+
+    fn expect_field(&mut self) -> Result<FieldElement, SsaParseError> {
+        match self.next() {
+            Some(Token::Number(number)) => {
+                parse_field(&number).ok_or_else(|| self.error(format!("invalid constant {number}")))
+            }
+            other => Err(self.unexpected(other, "a constant")),
+        }
+    }
+
+    /// Parses a type, e.g. `Field`, `[u8; 32]` or `[Field, u32]` for a slice.
+    fn parse_type(&mut self) -> Result<Type, SsaParseError> {
+        match self.next() {
+            Some(Token::Ident(name)) => match name.as_str() {
+                "reference" => Ok(Type::Reference),
+                "function" => Ok(Type::Function),
+                _ => numeric_type(&name)
+                    .map(Type::Numeric)
+                    .ok_or_else(|| self.error(format!("unknown type {name}"))),
+            },
+            Some(Token::LeftBracket) => {
+                let mut elements = vec![self.parse_type()?];
+                loop {
+                    match self.next() {
+                        Some(Token::Comma) => elements.push(self.parse_type()?),
+                        Some(Token::Semicolon) => {
+                            let length = self.expect_number()?;
+                            self.expect(Token::RightBracket)?;
+                            return Ok(Type::Array(Rc::new(elements), length));
+                        }
+                        Some(Token::RightBracket) => return Ok(Type::Slice(Rc::new(elements))),
+                        other => return Err(self.unexpected(other, "`,`, `;` or `]`")),
+                    }
+                }
+            }
+            other => Err(self.unexpected(other, "a type")),
+        }
+    }
+
+    fn finish(&self) -> Result<(), SsaParseError> {
+        match self.peek() {
+            Some(token) => Err(self.error(format!("unexpected {token:?} at the end of the line"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses the locations of a `// loc` comment, e.g. `0:10..25, 1:3..8`, where each location is
+/// a file id followed by the start and end of its span.
+fn parse_call_stack(line: usize, locations: &str) -> Result<CallStack, SsaParseError> {
+    locations
+        .split(',')
+        .map(|location| {
+            let location = location.trim();
+            let parse = || {
+                let (file, span) = location.split_once(':')?;
+                let (start, end) = span.split_once("..")?;
+                let span = Span::from(start.parse().ok()?..end.parse().ok()?);
+                Some(Location::new(span, file.parse::<usize>().ok()?.into()))
+            };
+            parse().ok_or_else(|| error(line, format!("invalid location `{location}`")))
+        })
+        .collect()
+}
+
+fn is_number_char(char: char) -> bool {
+    char.is_ascii_digit() || char == '×' || SUPERSCRIPT_DIGITS.contains(char)
+}
+
+/// Returns whether `name` is an id with the given prefix, e.g. `v3` for the prefix `v`.
+fn is_id(name: &str, prefix: char) -> bool {
+    name.strip_prefix(prefix)
+        .map_or(false, |index| !index.is_empty() && index.chars().all(|char| char.is_ascii_digit()))
+}
+
+fn numeric_type(name: &str) -> Option<NumericType> {
+    if name == "Field" {
+        return Some(NumericType::NativeField);
+    }
+    let bit_size = |bit_size: &str| bit_size.parse().ok().filter(|bit_size| *bit_size > 0);
+    if let Some(bit_size) = name.strip_prefix('u').and_then(bit_size) {
+        Some(NumericType::Unsigned { bit_size })
+    } else {
+        name.strip_prefix('i').and_then(bit_size).map(|bit_size| NumericType::Signed { bit_size })
+    }
+}
+
+/// Parses a field element as printed by its `Display` implementation, which may be negated
+/// and written as a power of two or a multiple of one, e.g. `-1`, `2⁶⁴` or `2³²×3`.
+fn parse_field(number: &str) -> Option<FieldElement> {
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number),
+    };
+    let (base, multiplier) = match number.split_once('×') {
+        Some((base, multiplier)) => (base, Some(multiplier)),
+        None => (number, None),
+    };
+
+    let mut value = match base.strip_prefix('2') {
+        Some(exponent)
+            if !exponent.is_empty() && !exponent.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            let exponent = exponent
+                .chars()
+                .map(|digit| {
+                    SUPERSCRIPT_DIGITS.chars().position(|superscript| superscript == digit)
+                })
+                .try_fold(0u32, |exponent, digit| Some(exponent * 10 + digit? as u32))?;
+            BigUint::from(2u32).pow(exponent)
+        }
+        _ => base.parse::<BigUint>().ok()?,
+    };
+    if let Some(multiplier) = multiplier {
+        value *= multiplier.parse::<BigUint>().ok()?;
+    }
+
+    let value = FieldElement::from_be_bytes_reduce(&value.to_bytes_be());
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+    use noirc_errors::{Location, Span};
+
+    use crate::ssa::{run_passes_on_ssa, ssa_gen::Ssa};
+
+    use super::parse_field;
+
+    fn assert_round_trip(src: &str) {
+        let ssa: Ssa = src.parse().unwrap();
+        assert_eq!(ssa.to_string(), src);
+    }
+
+    #[test]
+    fn round_trips_printed_ssa() {
+        assert_round_trip(
+            "acir fn main f0 {
+  b0(v0: Field, v1: Field):
+    v2 = add v0, v1
+    v4 = mul v2, Field 2
+    constrain v4 == Field 10 'doubled sum must be 10'
+    v7 = call f1(v4) -> Field
+    return v7
+}
+brillig fn foo f1 {
+  b0(v0: Field):
+    v1 = allocate
+    store v0 at v1
+    v2 = load v1 -> Field
+    return v2
+}
+",
+        );
+    }
+
+    #[test]
+    fn round_trips_control_flow_and_arrays() {
+        assert_round_trip(
+            "acir fn main f0 {
+  b0(v0: u1, v1: [Field; 2]):
+    jmpif v0 then: b1, else: b2
+  b1():
+    v3 = array_get v1, index u32 0 -> Field
+    jmp b3(v3)
+  b3(v4: Field):
+    range_check v4 to 8 bits
+    return v4
+  b2():
+    v7 = array_set v1, index u32 1, value Field -1
+    v8 = array_get v7, index u32 1 -> Field
+    jmp b3(v8)
+}
+",
+        );
+    }
+
+    #[test]
+    fn round_trips_reference_counts() {
+        assert_round_trip(
+            "brillig fn main f0 {
+  b0(v0: [Field; 2]):
+    inc_rc v0
+    v3 = array_set v0, index u32 0, value Field 1
+    dec_rc v0
+    return v3
+}
+",
+        );
+    }
+
+    #[test]
+    fn round_trips_call_stacks() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v2 = mul v0, Field 2 // loc 0:40..45, 1:10..15
+    constrain v2 == Field 10 'must be 10' // loc 0:50..70
+    return v2 // loc 0:80..82
+}
+";
+        assert_round_trip(src);
+
+        let ssa: Ssa = src.parse().unwrap();
+        let main = ssa.main();
+        let entry_block = &main.dfg[main.entry_block()];
+        let call_stack = main.dfg.get_call_stack(entry_block.instructions()[0]);
+        let expected_call_stack = vec![
+            Location::new(Span::from(40..45), 0.into()),
+            Location::new(Span::from(10..15), 1.into()),
+        ];
+        assert_eq!(call_stack.into_iter().collect::<Vec<_>>(), expected_call_stack);
+    }
+
+    #[test]
+    fn parses_printed_field_elements() {
+        let two_to_64 = FieldElement::from(2u128.pow(64));
+        assert_eq!(parse_field("2⁶⁴"), Some(two_to_64));
+        assert_eq!(parse_field("2⁶⁴×3"), Some(two_to_64 * FieldElement::from(3u128)));
+        assert_eq!(parse_field("-2⁶⁴"), Some(-two_to_64));
+        assert_eq!(parse_field("25"), Some(FieldElement::from(25u128)));
+        assert_eq!(parse_field("2×"), None);
+    }
+
+    #[test]
+    fn reports_undefined_values() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v1 = add v0, v9
+    return v1
+}";
+        let error = src.parse::<Ssa>().unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.message, "v9 is not defined");
+    }
+
+    #[test]
+    fn runs_passes_on_parsed_ssa() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v1 = allocate
+    store v0 at v1
+    v2 = load v1 -> Field
+    return v2
+}";
+        let ssa = run_passes_on_ssa(src, &["mem2reg"]).unwrap();
+        assert!(!ssa.contains("load"));
+    }
+}
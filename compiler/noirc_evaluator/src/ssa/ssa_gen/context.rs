@@ -36,6 +36,10 @@ use fxhash::FxHashMap as HashMap;
 pub(super) struct FunctionContext<'a> {
     definitions: HashMap<LocalId, Values>,
 
+    /// Parameters of the current function which are stored in mutable variables.
+    /// Their reference counts are incremented on entry and must be decremented before returning.
+    mutable_parameters: Vec<ValueId>,
+
     pub(super) builder: FunctionBuilder,
     shared_context: &'a SharedContext,
 }
@@ -96,7 +100,12 @@ impl<'a> FunctionContext<'a> {
             .1;
 
         let builder = FunctionBuilder::new(function_name, function_id, runtime);
-        let mut this = Self { definitions: HashMap::default(), builder, shared_context };
+        let mut this = Self {
+            definitions: HashMap::default(),
+            mutable_parameters: Vec::new(),
+            builder,
+            shared_context,
+        };
         this.add_parameters_to_scope(parameters);
         this
     }
@@ -108,11 +117,13 @@ impl<'a> FunctionContext<'a> {
     /// avoid calling new_function until the previous function is completely finished with ssa-gen.
     pub(super) fn new_function(&mut self, id: IrFunctionId, func: &ast::Function) {
         self.definitions.clear();
+        self.mutable_parameters.clear();
         if func.unconstrained {
             self.builder.new_brillig_function(func.name.clone(), id);
         } else {
             self.builder.new_function(func.name.clone(), id);
         }
+        self.builder.current_function.set_fold(func.fold);
         self.add_parameters_to_scope(&func.parameters);
     }
 
@@ -137,9 +148,12 @@ impl<'a> FunctionContext<'a> {
         mutable: bool,
     ) {
         // Add a separate parameter for each field type in 'parameter_type'
+        let mut parameter_fields = Vec::new();
         let parameter_value = Self::map_type(parameter_type, |typ| {
             let value = self.builder.add_parameter(typ);
+            parameter_fields.push(value);
             if mutable {
+                self.mutable_parameters.push(value);
                 self.new_mutable_variable(value)
             } else {
                 value.into()
@@ -147,17 +161,56 @@ impl<'a> FunctionContext<'a> {
         });
 
         self.definitions.insert(parameter_id, parameter_value);
+        self.record_debug_value_ids(parameter_id, parameter_fields);
+    }
+
+    /// Records that the variable `id` now holds `values`, provided that the debugger should be
+    /// able to display the variable.
+    pub(super) fn record_debug_value(&mut self, id: LocalId, values: &Values) {
+        if self.shared_context.program.debug_variables.contains(&id.0) {
+            let values = values.clone().into_value_list(self);
+            self.record_debug_value_ids(id, values);
+        }
+    }
+
+    fn record_debug_value_ids(&mut self, id: LocalId, values: Vec<ValueId>) {
+        if !values.is_empty() && self.shared_context.program.debug_variables.contains(&id.0) {
+            self.builder.insert_debug_value(id.0, values);
+        }
     }
 
     /// Allocate a single slot of memory and store into it the given initial value of the variable.
     /// Always returns a Value::Mutable wrapping the allocate instruction.
+    ///
+    /// If the value is an array, its reference count is incremented since it is now shared
+    /// with the variable.
     pub(super) fn new_mutable_variable(&mut self, value_to_store: ValueId) -> Value {
+        self.builder.increment_array_reference_count(value_to_store);
         let alloc = self.builder.insert_allocate();
         self.builder.insert_store(alloc, value_to_store);
         let typ = self.builder.type_of_value(value_to_store);
         Value::Mutable(alloc, typ)
     }
 
+    /// Decrements the reference counts of the mutable parameters of the current function,
+    /// which were incremented when they were stored in their variables.
+    pub(super) fn decrement_mutable_parameters(&mut self) {
+        for parameter in std::mem::take(&mut self.mutable_parameters) {
+            self.builder.decrement_array_reference_count(parameter);
+        }
+    }
+
+    /// Increments the reference count of each array within the given evaluated values.
+    ///
+    /// This is needed whenever an array becomes shared with a mutable variable or reference,
+    /// so that Brillig copies the array instead of mutating it in place on the next assignment.
+    pub(super) fn increment_array_reference_counts(&mut self, values: &Values) {
+        values.clone().for_each(|value| {
+            let value = value.eval(self);
+            self.builder.increment_array_reference_count(value);
+        });
+    }
+
     /// Maps the given type to a Tree of the result type.
     ///
     /// This can be used to (for example) flatten a tuple type, creating
@@ -831,6 +884,9 @@ impl<'a> FunctionContext<'a> {
                     *location,
                     max_length,
                 )?;
+                // A nested array may be shared with other arrays holding it, so it is always
+                // copied before being modified.
+                self.increment_array_reference_counts(&element);
                 Ok((element, index_lvalue))
             }
             ast::LValue::MemberAccess { object, field_index: index } => {
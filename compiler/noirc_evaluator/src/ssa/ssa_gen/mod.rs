@@ -84,6 +84,7 @@ impl<'a> FunctionContext<'a> {
     fn codegen_function_body(&mut self, body: &Expression) -> Result<(), RuntimeError> {
         let return_value = self.codegen_expression(body)?;
         let results = return_value.into_value_list(self);
+        self.decrement_mutable_parameters();
         self.builder.terminate_with_return(results);
         Ok(())
     }
@@ -140,8 +141,18 @@ impl<'a> FunctionContext<'a> {
     }
 
     /// Codegen an identifier, automatically loading its value if it is mutable.
+    ///
+    /// A loaded array is shared with the variable from then on, so its reference count
+    /// is incremented.
     fn codegen_ident(&mut self, ident: &ast::Ident) -> Values {
-        self.codegen_ident_reference(ident).map(|value| value.eval(self).into())
+        self.codegen_ident_reference(ident).map(|value| match value {
+            value::Value::Normal(value) => value.into(),
+            value::Value::Mutable(..) => {
+                let value = value.eval(self);
+                self.builder.increment_array_reference_count(value);
+                value.into()
+            }
+        })
     }
 
     fn codegen_literal(&mut self, literal: &ast::Literal) -> Result<Values, RuntimeError> {
@@ -248,6 +259,7 @@ impl<'a> FunctionContext<'a> {
                 Ok(self.codegen_reference(&unary.rhs)?.map(|rhs| {
                     match rhs {
                         value::Value::Normal(value) => {
+                            self.builder.increment_array_reference_count(value);
                             let alloc = self.builder.insert_allocate();
                             self.builder.insert_store(alloc, value);
                             Tree::Leaf(value::Value::Normal(alloc))
@@ -260,7 +272,9 @@ impl<'a> FunctionContext<'a> {
             }
             noirc_frontend::UnaryOp::Dereference { .. } => {
                 let rhs = self.codegen_expression(&unary.rhs)?;
-                Ok(self.dereference(&rhs, &unary.result_type))
+                let values = self.dereference(&rhs, &unary.result_type);
+                self.increment_array_reference_counts(&values);
+                Ok(values)
             }
         }
     }
@@ -290,8 +304,37 @@ impl<'a> FunctionContext<'a> {
         Ok(self.insert_binary(lhs, binary.operator, rhs, binary.location))
     }
 
+    /// Codegen the collection of an index expression.
+    ///
+    /// Reading an element does not share the collection with anything, so unlike
+    /// `codegen_expression` this does not increment the reference count of an array
+    /// loaded from a mutable variable or a reference.
+    fn codegen_index_collection(
+        &mut self,
+        collection: &Expression,
+    ) -> Result<Values, RuntimeError> {
+        match collection {
+            Expression::Ident(ident) => Ok(self.codegen_ident_reference(ident)),
+            Expression::ExtractTupleField(tuple, index) => {
+                let tuple = self.codegen_index_collection(tuple)?;
+                Ok(Self::get_field(tuple, *index))
+            }
+            Expression::Unary(ast::Unary {
+                operator: noirc_frontend::UnaryOp::Dereference { .. },
+                rhs,
+                result_type,
+                ..
+            }) => {
+                let rhs = self.codegen_expression(rhs)?;
+                Ok(self.dereference(&rhs, result_type))
+            }
+            other => self.codegen_expression(other),
+        }
+    }
+
     fn codegen_index(&mut self, index: &ast::Index) -> Result<Values, RuntimeError> {
-        let array_or_slice = self.codegen_expression(&index.collection)?.into_value_list(self);
+        let array_or_slice =
+            self.codegen_index_collection(&index.collection)?.into_value_list(self);
         let index_value = self.codegen_non_tuple_expression(&index.index)?;
         // Slices are represented as a tuple in the form: (length, slice contents).
         // Thus, slices require two value ids for their representation.
@@ -437,6 +480,7 @@ impl<'a> FunctionContext<'a> {
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
         self.define(for_expr.index_variable, loop_index.into());
+        self.record_debug_value(for_expr.index_variable, &loop_index.into());
         self.codegen_expression(&for_expr.block)?;
         let new_loop_index = self.make_offset(loop_index, 1);
         self.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
@@ -574,6 +618,7 @@ impl<'a> FunctionContext<'a> {
     /// the initial value before returning the allocate instruction.
     fn codegen_let(&mut self, let_expr: &ast::Let) -> Result<Values, RuntimeError> {
         let mut values = self.codegen_expression(&let_expr.expression)?;
+        self.record_debug_value(let_expr.id, &values);
 
         if let_expr.mutable {
             values = values.map(|value| {
@@ -616,8 +661,20 @@ impl<'a> FunctionContext<'a> {
     fn codegen_assign(&mut self, assign: &ast::Assign) -> Result<Values, RuntimeError> {
         let lhs = self.extract_current_value(&assign.lvalue)?;
         let rhs = self.codegen_expression(&assign.expression)?;
+        self.increment_array_reference_counts(&rhs);
+
+        // Only assignments to a whole local variable give the variable's new value
+        let assigned_variable = match &assign.lvalue {
+            ast::LValue::Ident(ast::Ident { definition: ast::Definition::Local(id), .. }) => {
+                Some((*id, rhs.clone()))
+            }
+            _ => None,
+        };
 
         self.assign_new_value(lhs, rhs);
+        if let Some((id, values)) = assigned_variable {
+            self.record_debug_value(id, &values);
+        }
         Ok(Self::unit_value())
     }
 
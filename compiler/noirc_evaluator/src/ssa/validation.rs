@@ -0,0 +1,436 @@
+//! Checks the SSA for broken invariants.
+//!
+//! The optimization passes, as well as ACIR and Brillig generation, rely on invariants of the
+//! SSA which are not enforced by its data structures. A pass which breaks one of them usually
+//! causes a confusing failure much later in the pipeline, so when debugging a pass it is useful
+//! to check the SSA after every pass to pinpoint the one which broke it.
+use std::collections::HashMap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::CallStack,
+        dom::DominatorTree,
+        function::{Function, RuntimeType},
+        instruction::{Instruction, InstructionId, TerminatorInstruction},
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+/// A broken invariant found by [`Ssa::validate`].
+#[derive(Debug)]
+pub(crate) struct SsaValidationError {
+    pub(crate) message: String,
+    pub(crate) call_stack: CallStack,
+}
+
+impl Ssa {
+    /// Checks that every function of the SSA upholds the invariants expected by the optimization
+    /// passes, returning the first broken one.
+    ///
+    /// If `forbid_memory_operations` is set, ACIR functions must also not contain any allocate,
+    /// load or store instructions as ACIR generation is unable to handle them.
+    pub(crate) fn validate(
+        &self,
+        forbid_memory_operations: bool,
+    ) -> Result<(), SsaValidationError> {
+        for function in self.functions.values() {
+            let forbid_memory_operations =
+                forbid_memory_operations && function.runtime() == RuntimeType::Acir;
+            FunctionValidator::new(self, function, forbid_memory_operations).validate()?;
+        }
+        Ok(())
+    }
+}
+
+struct FunctionValidator<'f> {
+    ssa: &'f Ssa,
+    function: &'f Function,
+    forbid_memory_operations: bool,
+    dom_tree: DominatorTree,
+
+    /// The block of each instruction in a reachable block, along with its index in that block.
+    instruction_positions: HashMap<InstructionId, (BasicBlockId, usize)>,
+
+    /// The types of the values returned by the first return terminator found, which every other
+    /// return terminator must agree with.
+    return_types: Option<Vec<Type>>,
+}
+
+impl<'f> FunctionValidator<'f> {
+    fn new(ssa: &'f Ssa, function: &'f Function, forbid_memory_operations: bool) -> Self {
+        Self {
+            ssa,
+            function,
+            forbid_memory_operations,
+            dom_tree: DominatorTree::with_function(function),
+            instruction_positions: HashMap::new(),
+            return_types: None,
+        }
+    }
+
+    fn validate(mut self) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let blocks = function.reachable_blocks();
+
+        for block in &blocks {
+            for (index, instruction) in function.dfg[*block].instructions().iter().enumerate() {
+                self.instruction_positions.insert(*instruction, (*block, index));
+            }
+        }
+
+        for block in blocks {
+            for (index, instruction) in function.dfg[block].instructions().iter().enumerate() {
+                self.validate_instruction(block, index, *instruction)?;
+            }
+            self.validate_terminator(block)?;
+        }
+        Ok(())
+    }
+
+    fn validate_instruction(
+        &mut self,
+        block: BasicBlockId,
+        index: usize,
+        instruction_id: InstructionId,
+    ) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let instruction = &dfg[instruction_id];
+        let call_stack = dfg.get_call_stack(instruction_id);
+
+        let mut operands = Vec::new();
+        instruction.for_each_value(|value| operands.push(value));
+        for operand in operands {
+            self.validate_use(operand, block, Some(index), &call_stack)?;
+        }
+
+        match instruction {
+            Instruction::Binary(binary) => {
+                self.expect_numeric(binary.lhs, &call_stack)?;
+                self.expect_numeric(binary.rhs, &call_stack)?;
+                self.expect_compatible_types(binary.lhs, binary.rhs, &call_stack)
+            }
+            Instruction::Constrain(lhs, rhs, _) => {
+                self.expect_compatible_types(*lhs, *rhs, &call_stack)
+            }
+            Instruction::Cast(value, _)
+            | Instruction::Not(value)
+            | Instruction::Truncate { value, .. }
+            | Instruction::RangeCheck { value, .. } => self.expect_numeric(*value, &call_stack),
+            Instruction::Call { func, arguments } => {
+                self.validate_call(instruction_id, *func, arguments, &call_stack)
+            }
+            Instruction::Allocate => self.expect_memory_operations_allowed("allocate", &call_stack),
+            Instruction::Load { address } => {
+                self.expect_memory_operations_allowed("load", &call_stack)?;
+                self.expect_type(*address, &Type::Reference, &call_stack)
+            }
+            Instruction::Store { address, .. } => {
+                self.expect_memory_operations_allowed("store", &call_stack)?;
+                self.expect_type(*address, &Type::Reference, &call_stack)
+            }
+            Instruction::EnableSideEffects { condition } => {
+                self.expect_type(*condition, &Type::bool(), &call_stack)
+            }
+            Instruction::IncrementRc { value } | Instruction::DecrementRc { value } => {
+                if !matches!(dfg.type_of_value(*value), Type::Array(..) | Type::Slice(_)) {
+                    let message = format!(
+                        "reference count of {value} is changed but it has type {}",
+                        dfg.type_of_value(*value)
+                    );
+                    return self.error(message, call_stack);
+                }
+                Ok(())
+            }
+            Instruction::DebugValue { .. } => Ok(()),
+            Instruction::ArrayGet { array, index } | Instruction::ArraySet { array, index, .. } => {
+                if !matches!(dfg.type_of_value(*array), Type::Array(..) | Type::Slice(_)) {
+                    let message =
+                        format!("{array} is indexed but has type {}", dfg.type_of_value(*array));
+                    return self.error(message, call_stack);
+                }
+                self.expect_numeric(*index, &call_stack)
+            }
+        }
+    }
+
+    fn validate_call(
+        &self,
+        instruction: InstructionId,
+        func: ValueId,
+        arguments: &[ValueId],
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let dfg = &self.function.dfg;
+        match &dfg[dfg.resolve(func)] {
+            Value::Function(id) => {
+                // Existence of the callee is checked along with the other uses.
+                let callee = &self.ssa.functions[id];
+                let parameter_count = callee.parameters().len();
+                if arguments.len() != parameter_count {
+                    let message = format!(
+                        "call to {id} passes {} arguments but it has {parameter_count} parameters",
+                        arguments.len(),
+                    );
+                    return self.error(message, call_stack.clone());
+                }
+                let has_return = callee.reachable_blocks().into_iter().any(|block| {
+                    matches!(
+                        callee.dfg[block].terminator(),
+                        Some(TerminatorInstruction::Return { .. })
+                    )
+                });
+                if has_return {
+                    let return_count = callee.returns().len();
+                    let result_count = dfg.instruction_results(instruction).len();
+                    if result_count != return_count {
+                        let message = format!(
+                            "call to {id} has {result_count} results but it returns {return_count} values"
+                        );
+                        return self.error(message, call_stack.clone());
+                    }
+                }
+                Ok(())
+            }
+            Value::Intrinsic(_) | Value::ForeignFunction(_) => Ok(()),
+            _ => self.expect_type(func, &Type::Function, call_stack),
+        }
+    }
+
+    fn validate_terminator(&mut self, block: BasicBlockId) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let Some(terminator) = function.dfg[block].terminator() else {
+            return self.error(format!("{block} has no terminator"), CallStack::new());
+        };
+
+        let call_stack = match terminator {
+            TerminatorInstruction::Jmp { call_stack, .. }
+            | TerminatorInstruction::Return { call_stack, .. } => call_stack.clone(),
+            TerminatorInstruction::JmpIf { .. } => CallStack::new(),
+        };
+
+        let mut operands = Vec::new();
+        terminator.for_each_value(|value| operands.push(value));
+        for operand in operands {
+            self.validate_use(operand, block, None, &call_stack)?;
+        }
+
+        match terminator {
+            TerminatorInstruction::JmpIf { condition, then_destination, else_destination } => {
+                self.expect_type(*condition, &Type::bool(), &call_stack)?;
+                self.validate_jump(block, *then_destination, &[], &call_stack)?;
+                self.validate_jump(block, *else_destination, &[], &call_stack)
+            }
+            TerminatorInstruction::Jmp { destination, arguments, .. } => {
+                self.validate_jump(block, *destination, arguments, &call_stack)
+            }
+            TerminatorInstruction::Return { return_values, .. } => {
+                let return_types: Vec<Type> =
+                    return_values.iter().map(|value| function.dfg.type_of_value(*value)).collect();
+                match &self.return_types {
+                    Some(expected) if *expected != return_types => {
+                        let message =
+                            format!("return in {block} disagrees with an earlier return on the returned types");
+                        self.error(message, call_stack)
+                    }
+                    Some(_) => Ok(()),
+                    None => {
+                        self.return_types = Some(return_types);
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that the arguments of a jump from `block` match the parameters of `destination`.
+    fn validate_jump(
+        &self,
+        block: BasicBlockId,
+        destination: BasicBlockId,
+        arguments: &[ValueId],
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let dfg = &self.function.dfg;
+        let parameters = dfg.block_parameters(destination);
+        if arguments.len() != parameters.len() {
+            let message = format!(
+                "jump from {block} to {destination} passes {} arguments but it has {} parameters",
+                arguments.len(),
+                parameters.len(),
+            );
+            return self.error(message, call_stack.clone());
+        }
+        for (argument, parameter) in arguments.iter().zip(parameters) {
+            let argument_type = dfg.type_of_value(*argument);
+            let parameter_type = dfg.type_of_value(*parameter);
+            if argument_type != parameter_type {
+                let message = format!(
+                    "jump from {block} passes {argument} of type {argument_type} to {parameter} of type {parameter_type}"
+                );
+                return self.error(message, call_stack.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `value` is defined before its use in `block`.
+    ///
+    /// `index` is the index of the using instruction within `block`, or `None` if `value` is used
+    /// by the terminator of `block`.
+    fn validate_use(
+        &mut self,
+        value: ValueId,
+        block: BasicBlockId,
+        index: Option<usize>,
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let value = self.function.dfg.resolve(value);
+        let is_defined = match &self.function.dfg[value] {
+            Value::Instruction { instruction, .. } => {
+                match self.instruction_positions.get(instruction) {
+                    Some((definition_block, definition_index)) if *definition_block == block => {
+                        index.map_or(true, |index| *definition_index < index)
+                    }
+                    Some((definition_block, _)) => {
+                        self.dom_tree.dominates(*definition_block, block)
+                    }
+                    None => false,
+                }
+            }
+            Value::Param { block: definition_block, .. } => {
+                self.dom_tree.is_reachable(*definition_block)
+                    && self.dom_tree.dominates(*definition_block, block)
+            }
+            Value::Function(id) => {
+                if !self.ssa.functions.contains_key(id) {
+                    let message = format!("{value} refers to {id}, which does not exist");
+                    return self.error(message, call_stack.clone());
+                }
+                true
+            }
+            Value::NumericConstant { .. }
+            | Value::Array { .. }
+            | Value::Intrinsic(_)
+            | Value::ForeignFunction(_) => true,
+        };
+
+        if is_defined {
+            Ok(())
+        } else {
+            let message =
+                format!("{value} is used in {block} but is not defined in a dominating position");
+            self.error(message, call_stack.clone())
+        }
+    }
+
+    fn expect_memory_operations_allowed(
+        &self,
+        instruction: &str,
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        if self.forbid_memory_operations {
+            let message = format!("{instruction} instruction remains in an ACIR function");
+            return self.error(message, call_stack.clone());
+        }
+        Ok(())
+    }
+
+    fn expect_type(
+        &self,
+        value: ValueId,
+        expected: &Type,
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let typ = self.function.dfg.type_of_value(value);
+        if typ != *expected {
+            let message = format!("expected {value} to have type {expected} but it has type {typ}");
+            return self.error(message, call_stack.clone());
+        }
+        Ok(())
+    }
+
+    fn expect_numeric(
+        &self,
+        value: ValueId,
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let typ = self.function.dfg.type_of_value(value);
+        if !matches!(typ, Type::Numeric(_)) {
+            let message = format!("expected {value} to be numeric but it has type {typ}");
+            return self.error(message, call_stack.clone());
+        }
+        Ok(())
+    }
+
+    /// Checks that two operands of an instruction have the same type. As in ACIR generation, a
+    /// `Field` operand is coerced into the type of the other operand.
+    fn expect_compatible_types(
+        &self,
+        lhs: ValueId,
+        rhs: ValueId,
+        call_stack: &CallStack,
+    ) -> Result<(), SsaValidationError> {
+        let lhs_type = self.function.dfg.type_of_value(lhs);
+        let rhs_type = self.function.dfg.type_of_value(rhs);
+        let field = Type::Numeric(NumericType::NativeField);
+        if lhs_type != rhs_type && lhs_type != field && rhs_type != field {
+            let message =
+                format!("operands {lhs} and {rhs} have mismatched types {lhs_type} and {rhs_type}");
+            return self.error(message, call_stack.clone());
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: String, call_stack: CallStack) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let message = format!("in {} ({}): {message}", function.name(), function.id());
+        Err(SsaValidationError { message, call_stack })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::ssa_gen::Ssa;
+
+    #[test]
+    fn reports_uses_not_dominated_by_their_definition() {
+        let src = "acir fn main f0 {
+  b0(v0: u1):
+    jmpif v0 then: b1, else: b2
+  b1():
+    v1 = not v0
+    jmp b3()
+  b2():
+    v2 = not v1
+    jmp b3()
+  b3():
+    return
+}";
+        let ssa: Ssa = src.parse().unwrap();
+        let error = ssa.validate(false).unwrap_err();
+        assert_eq!(
+            error.message,
+            "in main (f0): v1 is used in b2 but is not defined in a dominating position"
+        );
+    }
+
+    #[test]
+    fn forbids_memory_operations_in_acir_functions_only_when_requested() {
+        let src = "acir fn main f0 {
+  b0(v0: Field):
+    v1 = allocate
+    store v0 at v1
+    v2 = load v1 -> Field
+    return v2
+}";
+        let ssa: Ssa = src.parse().unwrap();
+        assert!(ssa.validate(false).is_ok());
+
+        let error = ssa.validate(true).unwrap_err();
+        assert_eq!(error.message, "in main (f0): allocate instruction remains in an ACIR function");
+    }
+}
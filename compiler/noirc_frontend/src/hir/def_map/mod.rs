@@ -162,6 +162,20 @@ impl CrateDefMap {
         })
     }
 
+    /// Go through all modules in this crate, and find all functions in
+    /// each module with the #[bench] attribute
+    pub fn get_all_bench_functions<'a>(
+        &'a self,
+        interner: &'a NodeInterner,
+    ) -> impl Iterator<Item = FuncId> + 'a {
+        self.modules.iter().flat_map(|(_, module)| {
+            module.value_definitions().filter_map(|id| {
+                id.as_function()
+                    .filter(|func_id| interner.function_attributes(func_id).is_bench_function())
+            })
+        })
+    }
+
     /// Go through all modules in this crate, find all `contract ... { ... }` declarations,
     /// and collect them all into a Vec.
     pub fn get_all_contracts(&self, interner: &NodeInterner) -> Vec<Contract> {
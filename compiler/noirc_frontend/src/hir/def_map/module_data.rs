@@ -23,6 +23,9 @@ pub struct ModuleData {
     /// Contains only the definitions directly defined in the current module
     definitions: ItemScope,
 
+    /// Each name brought into scope by a `use` statement, alongside the item it refers to
+    imports: Vec<(Ident, ModuleDefId)>,
+
     pub location: Location,
 
     /// True if this module is a `contract Foo { ... }` module containing contract functions
@@ -36,6 +39,7 @@ impl ModuleData {
             children: HashMap::new(),
             scope: ItemScope::default(),
             definitions: ItemScope::default(),
+            imports: Vec::new(),
             location,
             is_contract,
         }
@@ -105,7 +109,15 @@ impl ModuleData {
     }
 
     pub fn import(&mut self, name: Ident, id: ModuleDefId) -> Result<(), (Ident, Ident)> {
-        self.scope.add_item_to_namespace(name, id, None)
+        self.scope.add_item_to_namespace(name.clone(), id, None)?;
+        self.imports.push((name, id));
+        Ok(())
+    }
+
+    /// Return an iterator over each name imported into this module and the item it refers to.
+    /// An import resolving to both a type and a value is returned once per namespace.
+    pub fn imports(&self) -> impl Iterator<Item = &(Ident, ModuleDefId)> + '_ {
+        self.imports.iter()
     }
 
     pub fn find_name(&self, name: &Ident) -> PerNs {
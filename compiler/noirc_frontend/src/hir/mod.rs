@@ -173,18 +173,45 @@ impl Context {
         let interner = &self.def_interner;
         let def_map = self.def_map(crate_id).expect("The local crate should be analyzed already");
 
-        def_map
+        let test_functions = def_map
             .get_all_test_functions(interner)
-            .filter_map(|test_function| {
-                let fully_qualified_name =
-                    self.fully_qualified_function_name(crate_id, &test_function.get_id());
-                match &pattern {
-                    FunctionNameMatch::Anything => Some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Exact(pattern) => (&fully_qualified_name == pattern)
-                        .then_some((fully_qualified_name, test_function)),
+            .map(|test_function| (test_function.get_id(), test_function));
+        self.functions_matching(crate_id, test_functions, pattern)
+    }
+
+    /// Returns a list of all functions in the current crate marked with #[bench]
+    /// whose names match the given pattern.
+    pub fn get_all_bench_functions_in_crate_matching(
+        &self,
+        crate_id: &CrateId,
+        pattern: FunctionNameMatch,
+    ) -> Vec<(String, FuncId)> {
+        let interner = &self.def_interner;
+        let def_map = self.def_map(crate_id).expect("The local crate should be analyzed already");
+
+        let bench_functions =
+            def_map.get_all_bench_functions(interner).map(|func_id| (func_id, func_id));
+        self.functions_matching(crate_id, bench_functions, pattern)
+    }
+
+    /// Pairs each of the given functions of a crate with its fully qualified name,
+    /// keeping only the functions whose names match the given pattern.
+    fn functions_matching<T>(
+        &self,
+        crate_id: &CrateId,
+        functions: impl Iterator<Item = (FuncId, T)>,
+        pattern: FunctionNameMatch,
+    ) -> Vec<(String, T)> {
+        functions
+            .filter_map(|(func_id, function)| {
+                let fully_qualified_name = self.fully_qualified_function_name(crate_id, &func_id);
+                match pattern {
+                    FunctionNameMatch::Anything => Some((fully_qualified_name, function)),
+                    FunctionNameMatch::Exact(pattern) => (fully_qualified_name == pattern)
+                        .then_some((fully_qualified_name, function)),
                     FunctionNameMatch::Contains(pattern) => fully_qualified_name
                         .contains(pattern)
-                        .then_some((fully_qualified_name, test_function)),
+                        .then_some((fully_qualified_name, function)),
                 }
             })
             .collect()
@@ -20,6 +20,10 @@ pub enum ResolverError {
     DuplicateDefinition { name: String, first_span: Span, second_span: Span },
     #[error("Unused variable")]
     UnusedVariable { ident: Ident },
+    #[error("Unused {item_type}")]
+    UnusedItem { ident: Ident, item_type: &'static str },
+    #[error("Unused import")]
+    UnusedImport { ident: Ident },
     #[error("Could not find variable in this scope")]
     VariableNotDeclared { name: String, span: Span },
     #[error("path is not an identifier")]
@@ -54,6 +58,8 @@ pub enum ResolverError {
     CapturedMutableVariable { span: Span },
     #[error("Test functions are not allowed to have any parameters")]
     TestFunctionHasParameters { span: Span },
+    #[error("Bench functions are not allowed to have any parameters")]
+    BenchFunctionHasParameters { span: Span },
     #[error("Only struct types can be used in constructor expressions")]
     NonStructUsedInConstructor { typ: Type, span: Span },
     #[error("Only struct types can have generics")]
@@ -114,6 +120,24 @@ impl From<ResolverError> for Diagnostic {
                     ident.span(),
                 )
             }
+            ResolverError::UnusedItem { ident, item_type } => {
+                let name = &ident.0.contents;
+
+                Diagnostic::simple_warning(
+                    format!("{item_type} `{name}` is never used"),
+                    format!("unused {item_type}"),
+                    ident.span(),
+                )
+            }
+            ResolverError::UnusedImport { ident } => {
+                let name = &ident.0.contents;
+
+                Diagnostic::simple_warning(
+                    format!("unused import {name}"),
+                    "unused import".to_string(),
+                    ident.span(),
+                )
+            }
             ResolverError::VariableNotDeclared { name, span } => Diagnostic::simple_error(
                 format!("cannot find `{name}` in this scope "),
                 "not found in this scope".to_string(),
@@ -244,6 +268,12 @@ impl From<ResolverError> for Diagnostic {
                 "Try removing the parameters or moving the test into a wrapper function".into(),
                 span,
             ),
+            ResolverError::BenchFunctionHasParameters { span } => Diagnostic::simple_error(
+                "Bench functions cannot have any parameters".into(),
+                "Try removing the parameters or moving the benchmark into a wrapper function"
+                    .into(),
+                span,
+            ),
             ResolverError::NonStructUsedInConstructor { typ, span } => Diagnostic::simple_error(
                 "Only struct types can be used in constructor expressions".into(),
                 format!("{typ} has no fields to construct it with"),
@@ -9,3 +9,4 @@ pub mod errors;
 pub mod import;
 pub mod path_resolver;
 pub mod resolver;
+pub mod unused_items;
@@ -557,6 +557,7 @@ impl<'a> Resolver<'a> {
         // If we cannot find a local generic of the same name, try to look up a global
         match self.path_resolver.resolve(self.def_maps, path.clone()) {
             Ok(ModuleDefId::GlobalId(id)) => {
+                self.record_reference(path, ModuleDefId::GlobalId(id));
                 Some(Type::Constant(self.eval_global_as_array_length(id)))
             }
             _ => None,
@@ -812,6 +813,12 @@ impl<'a> Resolver<'a> {
             });
         }
 
+        if attributes.is_bench_function() && !parameters.is_empty() {
+            self.push_err(ResolverError::BenchFunctionHasParameters {
+                span: func.name_ident().span(),
+            });
+        }
+
         let mut typ = Type::Function(parameter_types, return_type, Box::new(Type::Unit));
 
         if !generics.is_empty() {
@@ -1635,7 +1642,24 @@ impl<'a> Resolver<'a> {
     }
 
     fn resolve_path(&mut self, path: Path) -> Result<ModuleDefId, ResolverError> {
-        self.path_resolver.resolve(self.def_maps, path).map_err(ResolverError::PathResolutionError)
+        let id = self
+            .path_resolver
+            .resolve(self.def_maps, path.clone())
+            .map_err(ResolverError::PathResolutionError)?;
+        self.record_reference(&path, id);
+        Ok(id)
+    }
+
+    /// Remember which item `path` resolved to, and which name it looked up in the current
+    /// module's scope, so that unused items and imports can be reported once resolution is done.
+    fn record_reference(&mut self, path: &Path, id: ModuleDefId) {
+        if path.kind == PathKind::Plain {
+            if let Some(first_segment) = path.segments.first() {
+                let module = self.path_resolver.module_id();
+                self.interner.record_name_reference(module, &first_segment.0.contents);
+            }
+        }
+        self.interner.record_item_reference(id);
     }
 
     fn resolve_block(&mut self, block_expr: BlockExpression) -> HirExpression {
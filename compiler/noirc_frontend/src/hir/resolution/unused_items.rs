@@ -0,0 +1,295 @@
+//! Finds the items of a crate which are never used so that a warning can be issued for each.
+//!
+//! A function is used if it is reachable from one of the crate's entry points: `main`, test
+//! and bench functions, contract functions and trait impl methods. Library crates additionally
+//! treat their public functions and all methods as entry points since they may be called by
+//! dependent crates. Reachability follows every reference to a function within the type checked
+//! HIR, which includes method calls since these are resolved to functions during type checking.
+//!
+//! Structs, globals and imports are used if any path was resolved to them during name resolution.
+//! Structs, globals and imports are only reported outside of libraries, which may export them
+//! instead. Every import is resolved before any is added to its module's scope, so an import can
+//! never be used only by another import.
+use std::collections::HashSet;
+
+use fm::FileId;
+
+use crate::graph::CrateId;
+use crate::hir::def_collector::dc_crate::CompilationError;
+use crate::hir::def_map::{LocalModuleId, ModuleDefId, ModuleId};
+use crate::hir::Context;
+use crate::hir_def::expr::{HirArrayLiteral, HirExpression, HirIdent, HirLiteral};
+use crate::hir_def::stmt::{HirLValue, HirStatement};
+use crate::node_interner::{DefinitionKind, ExprId, FuncId, NodeInterner, StmtId};
+use crate::{FunctionVisibility, Ident};
+
+use super::errors::ResolverError;
+
+/// Returns a warning for each function, struct, global and import of the given crate
+/// which is never used.
+pub fn find_unused_items(context: &Context, crate_id: CrateId) -> Vec<(CompilationError, FileId)> {
+    let Some(def_map) = context.def_map(&crate_id) else {
+        return Vec::new();
+    };
+    let interner = &context.def_interner;
+
+    let main = def_map.main_function();
+    let is_library =
+        main.is_none() && def_map.modules().iter().all(|(_, module)| !module.is_contract);
+
+    // Each struct is given its own module which holds the struct's methods
+    let method_modules: HashSet<LocalModuleId> = def_map
+        .modules()
+        .iter()
+        .flat_map(|(_, module)| module.type_definitions())
+        .filter_map(|definition| definition.as_type())
+        .map(|struct_id| struct_id.local_module_id())
+        .collect();
+
+    let mut reachability = Reachability::new(interner, crate_id);
+    let mut functions = Vec::new();
+
+    for (index, module) in def_map.modules().iter() {
+        let is_method = method_modules.contains(&LocalModuleId(index));
+
+        for func_id in module.value_definitions().filter_map(|definition| definition.as_function())
+        {
+            let attributes = interner.function_attributes(&func_id);
+            let is_exported = is_library
+                && (is_method
+                    || interner.function_visibility(func_id) == FunctionVisibility::Public);
+
+            let is_entry_point = Some(func_id) == main
+                || module.is_contract
+                || attributes.is_test_function()
+                || attributes.is_bench_function()
+                || interner.get_function_trait(&func_id).is_some()
+                || is_exported;
+
+            if is_entry_point {
+                reachability.add_function(func_id);
+            } else {
+                functions.push(func_id);
+            }
+        }
+    }
+    reachability.run();
+
+    let mut errors = Vec::new();
+
+    for func_id in functions {
+        if !reachability.functions.contains(&func_id) {
+            let ident = interner.function_ident(&func_id);
+            let file = interner.function_meta(&func_id).name.location.file;
+            push_unused_item(&mut errors, ident, "function", file);
+        }
+    }
+
+    for (index, module) in def_map.modules().iter() {
+        // Libraries may export any of their structs, globals and imports
+        if is_library {
+            continue;
+        }
+
+        let file = module.location.file;
+
+        for definition in module.type_definitions().chain(module.value_definitions()) {
+            if interner.is_item_referenced(definition) {
+                continue;
+            }
+
+            match definition {
+                ModuleDefId::TypeId(struct_id) => {
+                    let ident = interner.get_struct(struct_id).borrow().name.clone();
+                    push_unused_item(&mut errors, ident, "struct", file);
+                }
+                ModuleDefId::GlobalId(stmt_id) => {
+                    if let Some(global) = interner.get_global(&stmt_id) {
+                        push_unused_item(&mut errors, global.ident, "global", file);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let module_id = ModuleId { krate: crate_id, local_id: LocalModuleId(index) };
+        let mut reported_imports = HashSet::new();
+
+        for (name, item) in module.imports() {
+            // Traits may be imported only to bring their methods into scope
+            if matches!(item, ModuleDefId::TraitId(_))
+                || interner.is_name_referenced(module_id, &name.0.contents)
+            {
+                continue;
+            }
+
+            if reported_imports.insert(name.0.contents.clone()) {
+                let error = ResolverError::UnusedImport { ident: name.clone() };
+                errors.push((error.into(), file));
+            }
+        }
+    }
+
+    errors
+}
+
+fn push_unused_item(
+    errors: &mut Vec<(CompilationError, FileId)>,
+    ident: Ident,
+    item_type: &'static str,
+    file: FileId,
+) {
+    // Like unused variables, items prefixed with an underscore are allowed to be unused
+    if !ident.0.contents.starts_with('_') {
+        errors.push((ResolverError::UnusedItem { ident, item_type }.into(), file));
+    }
+}
+
+/// Finds each function of a crate reachable from a set of entry points by following
+/// the references to functions and globals within their bodies.
+struct Reachability<'interner> {
+    interner: &'interner NodeInterner,
+    crate_id: CrateId,
+
+    /// Every function found to be reachable so far
+    functions: HashSet<FuncId>,
+
+    /// The initializer expressions of each global which has already been visited
+    globals: HashSet<ExprId>,
+
+    /// Reachable functions whose bodies have yet to be visited
+    queue: Vec<FuncId>,
+}
+
+impl<'interner> Reachability<'interner> {
+    fn new(interner: &'interner NodeInterner, crate_id: CrateId) -> Self {
+        Self {
+            interner,
+            crate_id,
+            functions: HashSet::new(),
+            globals: HashSet::new(),
+            queue: Vec::new(),
+        }
+    }
+
+    /// Mark the given function as reachable. Functions from other crates are not followed
+    /// since only the items of the current crate are reported.
+    fn add_function(&mut self, func_id: FuncId) {
+        if self.interner.function_module(func_id).krate == self.crate_id
+            && self.functions.insert(func_id)
+        {
+            self.queue.push(func_id);
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(func_id) = self.queue.pop() {
+            let body = self.interner.function(&func_id);
+            self.visit_expression(*body.as_expr());
+        }
+    }
+
+    fn visit_ident(&mut self, ident: HirIdent) {
+        let interner = self.interner;
+        match interner.try_definition(ident.id).map(|definition| &definition.kind) {
+            Some(DefinitionKind::Function(func_id)) => self.add_function(*func_id),
+            Some(DefinitionKind::Global(expr_id)) => {
+                if self.globals.insert(*expr_id) {
+                    self.visit_expression(*expr_id);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn visit_expressions(&mut self, exprs: &[ExprId]) {
+        for expr in exprs {
+            self.visit_expression(*expr);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: ExprId) {
+        match self.interner.expression(&expr) {
+            HirExpression::Ident(ident) => self.visit_ident(ident),
+            HirExpression::Literal(literal) => match literal {
+                HirLiteral::Array(HirArrayLiteral::Standard(elements)) => {
+                    self.visit_expressions(&elements);
+                }
+                HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, .. }) => {
+                    self.visit_expression(repeated_element);
+                }
+                HirLiteral::FmtStr(_, idents) => self.visit_expressions(&idents),
+                HirLiteral::Bool(_)
+                | HirLiteral::Integer(_)
+                | HirLiteral::Str(_)
+                | HirLiteral::Unit => (),
+            },
+            HirExpression::Block(block) => {
+                for statement in block.statements() {
+                    self.visit_statement(*statement);
+                }
+            }
+            HirExpression::Prefix(prefix) => self.visit_expression(prefix.rhs),
+            HirExpression::Infix(infix) => self.visit_expressions(&[infix.lhs, infix.rhs]),
+            HirExpression::Index(index) => {
+                self.visit_expressions(&[index.collection, index.index]);
+            }
+            HirExpression::Constructor(constructor) => {
+                for (_, field) in constructor.fields {
+                    self.visit_expression(field);
+                }
+            }
+            HirExpression::MemberAccess(access) => self.visit_expression(access.lhs),
+            HirExpression::Call(call) => {
+                self.visit_expression(call.func);
+                self.visit_expressions(&call.arguments);
+            }
+            HirExpression::MethodCall(call) => {
+                self.visit_expression(call.object);
+                self.visit_expressions(&call.arguments);
+            }
+            HirExpression::Cast(cast) => self.visit_expression(cast.lhs),
+            HirExpression::If(if_expr) => {
+                self.visit_expressions(&[if_expr.condition, if_expr.consequence]);
+                if let Some(alternative) = if_expr.alternative {
+                    self.visit_expression(alternative);
+                }
+            }
+            HirExpression::Tuple(elements) => self.visit_expressions(&elements),
+            HirExpression::Lambda(lambda) => self.visit_expression(lambda.body),
+            // Trait methods are only resolved to an impl during monomorphization,
+            // so trait impl methods are all treated as entry points instead.
+            HirExpression::TraitMethodReference(..) | HirExpression::Error => (),
+        }
+    }
+
+    fn visit_statement(&mut self, statement: StmtId) {
+        match self.interner.statement(&statement) {
+            HirStatement::Let(let_statement) => self.visit_expression(let_statement.expression),
+            HirStatement::Constrain(constrain) => self.visit_expression(constrain.0),
+            HirStatement::Assign(assign) => {
+                self.visit_lvalue(&assign.lvalue);
+                self.visit_expression(assign.expression);
+            }
+            HirStatement::For(for_loop) => {
+                self.visit_expressions(&[for_loop.start_range, for_loop.end_range, for_loop.block]);
+            }
+            HirStatement::Expression(expr) | HirStatement::Semi(expr) => {
+                self.visit_expression(expr);
+            }
+            HirStatement::Error => (),
+        }
+    }
+
+    fn visit_lvalue(&mut self, lvalue: &HirLValue) {
+        match lvalue {
+            HirLValue::Ident(..) => (),
+            HirLValue::MemberAccess { object, .. } => self.visit_lvalue(object),
+            HirLValue::Index { array, index, .. } => {
+                self.visit_lvalue(array);
+                self.visit_expression(*index);
+            }
+            HirLValue::Dereference { lvalue, .. } => self.visit_lvalue(lvalue),
+        }
+    }
+}
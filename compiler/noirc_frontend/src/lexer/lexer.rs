@@ -614,6 +614,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bench_attribute() {
+        let input = r#"#[bench]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(token.token(), &Token::Attribute(Attribute::Function(FunctionAttribute::Bench)));
+    }
+
+    #[test]
+    fn fold_attribute() {
+        let input = r#"#[fold]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(token.token(), &Token::Attribute(Attribute::Function(FunctionAttribute::Fold)));
+    }
+
     #[test]
     fn contract_library_method_attribute() {
         let input = r#"#[contract_library_method]"#;
@@ -398,11 +398,19 @@ impl Attributes {
         matches!(self.function, Some(FunctionAttribute::Test(_)))
     }
 
+    pub fn is_bench_function(&self) -> bool {
+        matches!(self.function, Some(FunctionAttribute::Bench))
+    }
+
+    pub fn is_fold(&self) -> bool {
+        matches!(self.function, Some(FunctionAttribute::Fold))
+    }
+
     /// True if these attributes mean the given function is an entry point function if it was
     /// defined within a contract. Note that this does not check if the function is actually part
     /// of a contract.
     pub fn is_contract_entry_point(&self) -> bool {
-        !self.has_contract_library_method() && !self.is_test_function()
+        !self.has_contract_library_method() && !self.is_test_function() && !self.is_bench_function()
     }
 
     /// Returns note if a deprecated secondary attribute is found
@@ -493,6 +501,8 @@ impl Attribute {
                     None => return Err(malformed_scope),
                 }
             }
+            ["bench"] => Attribute::Function(FunctionAttribute::Bench),
+            ["fold"] => Attribute::Function(FunctionAttribute::Fold),
             ["field", name] => {
                 validate(name)?;
                 Attribute::Secondary(SecondaryAttribute::Field(name.to_string()))
@@ -533,6 +543,9 @@ pub enum FunctionAttribute {
     Builtin(String),
     Oracle(String),
     Test(TestScope),
+    Bench,
+    /// The function is compiled into its own circuit which is called rather than inlined.
+    Fold,
 }
 
 impl FunctionAttribute {
@@ -563,6 +576,8 @@ impl fmt::Display for FunctionAttribute {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FunctionAttribute::Test(scope) => write!(f, "#[test{scope}]"),
+            FunctionAttribute::Bench => write!(f, "#[bench]"),
+            FunctionAttribute::Fold => write!(f, "#[fold]"),
             FunctionAttribute::Foreign(ref k) => write!(f, "#[foreign({k})]"),
             FunctionAttribute::Builtin(ref k) => write!(f, "#[builtin({k})]"),
             FunctionAttribute::Oracle(ref k) => write!(f, "#[oracle({k})]"),
@@ -607,6 +622,8 @@ impl AsRef<str> for FunctionAttribute {
             FunctionAttribute::Builtin(string) => string,
             FunctionAttribute::Oracle(string) => string,
             FunctionAttribute::Test { .. } => "",
+            FunctionAttribute::Bench => "",
+            FunctionAttribute::Fold => "",
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::Location;
@@ -202,6 +204,9 @@ pub struct Function {
 
     pub return_type: Type,
     pub unconstrained: bool,
+    /// True if the function is marked `#[fold]`, in which case it is compiled into a separate
+    /// circuit which is called rather than being inlined into its callers.
+    pub fold: bool,
 }
 
 /// Compared to hir_def::types::Type, this monomorphized Type has:
@@ -243,6 +248,9 @@ pub struct Program {
     /// forwarding to the next phase.
     pub return_distinctness: Distinctness,
     pub return_location: Option<Location>,
+    /// The local variables whose values should be recorded in the debug information of the
+    /// compiled program, so that the debugger can display them.
+    pub debug_variables: BTreeSet<u32>,
 }
 
 impl Program {
@@ -252,7 +260,13 @@ impl Program {
         return_distinctness: Distinctness,
         return_location: Option<Location>,
     ) -> Program {
-        Program { functions, main_function_signature, return_distinctness, return_location }
+        Program {
+            functions,
+            main_function_signature,
+            return_distinctness,
+            return_location,
+            debug_variables: BTreeSet::new(),
+        }
     }
 
     pub fn main(&self) -> &Function {
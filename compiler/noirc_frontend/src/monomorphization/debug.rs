@@ -0,0 +1,281 @@
+//! Collection of the variables of monomorphized programs for the debugger.
+//!
+//! When a program is compiled for debugging, the variables and functions outside of the standard
+//! library are recorded in the program's [`DebugInfo`] so that the debugger can map values back
+//! to the variables in the source code. By default the program itself is unchanged: the ACIR and
+//! Brillig generation record where the values of these variables are held.
+//!
+//! The program may instead be instrumented, in which case each instrumented function calls the
+//! [`DEBUG_FN_ENTER_ORACLE`] and [`DEBUG_FN_EXIT_ORACLE`] on entry and exit, and the
+//! [`DEBUG_VAR_ASSIGN_ORACLE`] whenever one of its variables is assigned. The circuit of an
+//! instrumented program differs from that of the original program.
+//!
+//! Oracles may only be called from unconstrained code so each call is made through a separate
+//! unconstrained wrapper function. Keeping the wrappers separate for each call site ensures that
+//! the opcodes generated for them point back to that call site.
+use acvm::FieldElement;
+use iter_extended::vecmap;
+use noirc_errors::{
+    debug_info::{
+        DebugInfo, DebugVariable, DEBUG_FN_ENTER_ORACLE, DEBUG_FN_EXIT_ORACLE,
+        DEBUG_VAR_ASSIGN_ORACLE,
+    },
+    Location,
+};
+use noirc_printable_type::PrintableType;
+
+use crate::{
+    hir_def::{
+        expr::HirIdent,
+        stmt::{HirLValue, HirPattern},
+    },
+    node_interner, Signedness, Type, TypeBinding, TypeVariableKind,
+};
+
+use super::{
+    ast::{self, Definition, FuncId},
+    Monomorphizer,
+};
+
+impl<'interner> Monomorphizer<'interner> {
+    /// Only the variables of functions outside of the standard library are collected, and only
+    /// when the program is being compiled for the debugger.
+    pub(super) fn should_instrument_function(&self, function: node_interner::FuncId) -> bool {
+        self.debug_info.is_some() && !self.interner.function_module(function).krate.is_stdlib()
+    }
+
+    /// Wraps the body of a function so that it reports entering the function along with
+    /// the values of its parameters, and reports exiting the function once the body is evaluated.
+    ///
+    /// The body is returned unchanged, with only the function and its parameters being registered,
+    /// unless the program is being instrumented with calls to the debug oracles.
+    pub(super) fn instrument_function_body(
+        &mut self,
+        id: FuncId,
+        name: &str,
+        location: Location,
+        parameters: &[HirIdent],
+        body: ast::Expression,
+        return_type: &ast::Type,
+    ) -> ast::Expression {
+        let Some(debug_info) = &mut self.debug_info else { return body };
+        debug_info.functions.insert(id.0, name.to_owned());
+        if !self.emit_debug_oracles {
+            for ident in parameters {
+                self.debug_var_assign(ident);
+            }
+            return body;
+        }
+
+        let function_id = (field_literal(id.0, location), ast::Type::Field);
+
+        let mut statements = vec![self.debug_oracle_call(
+            DEBUG_FN_ENTER_ORACLE,
+            vec![function_id.clone()],
+            location,
+        )];
+        statements.extend(parameters.iter().filter_map(|ident| self.debug_var_assign(ident)));
+
+        let result_id = self.next_local_id();
+        let result_name = "__debug_return".to_owned();
+        statements.push(ast::Expression::Let(ast::Let {
+            id: result_id,
+            mutable: false,
+            name: result_name.clone(),
+            expression: Box::new(body),
+        }));
+        statements.push(self.debug_oracle_call(DEBUG_FN_EXIT_ORACLE, vec![function_id], location));
+        statements.push(ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition: Definition::Local(result_id),
+            mutable: false,
+            name: result_name,
+            typ: return_type.clone(),
+        }));
+        ast::Expression::Block(statements)
+    }
+
+    /// Reports the value of the local variable `ident`, which must already be defined,
+    /// registering it as a debug variable.
+    ///
+    /// Returns `None` if the program is not being instrumented with calls to the debug oracles
+    /// or if the variable's type cannot be displayed by the debugger.
+    pub(super) fn debug_var_assign(&mut self, ident: &HirIdent) -> Option<ast::Expression> {
+        if !self.instrument_function {
+            return None;
+        }
+        let typ = debug_printable_type(&self.interner.id_type(ident.id))?;
+        let variable = self.local_ident(ident)?;
+        let Definition::Local(local_id) = variable.definition else { return None };
+
+        let debug_info = self.debug_info.as_mut()?;
+        let function = self.current_function.0;
+        debug_info
+            .variables
+            .insert(local_id.0, DebugVariable { name: variable.name.clone(), typ, function });
+        if !self.emit_debug_oracles {
+            return None;
+        }
+
+        let arguments = vec![
+            (field_literal(local_id.0, ident.location), ast::Type::Field),
+            (ast::Expression::Ident(variable.clone()), variable.typ),
+        ];
+        Some(self.debug_oracle_call(DEBUG_VAR_ASSIGN_ORACLE, arguments, ident.location))
+    }
+
+    /// Reports the new value of the variable `ident` after it has been assigned to,
+    /// provided it was registered as a debug variable when it was defined.
+    pub(super) fn debug_var_reassign(&mut self, ident: &HirIdent) -> Option<ast::Expression> {
+        if !self.instrument_function || self.lookup_captured_lvalue(ident.id).is_some() {
+            return None;
+        }
+        let Some(Definition::Local(local_id)) = self.lookup_local(ident.id) else { return None };
+        if !self.debug_info.as_ref()?.variables.contains_key(&local_id.0) {
+            return None;
+        }
+        self.debug_var_assign(ident)
+    }
+
+    /// Calls the oracle `name` with `arguments` through a new unconstrained wrapper function.
+    fn debug_oracle_call(
+        &mut self,
+        name: &str,
+        arguments: Vec<(ast::Expression, ast::Type)>,
+        location: Location,
+    ) -> ast::Expression {
+        let (argument_values, argument_types): (Vec<_>, Vec<_>) = arguments.into_iter().unzip();
+
+        let mut parameters = Vec::with_capacity(argument_types.len());
+        for (index, typ) in argument_types.iter().enumerate() {
+            parameters.push((self.next_local_id(), false, format!("arg{index}"), typ.clone()));
+        }
+        let oracle_arguments = vecmap(&parameters, |(id, mutable, name, typ)| {
+            ast::Expression::Ident(ast::Ident {
+                location: None,
+                definition: Definition::Local(*id),
+                mutable: *mutable,
+                name: name.clone(),
+                typ: typ.clone(),
+            })
+        });
+
+        let function_type = |argument_types| {
+            ast::Type::Function(
+                argument_types,
+                Box::new(ast::Type::Unit),
+                Box::new(ast::Type::Unit),
+            )
+        };
+        let oracle = ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition: Definition::Oracle(name.to_owned()),
+            mutable: false,
+            name: name.to_owned(),
+            typ: function_type(argument_types.clone()),
+        });
+        let body = ast::Expression::Call(ast::Call {
+            func: Box::new(oracle),
+            arguments: oracle_arguments,
+            return_type: ast::Type::Unit,
+            location,
+        });
+
+        let wrapper_id = self.next_function_id();
+        self.push_function(
+            wrapper_id,
+            ast::Function {
+                id: wrapper_id,
+                name: name.to_owned(),
+                parameters,
+                body,
+                return_type: ast::Type::Unit,
+                unconstrained: true,
+                fold: false,
+            },
+        );
+
+        let wrapper = ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition: Definition::Function(wrapper_id),
+            mutable: false,
+            name: name.to_owned(),
+            typ: function_type(argument_types),
+        });
+        ast::Expression::Semi(Box::new(ast::Expression::Call(ast::Call {
+            func: Box::new(wrapper),
+            arguments: argument_values,
+            return_type: ast::Type::Unit,
+            location,
+        })))
+    }
+}
+
+/// Returns every identifier bound by `pattern`.
+pub(super) fn pattern_idents(pattern: &HirPattern) -> Vec<HirIdent> {
+    match pattern {
+        HirPattern::Identifier(ident) => vec![*ident],
+        HirPattern::Mutable(pattern, _) => pattern_idents(pattern),
+        HirPattern::Tuple(patterns, _) => patterns.iter().flat_map(pattern_idents).collect(),
+        HirPattern::Struct(_, fields, _) => {
+            fields.iter().flat_map(|(_, pattern)| pattern_idents(pattern)).collect()
+        }
+    }
+}
+
+/// Returns the variable which is (partially) assigned to by `lvalue`,
+/// unless it is assigned through a reference.
+pub(super) fn lvalue_ident(lvalue: &HirLValue) -> Option<&HirIdent> {
+    match lvalue {
+        HirLValue::Ident(ident, _) => Some(ident),
+        HirLValue::MemberAccess { object, .. } => lvalue_ident(object),
+        HirLValue::Index { array, .. } => lvalue_ident(array),
+        HirLValue::Dereference { .. } => None,
+    }
+}
+
+/// Converts `typ` into the [`PrintableType`] used by the debugger to decode values of the type,
+/// returning `None` for types which cannot be decoded such as slices, tuples and functions.
+fn debug_printable_type(typ: &Type) -> Option<PrintableType> {
+    match typ {
+        Type::FieldElement => Some(PrintableType::Field),
+        Type::Bool => Some(PrintableType::Boolean),
+        Type::Integer(Signedness::Unsigned, width) => {
+            Some(PrintableType::UnsignedInteger { width: *width })
+        }
+        Type::Integer(Signedness::Signed, width) => {
+            Some(PrintableType::SignedInteger { width: *width })
+        }
+        Type::String(length) => Some(PrintableType::String { length: length.evaluate_to_u64()? }),
+        Type::Array(length, element) => {
+            let length = length.evaluate_to_u64()?;
+            let typ = Box::new(debug_printable_type(element)?);
+            Some(PrintableType::Array { length, typ })
+        }
+        Type::Struct(definition, generics) => {
+            let struct_type = definition.borrow();
+            let fields = struct_type
+                .get_fields(generics)
+                .into_iter()
+                .map(|(name, typ)| debug_printable_type(&typ).map(|typ| (name, typ)))
+                .collect::<Option<_>>()?;
+            Some(PrintableType::Struct { name: struct_type.name.to_string(), fields })
+        }
+        Type::TypeVariable(binding, TypeVariableKind::IntegerOrField) => match &*binding.borrow() {
+            TypeBinding::Bound(typ) => debug_printable_type(typ),
+            TypeBinding::Unbound(_) => debug_printable_type(&Type::default_int_type()),
+        },
+        Type::TypeVariable(binding, _) | Type::NamedGeneric(binding, _) => {
+            match &*binding.borrow() {
+                TypeBinding::Bound(typ) => debug_printable_type(typ),
+                TypeBinding::Unbound(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn field_literal(value: u32, location: Location) -> ast::Expression {
+    let value = FieldElement::from(value as u128);
+    ast::Expression::Literal(ast::Literal::Integer(value, ast::Type::Field, location))
+}
@@ -10,7 +10,7 @@
 //! function, will monomorphize the entire reachable program.
 use acvm::FieldElement;
 use iter_extended::{btree_map, vecmap};
-use noirc_errors::Location;
+use noirc_errors::{debug_info::DebugInfo, Location};
 use noirc_printable_type::PrintableType;
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
@@ -31,8 +31,10 @@ use crate::{
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
+use self::debug::{lvalue_ident, pattern_idents};
 
 pub mod ast;
+mod debug;
 pub mod printer;
 
 struct LambdaContext {
@@ -76,6 +78,20 @@ struct Monomorphizer<'interner> {
     is_range_loop: bool,
 
     return_location: Option<Location>,
+
+    /// Set when the program is being compiled for the debugger, collecting the variables
+    /// and functions of the program.
+    debug_info: Option<DebugInfo>,
+
+    /// Whether the variables collected in `debug_info` are reported through calls to the debug
+    /// oracles. Otherwise the ACIR and Brillig generation record where they are held instead.
+    emit_debug_oracles: bool,
+
+    /// Whether the function currently being monomorphized is being instrumented for the debugger
+    instrument_function: bool,
+
+    /// The id of the function currently being monomorphized
+    current_function: FuncId,
 }
 
 type HirType = crate::Type;
@@ -92,7 +108,48 @@ type HirType = crate::Type;
 /// this function. Typically, this is the function named "main" in the source project,
 /// but it can also be, for example, an arbitrary test function for running `nargo test`.
 pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Program {
+    monomorphize_program(&mut Monomorphizer::new(interner), main)
+}
+
+/// Monomorphize the program as [`monomorphize`] does, additionally instrumenting every function
+/// outside of the standard library with calls to the debug oracles which report the values of
+/// its variables to the debugger.
+///
+/// The returned [`DebugInfo`] maps the variable and function ids passed to the oracles
+/// back to their names in the source code.
+///
+/// Note that the instrumented program differs from the one which [`monomorphize`] returns, so its
+/// circuit cannot be used to create proofs for the original program.
+pub fn monomorphize_debug(
+    main: node_interner::FuncId,
+    interner: &NodeInterner,
+) -> (Program, DebugInfo) {
+    let mut monomorphizer = Monomorphizer::new(interner);
+    monomorphizer.debug_info = Some(DebugInfo::default());
+    monomorphizer.emit_debug_oracles = true;
+    let program = monomorphize_program(&mut monomorphizer, main);
+    (program, monomorphizer.debug_info.unwrap_or_default())
+}
+
+/// Monomorphize the program as [`monomorphize`] does, additionally collecting the variables and
+/// functions outside of the standard library into the returned [`DebugInfo`].
+///
+/// The program is left unchanged apart from its [`Program::debug_variables`], which ask the ACIR
+/// and Brillig generation to record where the values of these variables are held.
+pub fn monomorphize_debug_variables(
+    main: node_interner::FuncId,
+    interner: &NodeInterner,
+) -> (Program, DebugInfo) {
     let mut monomorphizer = Monomorphizer::new(interner);
+    monomorphizer.debug_info = Some(DebugInfo::default());
+    let mut program = monomorphize_program(&mut monomorphizer, main);
+    let debug_info = monomorphizer.debug_info.unwrap_or_default();
+    program.debug_variables = debug_info.variables.keys().copied().collect();
+    (program, debug_info)
+}
+
+fn monomorphize_program(monomorphizer: &mut Monomorphizer, main: node_interner::FuncId) -> Program {
+    let interner = monomorphizer.interner;
     let function_sig = monomorphizer.compile_main(main);
 
     while !monomorphizer.queue.is_empty() {
@@ -104,7 +161,7 @@ pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Pro
         undo_instantiation_bindings(bindings);
     }
 
-    let functions = vecmap(monomorphizer.finished_functions, |(_, f)| f);
+    let functions = vecmap(std::mem::take(&mut monomorphizer.finished_functions), |(_, f)| f);
     let FuncMeta { return_distinctness, .. } = interner.function_meta(&main);
     Program::new(functions, function_sig, return_distinctness, monomorphizer.return_location)
 }
@@ -122,6 +179,10 @@ impl<'interner> Monomorphizer<'interner> {
             lambda_envs_stack: Vec::new(),
             is_range_loop: false,
             return_location: None,
+            debug_info: None,
+            emit_debug_oracles: false,
+            instrument_function: false,
+            current_function: Program::main_id(),
         }
     }
 
@@ -229,12 +290,32 @@ impl<'interner> Monomorphizer<'interner> {
             _ => meta.return_type(),
         });
 
+        self.current_function = id;
+        self.instrument_function = self.should_instrument_function(f);
+        let parameter_idents = if self.instrument_function {
+            meta.parameters.0.iter().flat_map(|parameter| pattern_idents(&parameter.0)).collect()
+        } else {
+            Vec::new()
+        };
+
         let parameters = self.parameters(meta.parameters);
-        let body = self.expr(body_expr_id);
+        let mut body = self.expr(body_expr_id);
+        if self.instrument_function {
+            body = self.instrument_function_body(
+                id,
+                &name,
+                meta.location,
+                &parameter_idents,
+                body,
+                &return_type,
+            );
+        }
         let unconstrained = modifiers.is_unconstrained
             || matches!(modifiers.contract_function_type, Some(ContractFunctionType::Open));
+        let fold = self.interner.function_attributes(&f).is_fold();
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, fold };
         self.push_function(id, function);
     }
 
@@ -447,7 +528,11 @@ impl<'interner> Monomorphizer<'interner> {
                 let index_variable = self.next_local_id();
                 self.define_local(for_loop.identifier.id, index_variable);
 
-                let block = Box::new(self.expr(for_loop.block));
+                let mut block = self.expr(for_loop.block);
+                if let Some(debug_var_assign) = self.debug_var_assign(&for_loop.identifier) {
+                    block = ast::Expression::Block(vec![debug_var_assign, block]);
+                }
+                let block = Box::new(block);
 
                 ast::Expression::For(ast::For {
                     index_variable,
@@ -469,7 +554,21 @@ impl<'interner> Monomorphizer<'interner> {
     fn let_statement(&mut self, let_statement: HirLetStatement) -> ast::Expression {
         let expr = self.expr(let_statement.expression);
         let expected_type = self.interner.id_type(let_statement.expression);
-        self.unpack_pattern(let_statement.pattern, expr, &expected_type)
+        if !self.instrument_function {
+            return self.unpack_pattern(let_statement.pattern, expr, &expected_type);
+        }
+
+        let idents = pattern_idents(&let_statement.pattern);
+        let let_expr = self.unpack_pattern(let_statement.pattern, expr, &expected_type);
+        let debug_var_assigns: Vec<_> =
+            idents.iter().filter_map(|ident| self.debug_var_assign(ident)).collect();
+        if debug_var_assigns.is_empty() {
+            return let_expr;
+        }
+
+        let mut statements = vec![let_expr];
+        statements.extend(debug_var_assigns);
+        ast::Expression::Block(statements)
     }
 
     fn constructor(
@@ -1076,9 +1175,15 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn assign(&mut self, assign: HirAssignStatement) -> ast::Expression {
+        let assigned_ident = lvalue_ident(&assign.lvalue).copied();
         let expression = Box::new(self.expr(assign.expression));
         let lvalue = self.lvalue(assign.lvalue);
-        ast::Expression::Assign(ast::Assign { expression, lvalue })
+        let assign = ast::Expression::Assign(ast::Assign { expression, lvalue });
+
+        match assigned_ident.and_then(|ident| self.debug_var_reassign(&ident)) {
+            Some(debug_var_assign) => ast::Expression::Block(vec![assign, debug_var_assign]),
+            None => assign,
+        }
     }
 
     fn lvalue(&mut self, lvalue: HirLValue) -> ast::LValue {
@@ -1132,7 +1237,8 @@ impl<'interner> Monomorphizer<'interner> {
         let name = lambda_name.to_owned();
         let unconstrained = false;
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, fold: false };
         self.push_function(id, function);
 
         let typ =
@@ -1246,7 +1352,8 @@ impl<'interner> Monomorphizer<'interner> {
         parameters.append(&mut converted_parameters);
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, fold: false };
         self.push_function(id, function);
 
         let lambda_value =
@@ -1364,7 +1471,8 @@ impl<'interner> Monomorphizer<'interner> {
         let name = lambda_name.to_owned();
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, fold: false };
         self.push_function(id, function);
 
         ast::Expression::Ident(ast::Ident {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use arena::{Arena, Index};
 use fm::FileId;
@@ -8,7 +8,7 @@ use noirc_errors::{Location, Span, Spanned};
 use crate::ast::Ident;
 use crate::graph::CrateId;
 use crate::hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias};
-use crate::hir::def_map::{LocalModuleId, ModuleId};
+use crate::hir::def_map::{LocalModuleId, ModuleDefId, ModuleId};
 use crate::hir::StorageSlot;
 use crate::hir_def::stmt::HirLetStatement;
 use crate::hir_def::traits::TraitImpl;
@@ -129,6 +129,14 @@ pub struct NodeInterner {
 
     // For trait implementation functions, this is their self type and trait they belong to
     func_id_to_trait: HashMap<FuncId, (Type, TraitId)>,
+
+    /// Every item a path was resolved to during name resolution.
+    /// Used to report structs and globals which are never used.
+    referenced_items: HashSet<ModuleDefId>,
+
+    /// Each name that was looked up in the scope of a module as the first segment of a path.
+    /// Used to report imports which are never used.
+    referenced_names: HashSet<(ModuleId, String)>,
 }
 
 /// Represents the methods on a given type that each share the same name.
@@ -396,6 +404,8 @@ impl Default for NodeInterner {
             function_modifiers: HashMap::new(),
             function_modules: HashMap::new(),
             func_id_to_trait: HashMap::new(),
+            referenced_items: HashSet::new(),
+            referenced_names: HashSet::new(),
             id_to_location: HashMap::new(),
             definitions: vec![],
             id_to_type: HashMap::new(),
@@ -838,6 +848,26 @@ impl NodeInterner {
         self.globals.clone()
     }
 
+    /// Records that a path was resolved to the given item.
+    pub fn record_item_reference(&mut self, item: ModuleDefId) {
+        self.referenced_items.insert(item);
+    }
+
+    /// Records that `name` was looked up in the scope of `module` while resolving a path.
+    pub fn record_name_reference(&mut self, module: ModuleId, name: &str) {
+        self.referenced_names.insert((module, name.to_owned()));
+    }
+
+    /// True if any path was resolved to the given item.
+    pub fn is_item_referenced(&self, item: ModuleDefId) -> bool {
+        self.referenced_items.contains(&item)
+    }
+
+    /// True if any path resolved from within `module` started with the given name.
+    pub fn is_name_referenced(&self, module: ModuleId, name: &str) -> bool {
+        self.referenced_names.contains(&(module, name.to_owned()))
+    }
+
     /// Returns the type of an item stored in the Interner or Error if it was not found.
     pub fn id_type(&self, index: impl Into<Index>) -> Type {
         self.id_to_type.get(&index.into()).cloned().unwrap_or(Type::Error)
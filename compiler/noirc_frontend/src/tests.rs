@@ -16,6 +16,7 @@ mod test {
     use crate::hir::def_map::ModuleData;
     use crate::hir::resolution::errors::ResolverError;
     use crate::hir::resolution::import::PathResolutionError;
+    use crate::hir::resolution::unused_items::find_unused_items;
     use crate::hir::type_check::TypeCheckError;
     use crate::hir::Context;
     use crate::node_interner::{NodeInterner, StmtId};
@@ -24,7 +25,7 @@ mod test {
     use crate::hir::def_collector::dc_crate::DefCollector;
     use crate::hir_def::expr::HirExpression;
     use crate::hir_def::stmt::HirStatement;
-    use crate::monomorphization::monomorphize;
+    use crate::monomorphization::{monomorphize, monomorphize_debug, monomorphize_debug_variables};
     use crate::parser::ParserErrorReason;
     use crate::ParsedModule;
     use crate::{
@@ -1098,6 +1099,63 @@ mod test {
         assert!(format!("{}", program) == expected);
     }
 
+    #[test]
+    fn debug_instrumentation_registers_variables_and_functions() {
+        let src = r#"
+        fn main(x: Field) -> pub Field {
+            let mut y = x + 1;
+            y = y * 2;
+            y
+        }
+        "#;
+        let (_program, context, _errors) = get_program(src);
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let (program, debug_info) = monomorphize_debug(main_func_id, &context.def_interner);
+
+        let variable_names: Vec<_> =
+            debug_info.variables.values().map(|variable| variable.name.as_str()).collect();
+        assert_eq!(variable_names, vec!["x", "y"]);
+        assert_eq!(debug_info.functions.values().collect::<Vec<_>>(), vec!["main"]);
+
+        // Each oracle call is made through its own unconstrained wrapper: entering and exiting
+        // `main`, assigning `x` and `y`, and reassigning `y`.
+        let wrappers = program.functions.iter().filter(|function| function.unconstrained).count();
+        assert_eq!(wrappers, 5);
+    }
+
+    #[test]
+    fn debug_variables_are_registered_without_instrumentation() {
+        let src = r#"
+        fn main(x: Field) -> pub Field {
+            let y = double(x);
+            y + 1
+        }
+
+        fn double(a: Field) -> Field {
+            a * 2
+        }
+        "#;
+        let (_program, context, _errors) = get_program(src);
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let (program, debug_info) =
+            monomorphize_debug_variables(main_func_id, &context.def_interner);
+
+        assert!(program.functions.iter().all(|function| !function.unconstrained));
+        assert_eq!(
+            program.debug_variables.iter().collect::<Vec<_>>(),
+            debug_info.variables.keys().collect::<Vec<_>>()
+        );
+
+        let variables: Vec<_> = debug_info
+            .variables
+            .values()
+            .map(|variable| {
+                (variable.name.as_str(), debug_info.functions[&variable.function].as_str())
+            })
+            .collect();
+        assert_eq!(variables, vec![("x", "main"), ("y", "main"), ("a", "double")]);
+    }
+
     #[test]
     fn simple_closure_with_no_captured_variables() {
         let src = r#"
@@ -1128,4 +1186,145 @@ fn lambda$f1(mut env$l1: (Field)) -> Field {
 "#;
         check_rewrite(src, expected_rewrite);
     }
+
+    fn get_unused_items(src: &str) -> Vec<String> {
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let crate_id = *context.root_crate_id();
+        let mut unused: Vec<_> = find_unused_items(&context, crate_id)
+            .into_iter()
+            .map(|(error, _)| match error {
+                CompilationError::ResolverError(ResolverError::UnusedItem { ident, item_type }) => {
+                    format!("{item_type} {}", ident.0.contents)
+                }
+                CompilationError::ResolverError(ResolverError::UnusedImport { ident }) => {
+                    format!("import {}", ident.0.contents)
+                }
+                other => panic!("Expected only unused item warnings, got: {other:?}"),
+            })
+            .collect();
+        unused.sort();
+        unused
+    }
+
+    #[test]
+    fn warns_about_items_unreachable_from_main() {
+        let src = r#"
+        mod foo {
+            pub fn bar() -> Field { 1 }
+            pub fn baz() -> Field { 2 }
+        }
+
+        use foo::bar;
+        use foo::baz;
+
+        struct Used { x: Field }
+        struct Unused { x: Field }
+
+        impl Used {
+            fn get(self) -> Field { self.x }
+            fn unused_method(self) -> Field { self.x }
+        }
+
+        global LENGTH = 2;
+        global UNUSED_GLOBAL = 3;
+
+        fn helper(used: Used) -> Field {
+            used.get() + bar()
+        }
+
+        fn dead() -> Field {
+            dead_callee()
+        }
+
+        fn dead_callee() -> Field { 0 }
+
+        fn _ignored() {}
+
+        fn main(x: Field) -> pub Field {
+            let array: [Field; LENGTH] = [x, x];
+            helper(Used { x: array[0] })
+        }
+        "#;
+
+        let expected = vec![
+            "function baz",
+            "function dead",
+            "function dead_callee",
+            "function unused_method",
+            "global UNUSED_GLOBAL",
+            "import baz",
+            "struct Unused",
+        ];
+        assert_eq!(get_unused_items(src), expected);
+    }
+
+    #[test]
+    fn library_crates_only_warn_about_private_functions() {
+        let src = r#"
+        struct Exported { x: Field }
+
+        impl Exported {
+            fn method(self) -> Field { self.x }
+        }
+
+        pub fn api() -> Field {
+            private_helper()
+        }
+
+        fn private_helper() -> Field { 1 }
+
+        fn unused_helper() {}
+
+        #[test]
+        fn test_api() {
+            assert(api() == 1);
+        }
+        "#;
+
+        assert_eq!(get_unused_items(src), vec!["function unused_helper"]);
+    }
+
+    #[test]
+    fn library_crates_do_not_warn_about_imports() {
+        let src = r#"
+        mod foo {
+            pub fn bar() -> Field { 1 }
+        }
+
+        use foo::bar;
+
+        pub fn api() -> Field { 2 }
+        "#;
+
+        assert!(get_unused_items(src).is_empty());
+    }
+
+    #[test]
+    fn imports_cannot_refer_to_other_imports() {
+        // If this ever resolves, `use foo::bar` must be counted as used by `find_unused_items`
+        let src = r#"
+        mod foo {
+            mod bar {
+                pub fn baz() -> Field { 1 }
+            }
+        }
+
+        use foo::bar;
+        use bar::baz;
+
+        fn main() -> pub Field {
+            baz()
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert!(errors.iter().any(|(error, _)| matches!(
+            error,
+            CompilationError::DefinitionError(DefCollectorErrorKind::PathResolutionError(
+                PathResolutionError::Unresolved(ident)
+            )) if ident.0.contents == "bar"
+        )));
+    }
 }
@@ -29,6 +29,10 @@ export type DependencyGraph = {
     library_dependencies: Readonly<Record<string, readonly string[]>>;
 }
 
+export type CompileOptions = {
+    force_brillig?: boolean;
+};
+
 export type CompiledContract = {
     noir_version: string;
     name: string;
@@ -68,6 +72,10 @@ extern "C" {
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub type JsDependencyGraph;
 
+    #[wasm_bindgen(extends = Object, js_name = "CompileOptions", typescript_type = "CompileOptions")]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub type JsCompileOptions;
+
     #[wasm_bindgen(extends = Object, js_name = "CompileResult", typescript_type = "CompileResult")]
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub type JsCompileResult;
@@ -126,6 +134,12 @@ struct DependencyGraph {
     library_dependencies: HashMap<CrateName, Vec<CrateName>>,
 }
 
+#[derive(Deserialize, Default)]
+struct WasmCompileOptions {
+    #[serde(default)]
+    force_brillig: bool,
+}
+
 pub enum CompileResult {
     Contract { contract: PreprocessedContract, debug: DebugArtifact },
     Program { program: PreprocessedProgram, debug: DebugArtifact },
@@ -136,6 +150,7 @@ pub fn compile(
     entry_point: String,
     contracts: Option<bool>,
     dependency_graph: Option<JsDependencyGraph>,
+    compile_options: Option<JsCompileOptions>,
 ) -> Result<JsCompileResult, JsCompileError> {
     console_error_panic_hook::set_once();
 
@@ -146,6 +161,13 @@ pub fn compile(
         DependencyGraph { root_dependencies: vec![], library_dependencies: HashMap::new() }
     };
 
+    let wasm_options: WasmCompileOptions = if let Some(compile_options) = compile_options {
+        <JsValue as JsValueSerdeExt>::into_serde(&JsValue::from(compile_options))
+            .map_err(|err| err.to_string())?
+    } else {
+        WasmCompileOptions::default()
+    };
+
     let root = Path::new("/");
     let fm = FileManager::new(root, Box::new(get_non_stdlib_asset));
     let graph = CrateGraph::default();
@@ -156,7 +178,8 @@ pub fn compile(
 
     process_dependency_graph(&mut context, dependency_graph);
 
-    let compile_options = CompileOptions::default();
+    let compile_options =
+        CompileOptions { force_brillig: wasm_options.force_brillig, ..CompileOptions::default() };
 
     // For now we default to plonk width = 3, though we can add it as a parameter
     let np_language = acvm::Language::PLONKCSat { width: 3 };
@@ -245,6 +268,7 @@ fn preprocess_program(program: CompiledProgram) -> CompileResult {
         abi: program.abi,
         noir_version: NOIR_ARTIFACT_VERSION_STRING.to_string(),
         bytecode: program.circuit,
+        functions: program.functions,
     };
 
     CompileResult::Program { program: preprocessed_program, debug: debug_artifact }
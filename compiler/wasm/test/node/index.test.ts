@@ -33,6 +33,20 @@ describe('noir wasm compilation', () => {
       expect(wasmCircuit.program.abi).to.deep.eq(cliCircuit.abi);
       expect(wasmCircuit.program.backend).to.eq(cliCircuit.backend);
     }).timeout(10e3);
+
+    it('forcing brillig changes the compiled circuit', async () => {
+      const wasmCircuit = await compile(join(__dirname, simpleScriptSourcePath));
+      const brilligCircuit = await compile(join(__dirname, simpleScriptSourcePath), false, undefined, {
+        force_brillig: true,
+      });
+
+      if (!('program' in wasmCircuit) || !('program' in brilligCircuit)) {
+        throw Error('Expected program to be present');
+      }
+
+      expect(brilligCircuit.program.abi).to.deep.eq(wasmCircuit.program.abi);
+      expect(brilligCircuit.program.bytecode).to.not.eq(wasmCircuit.program.bytecode);
+    }).timeout(10e3);
   });
 
   describe('can compile scripts with dependencies', () => {
@@ -1,16 +1,26 @@
+use acvm::acir::brillig::{ForeignCallResult, Value};
 use acvm::acir::circuit::{Opcode, OpcodeLocation};
+use acvm::acir::native_types::Witness;
+use acvm::brillig_vm::Registers;
 use acvm::pwg::{
-    ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, StepResult, ACVM,
+    ACVMStatus, AcirCallWaitInfo, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo,
+    StepResult, ACVM,
 };
-use acvm::BlackBoxFunctionSolver;
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
 
+use codespan_reporting::files::Files;
+use fm::FileId;
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::errors::{ExecutionError, Location};
-use nargo::ops::ForeignCallExecutor;
+use nargo::ops::{execute_acir_call, ForeignCallExecutor, ForeignCallExecutorSnapshot};
 use nargo::NargoError;
 
-use std::collections::{hash_set::Iter, HashSet};
+use noirc_printable_type::{PrintableType, PrintableValue};
+
+use std::collections::{hash_set::Iter, BTreeMap, HashSet};
+
+use crate::vars::DebugVars;
 
 #[derive(Debug)]
 pub(super) enum DebugCommandResult {
@@ -20,30 +30,135 @@ pub(super) enum DebugCommandResult {
     Error(NargoError),
 }
 
+/// A line of source code, identified by its file and zero-based line index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct SourceLine {
+    pub(super) file: FileId,
+    pub(super) line_index: usize,
+}
+
+/// A step of execution which is recorded so that it can be replayed.
+#[derive(Debug, Clone, Copy)]
+enum ExecutionStep {
+    /// Executes a single ACIR or Brillig opcode.
+    Opcode,
+    /// Executes an ACIR opcode, including all of the opcodes of a Brillig block.
+    AcirOpcode,
+    /// Overwrites the value of a witness.
+    SetWitness(Witness, FieldElement),
+    /// Overwrites the value of a register of the Brillig VM.
+    SetBrilligRegister(usize, FieldElement),
+    /// Overwrites a value in the memory of the Brillig VM.
+    WriteBrilligMemory(usize, FieldElement),
+}
+
+/// A step of execution along with the location of the opcode which was about to be executed.
+struct HistoryEntry {
+    step: ExecutionStep,
+    location: Option<OpcodeLocation>,
+}
+
+/// The number of steps of execution between the snapshots of the state of the program.
+const SNAPSHOT_INTERVAL: usize = 100;
+
+/// The number of registers of the Brillig VM, past which it panics when a register is written.
+const MAX_BRILLIG_REGISTERS: usize = 1 << 16;
+
+/// The number of values by which writing to the memory of the Brillig VM may grow it,
+/// so that a mistyped address does not allocate a huge amount of memory.
+const BRILLIG_MEMORY_GROWTH_LIMIT: usize = 1 << 16;
+
+/// The state of the program after a number of steps of execution.
+struct Snapshot<'a, B: BlackBoxFunctionSolver> {
+    step_count: usize,
+    acvm: ACVM<'a, B>,
+    brillig_solver: Option<BrilligSolver<'a, B>>,
+    debug_vars: DebugVars<'a>,
+    last_source_line: Option<SourceLine>,
+    foreign_call_cursor: usize,
+}
+
+/// The result of a foreign call made by the program, along with the state of the foreign call
+/// executor once it has handled the call.
+struct LoggedForeignCall {
+    function: String,
+    result: ForeignCallResult,
+    executor_state: ForeignCallExecutorSnapshot,
+}
+
+/// Travelling back in time is implemented by restoring the latest snapshot of the state of the
+/// program taken before the point to travel to, then replaying the steps executed since. Replaying
+/// is deterministic as the results of foreign calls are recorded and reused rather than requested
+/// again.
 pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver> {
     acvm: ACVM<'a, B>,
+    blackbox_solver: &'a B,
+    circuit: &'a Circuit,
+    /// The circuits which may be called from `circuit`, which are executed without stepping through them
+    functions: &'a [Circuit],
+    initial_witness: WitnessMap,
     brillig_solver: Option<BrilligSolver<'a, B>>,
     foreign_call_executor: ForeignCallExecutor,
     debug_artifact: &'a DebugArtifact,
-    show_output: bool,
     breakpoints: HashSet<OpcodeLocation>,
+    source_breakpoints: HashSet<SourceLine>,
+    /// The opcodes whose innermost source location lies on each line of source code
+    source_to_opcodes: BTreeMap<SourceLine, Vec<OpcodeLocation>>,
+    /// The source line of the last executed opcode which has a source location,
+    /// used so that a source breakpoint is only hit when execution enters its line
+    last_source_line: Option<SourceLine>,
+    debug_vars: DebugVars<'a>,
+    /// The steps executed since the start of the program
+    history: Vec<HistoryEntry>,
+    /// The snapshots taken every [`SNAPSHOT_INTERVAL`] steps, in order
+    snapshots: Vec<Snapshot<'a, B>>,
+    /// The results of the foreign calls made since the start of the program
+    foreign_call_log: Vec<LoggedForeignCall>,
+    /// The number of logged foreign call results which have been used by the current execution
+    foreign_call_cursor: usize,
+    /// The state of the foreign call executor before the program made any foreign calls
+    initial_executor_state: ForeignCallExecutorSnapshot,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     pub(super) fn new(
         blackbox_solver: &'a B,
         circuit: &'a Circuit,
+        functions: &'a [Circuit],
         debug_artifact: &'a DebugArtifact,
         initial_witness: WitnessMap,
+        foreign_call_executor: ForeignCallExecutor,
     ) -> Self {
-        Self {
-            acvm: ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness),
+        let mut context = Self {
+            acvm: ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness.clone()),
+            blackbox_solver,
+            circuit,
+            functions,
+            initial_witness,
             brillig_solver: None,
-            foreign_call_executor: ForeignCallExecutor::default(),
+            initial_executor_state: foreign_call_executor.snapshot(),
+            foreign_call_executor,
             debug_artifact,
-            show_output: true,
             breakpoints: HashSet::new(),
-        }
+            source_breakpoints: HashSet::new(),
+            source_to_opcodes: build_source_to_opcodes(debug_artifact),
+            last_source_line: None,
+            debug_vars: DebugVars::new(&debug_artifact.debug_symbols[0]),
+            history: Vec::new(),
+            snapshots: Vec::new(),
+            foreign_call_log: Vec::new(),
+            foreign_call_cursor: 0,
+        };
+        context.update_debug_vars();
+        context
+    }
+
+    /// Takes the foreign call executor out of the context, resetting it so that it can be
+    /// used by a new context when the debugging session is restarted.
+    pub(super) fn take_foreign_call_executor(&mut self) -> ForeignCallExecutor {
+        let mut foreign_call_executor = std::mem::take(&mut self.foreign_call_executor);
+        foreign_call_executor.reset();
+        foreign_call_executor
     }
 
     pub(super) fn get_opcodes(&self) -> &[Opcode] {
@@ -75,6 +190,18 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
             .and_then(|location| self.debug_artifact.debug_symbols[0].opcode_location(location))
     }
 
+    /// Returns the source line of the innermost location in `call_stack`
+    /// along with the depth of the call stack.
+    fn source_position(&self, call_stack: &[Location]) -> Option<(SourceLine, usize)> {
+        let location = call_stack.last()?;
+        let line_index = self.debug_artifact.location_line_index(*location).ok()?;
+        Some((SourceLine { file: location.file, line_index }, call_stack.len()))
+    }
+
+    fn get_current_source_position(&self) -> Option<(SourceLine, usize)> {
+        self.get_current_source_location().and_then(|call_stack| self.source_position(&call_stack))
+    }
+
     fn step_brillig_opcode(&mut self) -> DebugCommandResult {
         let Some(mut solver) = self.brillig_solver.take() else {
             unreachable!("Missing Brillig solver");
@@ -106,11 +233,9 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 
     fn handle_foreign_call(&mut self, foreign_call: ForeignCallWaitInfo) -> DebugCommandResult {
-        let foreign_call_result =
-            self.foreign_call_executor.execute(&foreign_call, self.show_output);
-        match foreign_call_result {
+        match self.foreign_call_result(&foreign_call) {
             Ok(foreign_call_result) => {
-                self.acvm.resolve_pending_foreign_call(foreign_call_result);
+                self.resolve_foreign_call(foreign_call_result);
                 // TODO: should we retry executing the opcode somehow in this case?
                 DebugCommandResult::Ok
             }
@@ -118,10 +243,63 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
     }
 
+    /// Returns the result of a foreign call made by the program, reusing the result of the call
+    /// from a previous execution if it is being replayed and logging it otherwise.
+    fn foreign_call_result(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, NargoError> {
+        if let Some(result) = self.debug_vars.handle_foreign_call(foreign_call) {
+            return Ok(result);
+        }
+
+        if let Some(logged_call) = self.foreign_call_log.get(self.foreign_call_cursor) {
+            if logged_call.function == foreign_call.function {
+                let result = logged_call.result.clone();
+                self.foreign_call_cursor += 1;
+                return Ok(result);
+            }
+            self.discard_later_foreign_calls();
+        }
+
+        let foreign_call_result = self.foreign_call_executor.execute(foreign_call)?;
+        self.foreign_call_log.push(LoggedForeignCall {
+            function: foreign_call.function.clone(),
+            result: foreign_call_result.clone(),
+            executor_state: self.foreign_call_executor.snapshot(),
+        });
+        self.foreign_call_cursor += 1;
+        Ok(foreign_call_result)
+    }
+
+    /// Discards the logged results of the foreign calls which have not been replayed yet, as
+    /// execution has diverged from the logged execution, and restores the foreign call executor
+    /// to its state at the current point of execution.
+    fn discard_later_foreign_calls(&mut self) {
+        self.foreign_call_log.truncate(self.foreign_call_cursor);
+        let executor_state = self
+            .foreign_call_log
+            .last()
+            .map_or(&self.initial_executor_state, |logged_call| &logged_call.executor_state);
+        self.foreign_call_executor.restore(executor_state);
+    }
+
+    fn resolve_foreign_call(&mut self, foreign_call_result: ForeignCallResult) {
+        // When stepping through a Brillig block the solver is held by the context
+        // rather than by the ACVM.
+        match &mut self.brillig_solver {
+            Some(solver) => solver.resolve_pending_foreign_call(foreign_call_result),
+            None => self.acvm.resolve_pending_foreign_call(foreign_call_result),
+        }
+    }
+
     fn handle_acvm_status(&mut self, status: ACVMStatus) -> DebugCommandResult {
         if let ACVMStatus::RequiresForeignCall(foreign_call) = status {
             return self.handle_foreign_call(foreign_call);
         }
+        if let ACVMStatus::RequiresAcirCall(acir_call) = status {
+            return self.handle_acir_call(acir_call);
+        }
 
         match status {
             ACVMStatus::Solved => DebugCommandResult::Done,
@@ -141,10 +319,109 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
             ACVMStatus::RequiresForeignCall(_) => {
                 unreachable!("Unexpected pending foreign call resolution");
             }
+            ACVMStatus::RequiresAcirCall(_) => {
+                unreachable!("Unexpected pending ACIR call resolution");
+            }
+        }
+    }
+
+    /// Executes the callee of an ACIR call in one step, as there is no debug information for it.
+    ///
+    /// The foreign calls made by the callee are logged and replayed like those of the caller.
+    fn handle_acir_call(&mut self, acir_call: AcirCallWaitInfo) -> DebugCommandResult {
+        let call_location = OpcodeLocation::Acir(self.acvm.instruction_pointer());
+        let (blackbox_solver, functions) = (self.blackbox_solver, self.functions);
+        let return_values = execute_acir_call(
+            blackbox_solver,
+            functions,
+            &acir_call,
+            call_location,
+            &mut |foreign_call| self.foreign_call_result(foreign_call),
+        );
+        match return_values {
+            Ok(return_values) => {
+                self.acvm.resolve_pending_acir_call(return_values);
+                self.handle_acvm_status(self.acvm.get_status().clone())
+            }
+            Err(error) => DebugCommandResult::Error(error),
         }
     }
 
     pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
+        self.execute_step(ExecutionStep::Opcode)
+    }
+
+    pub(super) fn step_acir_opcode(&mut self) -> DebugCommandResult {
+        self.execute_step(ExecutionStep::AcirOpcode)
+    }
+
+    /// Executes `step`, recording it in the history unless execution has already finished.
+    fn execute_step(&mut self, step: ExecutionStep) -> DebugCommandResult {
+        let location = self.get_current_opcode_location();
+        let recorded =
+            location.is_some() && !matches!(self.acvm.get_status(), ACVMStatus::Failure(_));
+        if recorded {
+            self.history.push(HistoryEntry { step, location });
+        }
+        let result = self.replay_step(step);
+        if recorded && self.history.len() % SNAPSHOT_INTERVAL == 0 {
+            self.snapshots.push(Snapshot {
+                step_count: self.history.len(),
+                acvm: self.acvm.clone(),
+                brillig_solver: self.brillig_solver.clone(),
+                debug_vars: self.debug_vars.clone(),
+                last_source_line: self.last_source_line,
+                foreign_call_cursor: self.foreign_call_cursor,
+            });
+        }
+        result
+    }
+
+    fn replay_step(&mut self, step: ExecutionStep) -> DebugCommandResult {
+        let result = match step {
+            ExecutionStep::Opcode => self.execute_opcode(),
+            ExecutionStep::AcirOpcode => self.execute_acir_opcode(),
+            ExecutionStep::SetWitness(witness, value) => {
+                self.acvm.overwrite_witness(witness, value);
+                DebugCommandResult::Ok
+            }
+            ExecutionStep::SetBrilligRegister(register_index, value) => {
+                if let Some(solver) = &mut self.brillig_solver {
+                    solver.set_register(register_index, value.into());
+                }
+                DebugCommandResult::Ok
+            }
+            ExecutionStep::WriteBrilligMemory(ptr, value) => {
+                if let Some(solver) = &mut self.brillig_solver {
+                    solver.write_memory_at(ptr, value.into());
+                }
+                DebugCommandResult::Ok
+            }
+        };
+        self.update_debug_vars();
+        result
+    }
+
+    /// Applies the variable assignments recorded in the debug info which have taken place
+    /// by the current point of execution.
+    fn update_debug_vars(&mut self) {
+        let location = self.get_current_opcode_location();
+        let brillig = self
+            .brillig_solver
+            .as_ref()
+            .map(|solver| (solver.get_registers(), solver.get_memory()));
+        self.debug_vars.apply_assignments(location, self.acvm.witness_map(), brillig);
+    }
+
+    /// Applies a modification of the state of the program as a step of execution, so that it is
+    /// reapplied when travelling back in time to a later point.
+    fn modify_state(&mut self, step: ExecutionStep) {
+        // Execution may diverge from here so the logged results of later foreign calls are stale.
+        self.discard_later_foreign_calls();
+        self.execute_step(step);
+    }
+
+    fn execute_opcode(&mut self) -> DebugCommandResult {
         if self.brillig_solver.is_some() {
             return self.step_brillig_opcode();
         }
@@ -158,7 +435,7 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
     }
 
-    pub(super) fn step_acir_opcode(&mut self) -> DebugCommandResult {
+    fn execute_acir_opcode(&mut self) -> DebugCommandResult {
         let status = if let Some(solver) = self.brillig_solver.take() {
             self.acvm.finish_brillig_with_solver(solver)
         } else {
@@ -167,16 +444,59 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         self.handle_acvm_status(status)
     }
 
-    pub(super) fn next(&mut self) -> DebugCommandResult {
-        let start_location = self.get_current_source_location();
+    /// Steps into opcodes until reaching an opcode with a source location
+    /// whose line and call stack depth satisfy `should_stop`.
+    fn step_until_source_position(
+        &mut self,
+        should_stop: impl Fn(SourceLine, usize) -> bool,
+    ) -> DebugCommandResult {
         loop {
             let result = self.step_into_opcode();
             if !matches!(result, DebugCommandResult::Ok) {
                 return result;
             }
-            let new_location = self.get_current_source_location();
-            if new_location.is_some() && new_location != start_location {
-                return DebugCommandResult::Ok;
+            if let Some((line, depth)) = self.get_current_source_position() {
+                if should_stop(line, depth) {
+                    return DebugCommandResult::Ok;
+                }
+            }
+        }
+    }
+
+    /// Steps to the next source line, stepping over any inlined function calls.
+    pub(super) fn next(&mut self) -> DebugCommandResult {
+        match self.get_current_source_position() {
+            Some((start_line, start_depth)) => self.step_until_source_position(|line, depth| {
+                depth < start_depth || (depth == start_depth && line != start_line)
+            }),
+            None => self.step_until_source_position(|_, _| true),
+        }
+    }
+
+    /// Steps to the next source line, stepping into any inlined function calls.
+    pub(super) fn step_in(&mut self) -> DebugCommandResult {
+        match self.get_current_source_position() {
+            Some(start) => self.step_until_source_position(|line, depth| (line, depth) != start),
+            None => self.step_until_source_position(|_, _| true),
+        }
+    }
+
+    /// Steps until execution returns from the current inlined function to its caller.
+    pub(super) fn step_out(&mut self) -> DebugCommandResult {
+        match self.get_current_source_position() {
+            Some((_, start_depth)) => {
+                self.step_until_source_position(|_, depth| depth < start_depth)
+            }
+            None => self.step_until_source_position(|_, _| true),
+        }
+    }
+
+    /// Continues execution until the end of the program, ignoring any breakpoints.
+    pub(super) fn finish(&mut self) -> DebugCommandResult {
+        loop {
+            match self.step_into_opcode() {
+                DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_) => (),
+                result => return result,
             }
         }
     }
@@ -190,12 +510,156 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
     }
 
-    fn breakpoint_reached(&self) -> bool {
-        if let Some(location) = self.get_current_opcode_location() {
-            self.breakpoints.contains(&location)
-        } else {
-            false
+    /// Returns the number of steps executed since the start of the program.
+    pub(super) fn executed_steps(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Restores the state of the program after its first `step_count` steps by restoring
+    /// the latest snapshot taken by then and replaying the steps executed since.
+    fn rewind_to(&mut self, step_count: usize) {
+        let mut history = std::mem::take(&mut self.history);
+        history.truncate(step_count);
+        let snapshot_count =
+            self.snapshots.partition_point(|snapshot| snapshot.step_count <= step_count);
+        self.snapshots.truncate(snapshot_count);
+
+        let replay_start = match self.snapshots.last() {
+            Some(snapshot) => {
+                self.acvm = snapshot.acvm.clone();
+                self.brillig_solver = snapshot.brillig_solver.clone();
+                self.debug_vars = snapshot.debug_vars.clone();
+                self.last_source_line = snapshot.last_source_line;
+                self.foreign_call_cursor = snapshot.foreign_call_cursor;
+                snapshot.step_count
+            }
+            None => {
+                self.acvm = ACVM::new(
+                    self.blackbox_solver,
+                    &self.circuit.opcodes,
+                    self.initial_witness.clone(),
+                );
+                self.brillig_solver = None;
+                self.debug_vars = DebugVars::new(&self.debug_artifact.debug_symbols[0]);
+                self.last_source_line = None;
+                self.foreign_call_cursor = 0;
+                self.update_debug_vars();
+                0
+            }
+        };
+        for entry in &history[replay_start..] {
+            self.replay_step(entry.step);
+        }
+        self.history = history;
+    }
+
+    /// Returns the location of the opcode which was about to be executed after `step_count` steps.
+    fn opcode_location_at_step(&self, step_count: usize) -> Option<OpcodeLocation> {
+        match self.history.get(step_count) {
+            Some(entry) => entry.location,
+            None => self.get_current_opcode_location(),
+        }
+    }
+
+    fn source_line_at_step(&self, step_count: usize) -> Option<SourceLine> {
+        let location = self.opcode_location_at_step(step_count)?;
+        let call_stack = self.debug_artifact.debug_symbols[0].opcode_location(&location)?;
+        self.source_position(&call_stack).map(|(line, _)| line)
+    }
+
+    fn is_breakpoint_at_step(&self, step_count: usize) -> bool {
+        let Some(location) = self.opcode_location_at_step(step_count) else { return false };
+        if self.breakpoints.contains(&location) {
+            return true;
         }
+        let Some(line) = self.source_line_at_step(step_count) else { return false };
+        let entered_line =
+            step_count == 0 || self.source_line_at_step(step_count - 1) != Some(line);
+        entered_line && self.source_breakpoints.contains(&line)
+    }
+
+    /// Steps back to the previously executed opcode.
+    pub(super) fn reverse_step_into_opcode(&mut self) -> DebugCommandResult {
+        self.rewind_to(self.history.len().saturating_sub(1));
+        DebugCommandResult::Ok
+    }
+
+    /// Steps back to the start of the previously executed source line.
+    pub(super) fn reverse_step(&mut self) -> DebugCommandResult {
+        let current_line = self.source_line_at_step(self.history.len());
+        let previous_line_end = (0..self.history.len()).rev().find(|step_count| {
+            let line = self.source_line_at_step(*step_count);
+            line.is_some() && line != current_line
+        });
+        let Some(mut target) = previous_line_end else {
+            self.rewind_to(0);
+            return DebugCommandResult::Ok;
+        };
+
+        let target_line = self.source_line_at_step(target);
+        while target > 0 {
+            let line = self.source_line_at_step(target - 1);
+            if line.is_some() && line != target_line {
+                break;
+            }
+            target -= 1;
+        }
+        self.rewind_to(target);
+        DebugCommandResult::Ok
+    }
+
+    /// Runs backwards until reaching the previous breakpoint or the start of the program.
+    pub(super) fn reverse_continue(&mut self) -> DebugCommandResult {
+        let breakpoint = (0..self.history.len())
+            .rev()
+            .find(|step_count| self.is_breakpoint_at_step(*step_count));
+        match breakpoint {
+            Some(step_count) => {
+                self.rewind_to(step_count);
+                DebugCommandResult::BreakpointReached(
+                    self.get_current_opcode_location()
+                        .expect("Breakpoint reached but we have no location"),
+                )
+            }
+            None => {
+                self.rewind_to(0);
+                DebugCommandResult::Ok
+            }
+        }
+    }
+
+    /// Travels to the first time the opcode at `location` is about to be executed, going back
+    /// if it has already been executed and otherwise running forward, ignoring any breakpoints.
+    pub(super) fn jump_to_opcode(&mut self, location: OpcodeLocation) -> DebugCommandResult {
+        if let Some(step_count) =
+            self.history.iter().position(|entry| entry.location == Some(location))
+        {
+            self.rewind_to(step_count);
+            return DebugCommandResult::Ok;
+        }
+        while self.get_current_opcode_location() != Some(location) {
+            match self.step_into_opcode() {
+                DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_) => (),
+                result => return result,
+            }
+        }
+        DebugCommandResult::Ok
+    }
+
+    fn breakpoint_reached(&mut self) -> bool {
+        let Some(location) = self.get_current_opcode_location() else {
+            return false;
+        };
+
+        let mut source_breakpoint_reached = false;
+        if let Some((current_line, _)) = self.get_current_source_position() {
+            let entered_line = self.last_source_line != Some(current_line);
+            source_breakpoint_reached =
+                entered_line && self.source_breakpoints.contains(&current_line);
+            self.last_source_line = Some(current_line);
+        }
+
+        source_breakpoint_reached || self.breakpoints.contains(&location)
     }
 
     pub(super) fn is_valid_opcode_location(&self, location: &OpcodeLocation) -> bool {
@@ -232,6 +696,121 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         self.breakpoints.iter()
     }
 
+    /// Returns the files containing source code which opcodes of the circuit are mapped to
+    /// whose path ends with `path`.
+    pub(super) fn find_source_files(&self, path: &str) -> Vec<FileId> {
+        self.debug_artifact
+            .file_map
+            .iter()
+            .filter(|(_, file)| file.path.ends_with(path))
+            .map(|(file_id, _)| *file_id)
+            .collect()
+    }
+
+    /// Returns the first line at or after `line_index` in `file` which has opcodes mapped to it.
+    pub(super) fn find_source_line(&self, file: FileId, line_index: usize) -> Option<SourceLine> {
+        let start = SourceLine { file, line_index };
+        let end = SourceLine { file, line_index: usize::MAX };
+        self.source_to_opcodes.range(start..=end).next().map(|(line, _)| *line)
+    }
+
+    pub(super) fn source_line_name(&self, line: &SourceLine) -> String {
+        let file_name = self
+            .debug_artifact
+            .name(line.file)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|_| "<unknown>".to_string());
+        format!("{file_name}:{}", line.line_index + 1)
+    }
+
+    pub(super) fn add_source_breakpoint(&mut self, line: SourceLine) -> bool {
+        self.source_breakpoints.insert(line)
+    }
+
+    pub(super) fn delete_source_breakpoint(&mut self, line: &SourceLine) -> bool {
+        self.source_breakpoints.remove(line)
+    }
+
+    pub(super) fn iterate_source_breakpoints(&self) -> Iter<'_, SourceLine> {
+        self.source_breakpoints.iter()
+    }
+
+    /// Whether the program was compiled with information about its variables,
+    /// without which the values of variables are unavailable.
+    pub(super) fn has_variable_info(&self) -> bool {
+        self.debug_vars.has_variable_info()
+    }
+
+    pub(super) fn get_current_function(&self) -> Option<&str> {
+        self.debug_vars.current_function()
+    }
+
+    pub(super) fn get_variables(&self) -> Vec<(&str, &PrintableValue, &PrintableType)> {
+        self.debug_vars.current_variables()
+    }
+
+    pub(super) fn evaluate_path(
+        &self,
+        path: &str,
+    ) -> Result<(PrintableValue, PrintableType), String> {
+        self.debug_vars.evaluate_path(path)
+    }
+
+    pub(super) fn get_witness_map(&self) -> &WitnessMap {
+        self.acvm.witness_map()
+    }
+
+    pub(super) fn overwrite_witness(&mut self, witness: Witness, value: FieldElement) {
+        self.modify_state(ExecutionStep::SetWitness(witness, value));
+    }
+
+    /// Returns the registers of the Brillig VM, if a Brillig block is being executed.
+    pub(super) fn get_brillig_registers(&self) -> Option<&Registers> {
+        self.brillig_solver.as_ref().map(|solver| solver.get_registers())
+    }
+
+    /// Sets the value of a register of the Brillig VM, returning an error if no Brillig block
+    /// is being executed or if the Brillig VM has no register with the given index.
+    pub(super) fn set_brillig_register(
+        &mut self,
+        register_index: usize,
+        value: FieldElement,
+    ) -> Result<(), String> {
+        if self.brillig_solver.is_none() {
+            return Err("Not executing a Brillig block".to_string());
+        }
+        if register_index >= MAX_BRILLIG_REGISTERS {
+            return Err(format!(
+                "Invalid register {register_index}, expected less than {MAX_BRILLIG_REGISTERS}"
+            ));
+        }
+        self.modify_state(ExecutionStep::SetBrilligRegister(register_index, value));
+        Ok(())
+    }
+
+    /// Returns the memory of the Brillig VM, if a Brillig block is being executed.
+    pub(super) fn get_brillig_memory(&self) -> Option<&[Value]> {
+        self.brillig_solver.as_ref().map(|solver| solver.get_memory())
+    }
+
+    /// Writes a value to the memory of the Brillig VM, returning an error if no Brillig block
+    /// is being executed or if the address is too far past the end of the memory.
+    pub(super) fn write_brillig_memory(
+        &mut self,
+        ptr: usize,
+        value: FieldElement,
+    ) -> Result<(), String> {
+        let Some(memory) = self.get_brillig_memory() else {
+            return Err("Not executing a Brillig block".to_string());
+        };
+        let max_ptr = memory.len() + BRILLIG_MEMORY_GROWTH_LIMIT;
+        if ptr >= max_ptr {
+            return Err(format!("Invalid memory address {ptr}, expected less than {max_ptr}"));
+        }
+        self.modify_state(ExecutionStep::WriteBrilligMemory(ptr, value));
+        Ok(())
+    }
+
     pub(super) fn is_solved(&self) -> bool {
         matches!(self.acvm.get_status(), ACVMStatus::Solved)
     }
@@ -240,3 +819,537 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         self.acvm.finalize()
     }
 }
+
+/// Builds the reverse of the debug info's map from opcodes to source locations,
+/// keyed by the line of each opcode's innermost source location.
+fn build_source_to_opcodes(
+    debug_artifact: &DebugArtifact,
+) -> BTreeMap<SourceLine, Vec<OpcodeLocation>> {
+    let mut source_to_opcodes: BTreeMap<SourceLine, Vec<OpcodeLocation>> = BTreeMap::new();
+    let Some(debug_info) = debug_artifact.debug_symbols.first() else {
+        return source_to_opcodes;
+    };
+    for (opcode_location, call_stack) in &debug_info.locations {
+        let Some(location) = call_stack.last() else { continue };
+        let Ok(line_index) = debug_artifact.location_line_index(*location) else { continue };
+        source_to_opcodes
+            .entry(SourceLine { file: location.file, line_index })
+            .or_default()
+            .push(*opcode_location);
+    }
+    source_to_opcodes
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::brillig::{Opcode as BrilligOpcode, RegisterIndex};
+    use acvm::acir::circuit::{Opcode, OpcodeLocation};
+    use acvm::FieldElement;
+    use nargo::ops::ForeignCallExecutor;
+    use noirc_driver::CompileOptions;
+    use noirc_printable_type::PrintableValue;
+
+    use crate::test_utils::{compile_program, initial_witness, StubbedSolver};
+
+    use super::{
+        DebugCommandResult, DebugContext, BRILLIG_MEMORY_GROWTH_LIMIT, MAX_BRILLIG_REGISTERS,
+        SNAPSHOT_INTERVAL,
+    };
+
+    /// `check` is inlined into `main` so its opcodes have a call stack of depth two.
+    const STEPPING_PROGRAM: &str = "fn main(x: Field, y: Field) {
+    assert(x * y == 6);
+    check(x, y);
+    assert(x + y == 5);
+}
+
+fn check(a: Field, b: Field) {
+    assert(a * a == 4);
+    assert(b * b == 9);
+}
+";
+
+    /// `z`, `a` and `b` are only held by expressions over the witnesses of `x` and `y`.
+    const VARIABLES_PROGRAM: &str = "fn main(x: Field, y: Field) {
+    let z = x * y;
+    assert(z == 6);
+    check(x + 1);
+}
+
+fn check(a: Field) {
+    let b = a * 2;
+    assert(b == 6);
+}
+";
+
+    /// Runs for several times [`SNAPSHOT_INTERVAL`] steps within a Brillig block when `x` is 50.
+    const LOOP_PROGRAM: &str = "unconstrained fn sum(n: u32) -> u32 {
+    let mut total = 0;
+    for i in 0..n {
+        total += i;
+    }
+    total
+}
+
+fn main(x: u32) {
+    assert(sum(x) == 1225);
+}
+";
+
+    /// Changes the result of the mocked `get_value` oracle between its two calls.
+    const MOCK_PROGRAM: &str = "use dep::std::test::OracleMock;
+
+#[oracle(get_value)]
+unconstrained fn get_value_oracle() -> Field {}
+
+unconstrained fn get_value() -> Field {
+    get_value_oracle()
+}
+
+fn main(x: Field) {
+    let mock = OracleMock::mock(\"get_value\").returns(x);
+    assert(get_value() == x);
+    let _mock = mock.returns(x + 1);
+    assert(get_value() == x + 1);
+}
+";
+
+    /// The oracle is called by a `#[fold]` function, so its calls are made by the callee of an
+    /// ACIR call rather than by `main`.
+    const FOLDED_MOCK_PROGRAM: &str = "use dep::std::test::OracleMock;
+
+#[oracle(get_value)]
+unconstrained fn get_value_oracle() -> Field {}
+
+unconstrained fn get_value() -> Field {
+    get_value_oracle()
+}
+
+#[fold]
+fn checked_value(x: Field) -> Field {
+    let value = get_value();
+    assert(value == x);
+    value
+}
+
+fn main(x: Field) {
+    let mock = OracleMock::mock(\"get_value\").returns(x);
+    let first = checked_value(x);
+    let _mock = mock.returns(x + 1);
+    assert(checked_value(x + 1) == first + 1);
+}
+";
+
+    /// The Brillig blocks of `main` return the wrong values unless `x` is 4.
+    const BRILLIG_OUTPUTS_PROGRAM: &str = "unconstrained fn double(x: Field) -> Field {
+    x * 2
+}
+
+unconstrained fn pair(x: Field) -> [Field; 2] {
+    [x, x * 2]
+}
+
+fn main(x: Field) {
+    assert(double(x) == 8);
+    let p = pair(x);
+    assert(p[1] == 8);
+}
+";
+
+    /// Compiles `source` and runs `test` on a context about to execute its first opcode
+    /// with the given inputs.
+    fn with_debug_context(
+        source: &str,
+        options: &CompileOptions,
+        inputs: &[(&str, u128)],
+        test: impl FnOnce(&mut DebugContext<StubbedSolver>),
+    ) {
+        let (program, debug_artifact) = compile_program(source, options);
+        let initial_witness = initial_witness(&program, inputs);
+        let mut context = DebugContext::new(
+            &StubbedSolver,
+            &program.circuit,
+            &program.functions,
+            &debug_artifact,
+            initial_witness,
+            ForeignCallExecutor::default(),
+        );
+        test(&mut context);
+    }
+
+    fn with_stepping_program(test: impl FnOnce(&mut DebugContext<StubbedSolver>)) {
+        with_debug_context(
+            STEPPING_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 2), ("y", 3)],
+            test,
+        );
+    }
+
+    /// Returns the one-based line of the opcode about to be executed.
+    fn current_line(context: &DebugContext<StubbedSolver>) -> Option<usize> {
+        context.get_current_source_position().map(|(line, _)| line.line_index + 1)
+    }
+
+    /// Returns the value of the variable `name` of the current function if it is a field.
+    fn field_variable(context: &DebugContext<StubbedSolver>, name: &str) -> Option<u128> {
+        match context.evaluate_path(name) {
+            Ok((PrintableValue::Field(value), _)) => Some(value.to_u128()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn next_steps_over_inlined_calls() {
+        with_stepping_program(|context| {
+            assert_eq!(current_line(context), Some(2));
+
+            assert!(matches!(context.next(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(4));
+
+            assert!(matches!(context.next(), DebugCommandResult::Done));
+            assert!(context.is_solved());
+        });
+    }
+
+    #[test]
+    fn step_in_and_out_of_inlined_calls() {
+        with_stepping_program(|context| {
+            assert!(matches!(context.step_in(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(8));
+            assert!(matches!(context.step_in(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(9));
+
+            assert!(matches!(context.step_out(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(4));
+        });
+    }
+
+    #[test]
+    fn continue_stops_at_source_breakpoint() {
+        with_stepping_program(|context| {
+            // Equivalent to `break main.nr:9`
+            let files = context.find_source_files("main.nr");
+            assert_eq!(files.len(), 1);
+            let line = context.find_source_line(files[0], 8).expect("line 9 should have opcodes");
+            assert!(context.source_line_name(&line).ends_with("main.nr:9"));
+            assert!(context.add_source_breakpoint(line));
+
+            assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+            assert_eq!(current_line(context), Some(9));
+        });
+    }
+
+    #[test]
+    fn finish_ignores_breakpoints() {
+        with_stepping_program(|context| {
+            let file = context.find_source_files("main.nr")[0];
+            let line = context.find_source_line(file, 7).expect("line 8 should have opcodes");
+            context.add_source_breakpoint(line);
+
+            assert!(matches!(context.finish(), DebugCommandResult::Done));
+            assert!(context.is_solved());
+        });
+    }
+
+    #[test]
+    fn variables_are_read_from_recorded_locations() {
+        let options = CompileOptions { debug_variables: true, ..CompileOptions::default() };
+        with_debug_context(VARIABLES_PROGRAM, &options, &[("x", 2), ("y", 3)], |context| {
+            assert!(context.has_variable_info());
+            assert_eq!(current_line(context), Some(3));
+            assert_eq!(context.get_current_function(), Some("main"));
+            assert_eq!(field_variable(context, "x"), Some(2));
+            assert_eq!(field_variable(context, "y"), Some(3));
+            assert_eq!(field_variable(context, "z"), Some(6));
+
+            assert!(matches!(context.step_in(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(9));
+            assert_eq!(context.get_current_function(), Some("check"));
+            assert_eq!(field_variable(context, "a"), Some(3));
+            assert_eq!(field_variable(context, "b"), Some(6));
+            assert_eq!(field_variable(context, "x"), None);
+        });
+    }
+
+    #[test]
+    fn variables_are_read_on_each_iteration_of_brillig_loops() {
+        let options = CompileOptions { debug_variables: true, ..CompileOptions::default() };
+        with_debug_context(LOOP_PROGRAM, &options, &[("x", 50)], |context| {
+            let mut totals = Vec::new();
+            while matches!(context.step_into_opcode(), DebugCommandResult::Ok) {
+                if let Some(total) = field_variable(context, "total") {
+                    if totals.last() != Some(&total) {
+                        totals.push(total);
+                    }
+                }
+            }
+            assert!(context.is_solved());
+
+            // `total` is the sum of the indices of the iterations so far.
+            assert_eq!(totals[..5], [0, 1, 3, 6, 10]);
+            assert_eq!(totals.last(), Some(&1225));
+        });
+    }
+
+    #[test]
+    fn recording_variable_locations_does_not_change_the_circuit() {
+        let options = CompileOptions { debug_variables: true, ..CompileOptions::default() };
+        let (program, _) = compile_program(VARIABLES_PROGRAM, &CompileOptions::default());
+        let (debug_program, debug_artifact) = compile_program(VARIABLES_PROGRAM, &options);
+
+        assert_eq!(program.circuit, debug_program.circuit);
+        assert!(!debug_artifact.debug_symbols[0].variable_assignments.is_empty());
+    }
+
+    /// Sets a breakpoint on the given one-based line of `main.nr`.
+    fn add_line_breakpoint(context: &mut DebugContext<StubbedSolver>, line: usize) {
+        let file = context.find_source_files("main.nr")[0];
+        let line = context.find_source_line(file, line - 1).expect("line should have opcodes");
+        context.add_source_breakpoint(line);
+    }
+
+    #[test]
+    fn reverse_step_returns_to_previous_lines() {
+        with_stepping_program(|context| {
+            assert!(matches!(context.next(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(4));
+
+            // Stepping back enters the inlined call to `check` at its last line.
+            assert!(matches!(context.reverse_step(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(9));
+            assert!(matches!(context.reverse_step(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(8));
+            assert!(matches!(context.reverse_step(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(2));
+            assert_eq!(context.executed_steps(), 0);
+
+            assert!(matches!(context.next(), DebugCommandResult::Ok));
+            assert_eq!(current_line(context), Some(4));
+        });
+    }
+
+    #[test]
+    fn reverse_continue_stops_at_previous_breakpoint() {
+        with_stepping_program(|context| {
+            add_line_breakpoint(context, 8);
+            assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+            assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+            assert!(matches!(context.reverse_continue(), DebugCommandResult::BreakpointReached(_)));
+            assert_eq!(current_line(context), Some(8));
+            assert!(!context.is_solved());
+
+            assert!(matches!(context.reverse_continue(), DebugCommandResult::Ok));
+            assert_eq!(context.executed_steps(), 0);
+            assert_eq!(current_line(context), Some(2));
+        });
+    }
+
+    #[test]
+    fn jump_to_opcode_travels_in_both_directions() {
+        with_stepping_program(|context| {
+            let start = context.get_current_opcode_location().unwrap();
+            assert!(matches!(context.next(), DebugCommandResult::Ok));
+            let target = context.get_current_opcode_location().unwrap();
+            assert_eq!(current_line(context), Some(4));
+
+            assert!(matches!(context.jump_to_opcode(start), DebugCommandResult::Ok));
+            assert_eq!(context.executed_steps(), 0);
+            assert_eq!(current_line(context), Some(2));
+
+            assert!(matches!(context.jump_to_opcode(target), DebugCommandResult::Ok));
+            assert_eq!(context.get_current_opcode_location(), Some(target));
+            assert_eq!(current_line(context), Some(4));
+        });
+    }
+
+    #[test]
+    fn reverse_steps_restore_the_state_of_earlier_steps() {
+        with_debug_context(LOOP_PROGRAM, &CompileOptions::default(), &[("x", 50)], |context| {
+            let mut states = Vec::new();
+            loop {
+                let location = context.get_current_opcode_location();
+                let registers = context.get_brillig_registers().cloned();
+                states.push((location, registers));
+                if !matches!(context.step_into_opcode(), DebugCommandResult::Ok) {
+                    break;
+                }
+            }
+            assert!(context.is_solved());
+            assert!(states.len() > 2 * SNAPSHOT_INTERVAL);
+
+            for (step_count, (location, registers)) in states.iter().enumerate().rev() {
+                assert!(matches!(context.reverse_step_into_opcode(), DebugCommandResult::Ok));
+                assert_eq!(context.executed_steps(), step_count);
+                assert_eq!(context.get_current_opcode_location(), *location);
+                assert_eq!(context.get_brillig_registers(), registers.as_ref());
+            }
+        });
+    }
+
+    #[test]
+    fn diverging_after_rewinding_restores_mocks() {
+        with_debug_context(MOCK_PROGRAM, &CompileOptions::default(), &[("x", 7)], |context| {
+            let (x_witness, x_value) =
+                context.get_witness_map().clone().into_iter().next().expect("x should be set");
+            add_line_breakpoint(context, 12);
+            add_line_breakpoint(context, 14);
+
+            assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+            assert_eq!(current_line(context), Some(12));
+            assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+            assert_eq!(current_line(context), Some(14));
+
+            // The mock returns `x + 1` by now, but returned `x` when line 12 was executed.
+            assert!(matches!(context.reverse_continue(), DebugCommandResult::BreakpointReached(_)));
+            assert_eq!(current_line(context), Some(12));
+            context.overwrite_witness(x_witness, x_value);
+
+            assert!(matches!(context.finish(), DebugCommandResult::Done));
+            assert!(context.is_solved());
+        });
+    }
+
+    #[test]
+    fn foreign_calls_of_acir_calls_are_replayed() {
+        with_debug_context(
+            FOLDED_MOCK_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 7)],
+            |context| {
+                assert!(matches!(context.finish(), DebugCommandResult::Done));
+
+                // The mock returns `x + 1` by now, so the first call to `checked_value` only succeeds
+                // again if the result of its oracle call is replayed.
+                assert!(matches!(context.reverse_continue(), DebugCommandResult::Ok));
+                assert_eq!(context.executed_steps(), 0);
+                assert!(matches!(context.finish(), DebugCommandResult::Done));
+                assert!(context.is_solved());
+            },
+        );
+    }
+
+    /// Steps into opcodes until reaching the `Stop` instruction of a Brillig block,
+    /// at which point the registers hold the outputs of the block.
+    fn step_to_brillig_stop(context: &mut DebugContext<StubbedSolver>) {
+        loop {
+            assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+            let Some(OpcodeLocation::Brillig { acir_index, brillig_index }) =
+                context.get_current_opcode_location()
+            else {
+                continue;
+            };
+            let Opcode::Brillig(brillig) = &context.get_opcodes()[acir_index] else {
+                unreachable!("Brillig location of a non-Brillig opcode");
+            };
+            if matches!(brillig.bytecode[brillig_index], BrilligOpcode::Stop) {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn overwritten_witnesses_are_used_by_later_opcodes() {
+        with_debug_context(
+            STEPPING_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 1), ("y", 3)],
+            |context| {
+                let (x_witness, _) = context.get_witness_map().clone().into_iter().next().unwrap();
+                context.overwrite_witness(x_witness, FieldElement::from(2_u128));
+                assert_eq!(context.get_witness_map()[&x_witness], FieldElement::from(2_u128));
+
+                assert!(matches!(context.finish(), DebugCommandResult::Done));
+                assert!(context.is_solved());
+
+                // The modification is reapplied when travelling back in time to a later point.
+                assert!(matches!(
+                    context.jump_to_opcode(OpcodeLocation::Acir(1)),
+                    DebugCommandResult::Ok
+                ));
+                assert_eq!(context.get_witness_map()[&x_witness], FieldElement::from(2_u128));
+            },
+        );
+    }
+
+    #[test]
+    fn overwritten_registers_are_written_to_brillig_outputs() {
+        with_debug_context(
+            BRILLIG_OUTPUTS_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 3)],
+            |context| {
+                assert!(context.set_brillig_register(0, FieldElement::from(8_u128)).is_err());
+
+                step_to_brillig_stop(context);
+                let registers = context.get_brillig_registers().unwrap();
+                assert_eq!(registers.get(RegisterIndex(0)).to_field(), FieldElement::from(6_u128));
+                assert!(context.set_brillig_register(0, FieldElement::from(8_u128)).is_ok());
+                let registers = context.get_brillig_registers().unwrap();
+                assert_eq!(registers.get(RegisterIndex(0)).to_field(), FieldElement::from(8_u128));
+
+                // `double(x)` now returns 8, but `pair(x)` still returns the wrong values.
+                assert!(matches!(context.finish(), DebugCommandResult::Error(_)));
+            },
+        );
+    }
+
+    #[test]
+    fn overwritten_memory_is_written_to_brillig_outputs() {
+        with_debug_context(
+            BRILLIG_OUTPUTS_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 3)],
+            |context| {
+                assert!(context.write_brillig_memory(0, FieldElement::from(8_u128)).is_err());
+
+                step_to_brillig_stop(context);
+                assert!(context.set_brillig_register(0, FieldElement::from(8_u128)).is_ok());
+                step_to_brillig_stop(context);
+
+                // The returned array is held in memory at the address held by the first register.
+                let registers = context.get_brillig_registers().unwrap();
+                let address = registers.get(RegisterIndex(0)).to_usize() + 1;
+                let memory = context.get_brillig_memory().unwrap();
+                assert_eq!(memory[address].to_field(), FieldElement::from(6_u128));
+                assert!(context.write_brillig_memory(address, FieldElement::from(8_u128)).is_ok());
+                let memory = context.get_brillig_memory().unwrap();
+                assert_eq!(memory[address].to_field(), FieldElement::from(8_u128));
+
+                assert!(matches!(context.finish(), DebugCommandResult::Done));
+                assert!(context.is_solved());
+            },
+        );
+    }
+
+    #[test]
+    fn out_of_range_registers_and_memory_addresses_are_rejected() {
+        with_debug_context(
+            BRILLIG_OUTPUTS_PROGRAM,
+            &CompileOptions::default(),
+            &[("x", 3)],
+            |context| {
+                step_to_brillig_stop(context);
+                let executed_steps = context.executed_steps();
+                let value = FieldElement::from(1_u128);
+
+                assert!(context.set_brillig_register(MAX_BRILLIG_REGISTERS, value).is_err());
+                assert!(context.set_brillig_register(usize::MAX, value).is_err());
+
+                let memory_size = context.get_brillig_memory().unwrap().len();
+                let max_ptr = memory_size + BRILLIG_MEMORY_GROWTH_LIMIT;
+                assert!(context.write_brillig_memory(max_ptr, value).is_err());
+                assert!(context.write_brillig_memory(usize::MAX, value).is_err());
+
+                // Rejected modifications are not recorded as steps of execution.
+                assert_eq!(context.executed_steps(), executed_steps);
+                assert_eq!(context.get_brillig_memory().unwrap().len(), memory_size);
+
+                assert!(context.set_brillig_register(MAX_BRILLIG_REGISTERS - 1, value).is_ok());
+                assert!(context.write_brillig_memory(max_ptr - 1, value).is_ok());
+                assert_eq!(context.get_brillig_memory().unwrap().len(), max_ptr);
+            },
+        );
+    }
+}
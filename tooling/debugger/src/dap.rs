@@ -0,0 +1,661 @@
+//! A server for the [Debug Adapter Protocol](https://microsoft.github.io/debug-adapter-protocol/),
+//! which allows editors such as VS Code to drive the debugger.
+//!
+//! Messages are exchanged as JSON bodies preceded by a `Content-Length` header. The server handles
+//! a single thread of execution, as circuits are executed sequentially.
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use acvm::acir::native_types::WitnessMap;
+use acvm::BlackBoxFunctionSolver;
+use codespan_reporting::files::Files;
+use nargo::artifacts::debug::DebugArtifact;
+use nargo::ops::ForeignCallExecutor;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::context::{DebugCommandResult, DebugContext};
+use crate::vars::display_value;
+
+/// The id of the only thread reported to the client.
+const THREAD_ID: i64 = 1;
+
+/// The reference of the scope holding the variables of the current function.
+const LOCALS_REFERENCE: i64 = 1;
+/// The reference of the scope holding the current witness map.
+const WITNESS_MAP_REFERENCE: i64 = 2;
+
+#[derive(Debug, Error)]
+pub enum DapError {
+    #[error("Failed to communicate with the client: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Received a malformed message: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Received a message with an invalid header: {0}")]
+    InvalidHeader(String),
+}
+
+/// A request sent by the client.
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl Request {
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.arguments)
+    }
+}
+
+/// The arguments of the `launch` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchArguments {
+    /// The directory of the project to debug.
+    pub project_folder: String,
+    /// The name of the package to debug, required if the project is a workspace.
+    pub package: Option<String>,
+    /// The name of the toml file which contains the inputs for the prover.
+    pub prover_name: Option<String>,
+    /// The name of a test function to debug instead of `main`.
+    pub test_name: Option<String>,
+    /// A function of a contract package to debug instead of `main`, given as `<contract>::<function>`.
+    pub contract_function: Option<String>,
+    /// Whether to stop on the first opcode rather than running until a breakpoint is reached.
+    #[serde(default)]
+    pub stop_on_entry: bool,
+}
+
+/// Reads requests from the client and sends responses and events back to it.
+pub struct DapServer<R, W> {
+    reader: R,
+    writer: W,
+    next_seq: i64,
+}
+
+impl<R: BufRead, W: Write> DapServer<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer, next_seq: 1 }
+    }
+
+    /// Reads the next request, returning `None` once the client has closed the connection.
+    pub fn next_request(&mut self) -> Result<Option<Request>, DapError> {
+        let mut content_length = None;
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                if content_length.is_some() {
+                    break;
+                }
+                continue;
+            }
+            if let Some(length) = line.strip_prefix("Content-Length:") {
+                let length = length
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| DapError::InvalidHeader(line.to_string()))?;
+                content_length = Some(length);
+            }
+        }
+
+        let mut body = vec![0; content_length.unwrap_or_default()];
+        self.reader.read_exact(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+
+    /// Responds to the `initialize` request with the capabilities of the server.
+    pub fn respond_to_initialize(&mut self, request: &Request) -> Result<(), DapError> {
+        let capabilities = json!({
+            "supportsConfigurationDoneRequest": true,
+            "supportsInstructionBreakpoints": true,
+            "supportsSteppingGranularity": true,
+            "supportsStepBack": true,
+            "supportsEvaluateForHovers": true,
+        });
+        self.respond(request, Some(capabilities))
+    }
+
+    pub fn respond(&mut self, request: &Request, body: Option<Value>) -> Result<(), DapError> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": true,
+            "command": request.command,
+            "body": body,
+        }))
+    }
+
+    pub fn respond_error(&mut self, request: &Request, message: &str) -> Result<(), DapError> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": false,
+            "command": request.command,
+            "message": message,
+        }))
+    }
+
+    pub fn send_event(&mut self, event: &str, body: Option<Value>) -> Result<(), DapError> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn send(&mut self, mut message: Value) -> Result<(), DapError> {
+        message["seq"] = json!(self.next_seq);
+        self.next_seq += 1;
+
+        let body = serde_json::to_string(&message)?;
+        write!(self.writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+struct DapSession<'a, 's, R, W, B: BlackBoxFunctionSolver> {
+    server: &'s mut DapServer<R, W>,
+    context: DebugContext<'a, B>,
+    debug_artifact: &'a DebugArtifact,
+    stop_on_entry: bool,
+}
+
+impl<'a, 's, R: BufRead, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, 's, R, W, B> {
+    /// Handles requests until the client disconnects.
+    fn run(&mut self) -> Result<(), DapError> {
+        self.server.send_event("initialized", None)?;
+
+        while let Some(request) = self.server.next_request()? {
+            match request.command.as_str() {
+                "disconnect" | "terminate" => {
+                    self.server.respond(&request, None)?;
+                    break;
+                }
+                "configurationDone" => {
+                    self.server.respond(&request, None)?;
+                    if self.stop_on_entry {
+                        self.send_stopped_event("entry", None)?;
+                    } else {
+                        let result = self.context.cont();
+                        self.handle_execution_result(result)?;
+                    }
+                }
+                "setBreakpoints" => self.set_source_breakpoints(&request)?,
+                "setInstructionBreakpoints" => self.set_instruction_breakpoints(&request)?,
+                "setExceptionBreakpoints" => {
+                    self.server.respond(&request, Some(json!({ "breakpoints": [] })))?;
+                }
+                "threads" => {
+                    let threads = json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] });
+                    self.server.respond(&request, Some(threads))?;
+                }
+                "stackTrace" => {
+                    let stack_trace = self.stack_trace();
+                    self.server.respond(&request, Some(stack_trace))?;
+                }
+                "scopes" => {
+                    let scopes = json!({ "scopes": [
+                        scope("Locals", LOCALS_REFERENCE),
+                        scope("Witness Map", WITNESS_MAP_REFERENCE),
+                    ] });
+                    self.server.respond(&request, Some(scopes))?;
+                }
+                "variables" => self.variables(&request)?,
+                "evaluate" => self.evaluate(&request)?,
+                "next" | "stepIn" | "stepOut" | "continue" | "stepBack" | "reverseContinue" => {
+                    self.execute(&request)?;
+                }
+                command => {
+                    self.server
+                        .respond_error(&request, &format!("Unsupported request `{command}`"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn set_source_breakpoints(&mut self, request: &Request) -> Result<(), DapError> {
+        #[derive(Deserialize)]
+        struct Source {
+            path: String,
+        }
+        #[derive(Deserialize)]
+        struct SourceBreakpoint {
+            line: usize,
+        }
+        #[derive(Deserialize)]
+        struct Arguments {
+            source: Source,
+            #[serde(default)]
+            breakpoints: Vec<SourceBreakpoint>,
+        }
+
+        let arguments: Arguments = match request.parse_arguments() {
+            Ok(arguments) => arguments,
+            Err(error) => return self.server.respond_error(request, &error.to_string()),
+        };
+        let files = self.context.find_source_files(&arguments.source.path);
+
+        // The request replaces all of the breakpoints previously set in the file.
+        let previous: Vec<_> = self
+            .context
+            .iterate_source_breakpoints()
+            .filter(|line| files.contains(&line.file))
+            .copied()
+            .collect();
+        for line in &previous {
+            self.context.delete_source_breakpoint(line);
+        }
+
+        let breakpoints: Vec<_> = arguments
+            .breakpoints
+            .iter()
+            .map(|breakpoint| {
+                let line = files.iter().find_map(|file| {
+                    self.context.find_source_line(*file, breakpoint.line.saturating_sub(1))
+                });
+                match line {
+                    Some(line) => {
+                        self.context.add_source_breakpoint(line);
+                        json!({ "verified": true, "line": line.line_index + 1 })
+                    }
+                    None => json!({
+                        "verified": false,
+                        "line": breakpoint.line,
+                        "message": "No opcodes are associated with this line",
+                    }),
+                }
+            })
+            .collect();
+        self.server.respond(request, Some(json!({ "breakpoints": breakpoints })))
+    }
+
+    fn set_instruction_breakpoints(&mut self, request: &Request) -> Result<(), DapError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct InstructionBreakpoint {
+            instruction_reference: String,
+        }
+        #[derive(Deserialize)]
+        struct Arguments {
+            breakpoints: Vec<InstructionBreakpoint>,
+        }
+
+        let arguments: Arguments = match request.parse_arguments() {
+            Ok(arguments) => arguments,
+            Err(error) => return self.server.respond_error(request, &error.to_string()),
+        };
+
+        // The request replaces all of the instruction breakpoints previously set.
+        let previous: Vec<_> = self.context.iterate_breakpoints().copied().collect();
+        for location in &previous {
+            self.context.delete_breakpoint(location);
+        }
+
+        let breakpoints: Vec<_> = arguments
+            .breakpoints
+            .iter()
+            .map(|breakpoint| match OpcodeLocation::from_str(&breakpoint.instruction_reference) {
+                Ok(location) if self.context.is_valid_opcode_location(&location) => {
+                    self.context.add_breakpoint(location);
+                    json!({ "verified": true, "instructionReference": location.to_string() })
+                }
+                _ => {
+                    let message =
+                        format!("Invalid opcode location {}", breakpoint.instruction_reference);
+                    json!({ "verified": false, "message": message })
+                }
+            })
+            .collect();
+        self.server.respond(request, Some(json!({ "breakpoints": breakpoints })))
+    }
+
+    /// Returns the source locations of the current opcode, innermost first.
+    fn stack_trace(&self) -> Value {
+        let opcode_location = self.context.get_current_opcode_location();
+        let locations = self.context.get_current_source_location().unwrap_or_default();
+
+        let frames: Vec<_> = locations
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, location)| {
+                let path =
+                    self.debug_artifact.name(location.file).ok().map(|name| name.to_string());
+                let name = self
+                    .debug_artifact
+                    .location_source_code(*location)
+                    .map(|source| source.trim().lines().next().unwrap_or_default().to_string())
+                    .unwrap_or_default();
+                let line = self.debug_artifact.location_line_number(*location).unwrap_or_default();
+                let column =
+                    self.debug_artifact.location_column_number(*location).unwrap_or_default();
+
+                let mut frame = json!({
+                    "id": index,
+                    "name": name,
+                    "source": { "path": path },
+                    "line": line,
+                    "column": column,
+                });
+                if index == 0 {
+                    if let Some(opcode_location) = opcode_location {
+                        frame["instructionPointerReference"] = json!(opcode_location.to_string());
+                    }
+                }
+                frame
+            })
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn variables(&mut self, request: &Request) -> Result<(), DapError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Arguments {
+            variables_reference: i64,
+        }
+
+        let arguments: Arguments = match request.parse_arguments() {
+            Ok(arguments) => arguments,
+            Err(error) => return self.server.respond_error(request, &error.to_string()),
+        };
+        let variables: Vec<_> = match arguments.variables_reference {
+            LOCALS_REFERENCE => self
+                .context
+                .get_variables()
+                .into_iter()
+                .map(|(name, value, typ)| {
+                    json!({
+                        "name": name,
+                        "value": display_value(value, typ),
+                        "variablesReference": 0,
+                    })
+                })
+                .collect(),
+            WITNESS_MAP_REFERENCE => self
+                .context
+                .get_witness_map()
+                .clone()
+                .into_iter()
+                .map(|(witness, value)| {
+                    json!({
+                        "name": format!("_{}", witness.witness_index()),
+                        "value": value.to_string(),
+                        "variablesReference": 0,
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        self.server.respond(request, Some(json!({ "variables": variables })))
+    }
+
+    fn evaluate(&mut self, request: &Request) -> Result<(), DapError> {
+        #[derive(Deserialize)]
+        struct Arguments {
+            expression: String,
+        }
+
+        let arguments: Arguments = match request.parse_arguments() {
+            Ok(arguments) => arguments,
+            Err(error) => return self.server.respond_error(request, &error.to_string()),
+        };
+        match self.context.evaluate_path(&arguments.expression) {
+            Ok((value, typ)) => {
+                let result = json!({
+                    "result": display_value(&value, &typ),
+                    "variablesReference": 0,
+                });
+                self.server.respond(request, Some(result))
+            }
+            Err(message) => self.server.respond_error(request, &message),
+        }
+    }
+
+    fn execute(&mut self, request: &Request) -> Result<(), DapError> {
+        #[derive(Deserialize, Default)]
+        struct Arguments {
+            granularity: Option<String>,
+        }
+
+        let Arguments { granularity } = request.parse_arguments().unwrap_or_default();
+        let by_instruction = granularity.as_deref() == Some("instruction");
+
+        if request.command == "continue" {
+            self.server.respond(request, Some(json!({ "allThreadsContinued": true })))?;
+        } else {
+            self.server.respond(request, None)?;
+        }
+
+        let result = match (request.command.as_str(), by_instruction) {
+            ("next", true) => self.context.step_acir_opcode(),
+            ("next", false) => self.context.next(),
+            ("stepIn", true) => self.context.step_into_opcode(),
+            ("stepIn", false) => self.context.step_in(),
+            ("stepOut", _) => self.context.step_out(),
+            ("stepBack", true) => self.context.reverse_step_into_opcode(),
+            ("stepBack", false) => self.context.reverse_step(),
+            ("reverseContinue", _) => self.context.reverse_continue(),
+            _ => self.context.cont(),
+        };
+        self.handle_execution_result(result)
+    }
+
+    fn handle_execution_result(&mut self, result: DebugCommandResult) -> Result<(), DapError> {
+        match result {
+            DebugCommandResult::Ok => self.send_stopped_event("step", None),
+            DebugCommandResult::BreakpointReached(_) => self.send_stopped_event("breakpoint", None),
+            DebugCommandResult::Error(error) => {
+                self.send_stopped_event("exception", Some(error.to_string()))
+            }
+            DebugCommandResult::Done => {
+                let exit_code = if self.context.is_solved() { 0 } else { 1 };
+                self.server.send_event("exited", Some(json!({ "exitCode": exit_code })))?;
+                self.server.send_event("terminated", None)
+            }
+        }
+    }
+
+    fn send_stopped_event(&mut self, reason: &str, text: Option<String>) -> Result<(), DapError> {
+        self.server.send_event(
+            "stopped",
+            Some(json!({
+                "reason": reason,
+                "text": text,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+            })),
+        )
+    }
+}
+
+fn scope(name: &str, variables_reference: i64) -> Value {
+    json!({ "name": name, "variablesReference": variables_reference, "expensive": false })
+}
+
+/// Debugs `circuit` on behalf of the client connected to `server`, which must already have
+/// initialized the session and requested the launch.
+#[allow(clippy::too_many_arguments)]
+pub fn run_session<R: BufRead, W: Write, B: BlackBoxFunctionSolver>(
+    server: &mut DapServer<R, W>,
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    debug_artifact: &DebugArtifact,
+    initial_witness: WitnessMap,
+    foreign_call_executor: ForeignCallExecutor,
+    stop_on_entry: bool,
+) -> Result<(), DapError> {
+    let context = DebugContext::new(
+        blackbox_solver,
+        circuit,
+        functions,
+        debug_artifact,
+        initial_witness,
+        foreign_call_executor,
+    );
+    DapSession { server, context, debug_artifact, stop_on_entry }.run()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use nargo::ops::ForeignCallExecutor;
+    use noirc_driver::CompileOptions;
+    use serde_json::{json, Value};
+
+    use crate::test_utils::{compile_program, initial_witness, StubbedSolver};
+
+    use super::{run_session, DapServer, LaunchArguments, LOCALS_REFERENCE};
+
+    const PROGRAM: &str = "fn main(x: Field, y: Field) {
+    let z = x * y;
+    assert(z == 6);
+    check(x + 1);
+    assert(x + y == 5);
+}
+
+fn check(a: Field) {
+    let b = a * 2;
+    assert(b == 6);
+}
+";
+
+    /// Encodes `requests` as the messages sent by a client, numbering them from one.
+    fn client_messages(requests: &[(&str, Value)]) -> Vec<u8> {
+        let mut messages = Vec::new();
+        for (index, (command, arguments)) in requests.iter().enumerate() {
+            let body = json!({
+                "seq": index + 1,
+                "type": "request",
+                "command": command,
+                "arguments": arguments,
+            })
+            .to_string();
+            messages.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).bytes());
+        }
+        messages
+    }
+
+    /// Decodes the messages sent by the server.
+    fn server_messages(output: &[u8]) -> Vec<Value> {
+        let mut messages = Vec::new();
+        let mut rest = std::str::from_utf8(output).expect("messages should be valid UTF-8");
+        while let Some(header_end) = rest.find("\r\n\r\n") {
+            let length: usize = rest[..header_end]
+                .trim_start_matches("Content-Length:")
+                .trim()
+                .parse()
+                .expect("header should hold the length of the message");
+            let body_start = header_end + 4;
+            let body = &rest[body_start..body_start + length];
+            messages.push(serde_json::from_str(body).expect("message should be valid JSON"));
+            rest = &rest[body_start + length..];
+        }
+        messages
+    }
+
+    /// Returns the body of the successful response to the request numbered `seq`.
+    fn response_body(messages: &[Value], seq: i64) -> &Value {
+        let response = messages
+            .iter()
+            .find(|message| message["type"] == "response" && message["request_seq"] == seq)
+            .unwrap_or_else(|| panic!("request {seq} should have a response"));
+        assert_eq!(response["success"], true, "request {seq} failed: {response}");
+        &response["body"]
+    }
+
+    fn variables(body: &Value) -> Vec<(String, String)> {
+        body["variables"]
+            .as_array()
+            .expect("response should list variables")
+            .iter()
+            .map(|variable| {
+                let name = variable["name"].as_str().unwrap_or_default().to_string();
+                let value = variable["value"].as_str().unwrap_or_default().to_string();
+                (name, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn session_stops_at_breakpoints_and_shows_variables() {
+        let options = CompileOptions { debug_variables: true, ..CompileOptions::default() };
+        let (program, debug_artifact) = compile_program(PROGRAM, &options);
+        let initial_witness = initial_witness(&program, &[("x", 2), ("y", 3)]);
+
+        let locals = json!({ "variablesReference": LOCALS_REFERENCE });
+        let input = client_messages(&[
+            ("initialize", json!({ "adapterID": "noir" })),
+            ("launch", json!({ "projectFolder": "/test" })),
+            (
+                "setBreakpoints",
+                json!({ "source": { "path": "main.nr" }, "breakpoints": [{ "line": 10 }] }),
+            ),
+            ("configurationDone", Value::Null),
+            ("variables", locals.clone()),
+            ("next", json!({ "threadId": 1 })),
+            ("variables", locals),
+            ("disconnect", Value::Null),
+        ]);
+        let mut server = DapServer::new(Cursor::new(input), Vec::new());
+
+        // The requests preceding the launch are handled by the caller of `run_session`.
+        let initialize = server.next_request().unwrap().expect("should receive `initialize`");
+        server.respond_to_initialize(&initialize).unwrap();
+        let launch = server.next_request().unwrap().expect("should receive `launch`");
+        let arguments: LaunchArguments = launch.parse_arguments().unwrap();
+        assert_eq!(arguments.project_folder, "/test");
+        assert!(!arguments.stop_on_entry);
+        server.respond(&launch, None).unwrap();
+
+        run_session(
+            &mut server,
+            &StubbedSolver,
+            &program.circuit,
+            &program.functions,
+            &debug_artifact,
+            initial_witness,
+            ForeignCallExecutor::default(),
+            arguments.stop_on_entry,
+        )
+        .unwrap();
+        let messages = server_messages(&server.writer);
+
+        assert_eq!(response_body(&messages, 1)["supportsStepBack"], true);
+        assert_eq!(
+            response_body(&messages, 3)["breakpoints"],
+            json!([{ "verified": true, "line": 10 }])
+        );
+
+        let stop_reasons: Vec<_> = messages
+            .iter()
+            .filter(|message| message["event"] == "stopped")
+            .map(|message| message["body"]["reason"].as_str().unwrap_or_default())
+            .collect();
+        assert_eq!(stop_reasons, vec!["breakpoint", "step"]);
+
+        // Stopped on line 10, within `check`
+        assert_eq!(
+            variables(response_body(&messages, 5)),
+            vec![("a".to_string(), "0x03".to_string()), ("b".to_string(), "0x06".to_string())]
+        );
+        // Stepped over the rest of `check` back to line 5, within `main`
+        let main_variables: Vec<_> =
+            variables(response_body(&messages, 7)).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(main_variables, vec!["x", "y", "z"]);
+
+        assert!(response_body(&messages, 8).is_null());
+    }
+}
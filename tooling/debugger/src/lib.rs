@@ -1,18 +1,32 @@
 mod context;
+pub mod dap;
 mod repl;
+#[cfg(test)]
+mod test_utils;
+mod vars;
 
 use acvm::BlackBoxFunctionSolver;
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 
 use nargo::artifacts::debug::DebugArtifact;
+use nargo::ops::ForeignCallExecutor;
 
 use nargo::NargoError;
 
 pub fn debug_circuit<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     circuit: &Circuit,
+    functions: &[Circuit],
     debug_artifact: DebugArtifact,
     initial_witness: WitnessMap,
+    foreign_call_executor: ForeignCallExecutor,
 ) -> Result<Option<WitnessMap>, NargoError> {
-    repl::run(blackbox_solver, circuit, &debug_artifact, initial_witness)
+    repl::run(
+        blackbox_solver,
+        circuit,
+        functions,
+        &debug_artifact,
+        initial_witness,
+        foreign_call_executor,
+    )
 }
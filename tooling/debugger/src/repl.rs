@@ -1,10 +1,13 @@
-use crate::context::{DebugCommandResult, DebugContext};
+use crate::context::{DebugCommandResult, DebugContext, SourceLine};
+use crate::vars::display_value;
 
 use acvm::acir::circuit::{Opcode, OpcodeLocation};
-use acvm::BlackBoxFunctionSolver;
+use acvm::acir::native_types::Witness;
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
 
 use nargo::artifacts::debug::DebugArtifact;
+use nargo::ops::ForeignCallExecutor;
 use nargo::NargoError;
 
 use easy_repl::{command, CommandStatus, Repl};
@@ -16,32 +19,104 @@ use noirc_errors::Location;
 use owo_colors::OwoColorize;
 
 use std::ops::Range;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// A location at which a breakpoint can be set: either an opcode location such as `3` or `3.2`,
+/// or a line of source code such as `src/main.nr:42`.
+#[derive(Debug, Clone)]
+enum BreakpointLocation {
+    Opcode(OpcodeLocation),
+    Source { file: String, line_number: usize },
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid breakpoint location {0}, expected an opcode location or <file>:<line>")]
+struct BreakpointLocationFromStrError(String);
+
+impl FromStr for BreakpointLocation {
+    type Err = BreakpointLocationFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(location) = OpcodeLocation::from_str(s) {
+            return Ok(BreakpointLocation::Opcode(location));
+        }
+        match s.rsplit_once(':') {
+            Some((file, line_number)) if !file.is_empty() => line_number
+                .parse()
+                .map(|line_number| BreakpointLocation::Source {
+                    file: file.to_string(),
+                    line_number,
+                })
+                .map_err(|_| BreakpointLocationFromStrError(s.to_string())),
+            _ => Err(BreakpointLocationFromStrError(s.to_string())),
+        }
+    }
+}
+
+/// A range of Brillig memory addresses such as `4..8`, or a single address such as `4`.
+#[derive(Debug, Clone)]
+struct MemoryRange(Range<usize>);
+
+#[derive(Debug, Error)]
+#[error("Invalid memory range {0}, expected an address such as 4 or a range such as 4..8")]
+struct MemoryRangeFromStrError(String);
+
+impl FromStr for MemoryRange {
+    type Err = MemoryRangeFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_address =
+            |address: &str| address.trim().parse().map_err(|_| MemoryRangeFromStrError(s.into()));
+        match s.split_once("..") {
+            Some((start, end)) => Ok(MemoryRange(parse_address(start)?..parse_address(end)?)),
+            None => {
+                let address = parse_address(s)?;
+                Ok(MemoryRange(address..address + 1))
+            }
+        }
+    }
+}
 
 pub struct ReplDebugger<'a, B: BlackBoxFunctionSolver> {
     context: DebugContext<'a, B>,
     blackbox_solver: &'a B,
     circuit: &'a Circuit,
+    functions: &'a [Circuit],
     debug_artifact: &'a DebugArtifact,
     initial_witness: WitnessMap,
     last_result: DebugCommandResult,
+    /// The watched expressions along with the value each had when last displayed
+    watches: Vec<(String, Option<String>)>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
     pub fn new(
         blackbox_solver: &'a B,
         circuit: &'a Circuit,
+        functions: &'a [Circuit],
         debug_artifact: &'a DebugArtifact,
         initial_witness: WitnessMap,
+        foreign_call_executor: ForeignCallExecutor,
     ) -> Self {
-        let context =
-            DebugContext::new(blackbox_solver, circuit, debug_artifact, initial_witness.clone());
+        let context = DebugContext::new(
+            blackbox_solver,
+            circuit,
+            functions,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+        );
         Self {
             context,
             blackbox_solver,
             circuit,
+            functions,
             debug_artifact,
             initial_witness,
             last_result: DebugCommandResult::Ok,
+            watches: Vec::new(),
         }
     }
 
@@ -208,6 +283,71 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    /// Finds the first line of source code at or after `line_number` in `file`
+    /// which has opcodes mapped to it, printing the reason if there is none.
+    fn resolve_source_line(&self, file: &str, line_number: usize) -> Option<SourceLine> {
+        let files = self.context.find_source_files(file);
+        let file_id = match files.as_slice() {
+            [] => {
+                println!("No source file matching {file}");
+                return None;
+            }
+            [file_id] => *file_id,
+            _ => {
+                println!("Source file {file} is ambiguous, it matches {} files", files.len());
+                return None;
+            }
+        };
+        if line_number == 0 {
+            println!("Invalid line number {line_number}, lines are numbered from 1");
+            return None;
+        }
+
+        let line = self.context.find_source_line(file_id, line_number - 1);
+        if line.is_none() {
+            println!("No code found at or after line {line_number} of {file}");
+        }
+        line
+    }
+
+    fn add_source_breakpoint_at(&mut self, file: &str, line_number: usize) {
+        let Some(line) = self.resolve_source_line(file, line_number) else { return };
+        let line_name = self.context.source_line_name(&line);
+        if self.context.add_source_breakpoint(line) {
+            println!("Added breakpoint at {line_name}");
+        } else {
+            println!("Breakpoint at {line_name} already set");
+        }
+    }
+
+    fn delete_source_breakpoint_at(&mut self, file: &str, line_number: usize) {
+        let Some(line) = self.resolve_source_line(file, line_number) else { return };
+        let line_name = self.context.source_line_name(&line);
+        if self.context.delete_source_breakpoint(&line) {
+            println!("Breakpoint at {line_name} deleted");
+        } else {
+            println!("Breakpoint at {line_name} not set");
+        }
+    }
+
+    fn add_breakpoint(&mut self, location: BreakpointLocation) {
+        match location {
+            BreakpointLocation::Opcode(location) => self.add_breakpoint_at(location),
+            BreakpointLocation::Source { file, line_number } => {
+                self.add_source_breakpoint_at(&file, line_number);
+            }
+        }
+    }
+
+    fn delete_breakpoint(&mut self, location: BreakpointLocation) {
+        match location {
+            BreakpointLocation::Opcode(location) => self.delete_breakpoint_at(location),
+            BreakpointLocation::Source { file, line_number } => {
+                self.delete_source_breakpoint_at(&file, line_number);
+            }
+        }
+    }
+
     fn validate_in_progress(&self) -> bool {
         match self.last_result {
             DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
@@ -235,6 +375,84 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
         self.last_result = result;
         self.show_current_vm_status();
+        self.show_watches();
+    }
+
+    fn warn_if_no_variable_info(&self) -> bool {
+        if !self.context.has_variable_info() {
+            println!("The program was not compiled for the debugger so variables are unavailable");
+        }
+        self.context.has_variable_info()
+    }
+
+    fn show_vars(&self) {
+        if !self.warn_if_no_variable_info() {
+            return;
+        }
+        let variables = self.context.get_variables();
+        match self.context.get_current_function() {
+            Some(function) if !variables.is_empty() => println!("Variables in {function}:"),
+            _ => {
+                println!("No variables in the current scope");
+                return;
+            }
+        }
+        for (name, value, typ) in variables {
+            println!("  {name}: {}", display_value(value, typ));
+        }
+    }
+
+    fn print_expression(&self, path: &str) {
+        if !self.warn_if_no_variable_info() {
+            return;
+        }
+        match self.context.evaluate_path(path) {
+            Ok((value, typ)) => println!("{path} = {}", display_value(&value, &typ)),
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    fn add_watch(&mut self, path: String) {
+        if !self.warn_if_no_variable_info() {
+            return;
+        }
+        if self.watches.iter().any(|(watched_path, _)| *watched_path == path) {
+            println!("Already watching {path}");
+            return;
+        }
+        println!("Watching {path}");
+        self.watches.push((path, None));
+        self.show_watches();
+    }
+
+    fn delete_watch(&mut self, path: &str) {
+        let watch_count = self.watches.len();
+        self.watches.retain(|(watched_path, _)| watched_path != path);
+        if self.watches.len() < watch_count {
+            println!("Stopped watching {path}");
+        } else {
+            println!("Not watching {path}");
+        }
+    }
+
+    /// Displays the current value of every watched expression, noting those which have changed.
+    fn show_watches(&mut self) {
+        for (path, last_value) in &mut self.watches {
+            let value = self
+                .context
+                .evaluate_path(path)
+                .ok()
+                .map(|(value, typ)| display_value(&value, &typ));
+            let changed = last_value.is_some() && value != *last_value;
+            match &value {
+                Some(value) if changed => println!("watch: {path} = {value} (changed)"),
+                Some(value) => println!("watch: {path} = {value}"),
+                None => println!("watch: {path} is not available in the current scope"),
+            }
+            if value.is_some() {
+                *last_value = value;
+            }
+        }
     }
 
     fn step_acir_opcode(&mut self) {
@@ -258,6 +476,28 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    fn step_in(&mut self) {
+        if self.validate_in_progress() {
+            let result = self.context.step_in();
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    fn step_out(&mut self) {
+        if self.validate_in_progress() {
+            let result = self.context.step_out();
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.validate_in_progress() {
+            println!("(Finishing execution...)");
+            let result = self.context.finish();
+            self.handle_debug_command_result(result);
+        }
+    }
+
     fn cont(&mut self) {
         if self.validate_in_progress() {
             println!("(Continuing execution...)");
@@ -266,18 +506,139 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    fn validate_not_at_start(&self) -> bool {
+        if self.context.executed_steps() == 0 {
+            println!("Already at the start of the program");
+            return false;
+        }
+        true
+    }
+
+    fn reverse_step_into_opcode(&mut self) {
+        if self.validate_not_at_start() {
+            let result = self.context.reverse_step_into_opcode();
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    fn reverse_step(&mut self) {
+        if self.validate_not_at_start() {
+            let result = self.context.reverse_step();
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    fn reverse_continue(&mut self) {
+        if self.validate_not_at_start() {
+            println!("(Continuing execution backwards...)");
+            let result = self.context.reverse_continue();
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    fn jump_to_opcode(&mut self, location: OpcodeLocation) {
+        if !self.context.is_valid_opcode_location(&location) {
+            println!("Invalid opcode location {location}");
+            return;
+        }
+        let result = self.context.jump_to_opcode(location);
+        self.handle_debug_command_result(result);
+    }
+
+    fn show_witness_map(&self) {
+        for (witness, value) in self.context.get_witness_map().clone() {
+            println!("_{} = {value}", witness.witness_index());
+        }
+    }
+
+    fn show_witness(&self, index: u32) {
+        match self.context.get_witness_map().get_index(index) {
+            Some(value) => println!("_{index} = {value}"),
+            None => println!("Witness _{index} has not been solved"),
+        }
+    }
+
+    fn update_witness(&mut self, index: u32, value: String) {
+        let Some(field_value) = FieldElement::try_from_str(&value) else {
+            println!("Invalid witness value {value}");
+            return;
+        };
+        self.context.overwrite_witness(Witness(index), field_value);
+        println!("_{index} = {value}");
+    }
+
+    fn show_brillig_registers(&self) {
+        let Some(registers) = self.context.get_brillig_registers() else {
+            println!("Not executing a Brillig block");
+            return;
+        };
+        if registers.inner.is_empty() {
+            println!("Brillig VM registers not available");
+            return;
+        }
+        for (index, value) in registers.inner.iter().enumerate() {
+            println!("{index} = {}", value.to_field());
+        }
+    }
+
+    fn set_brillig_register(&mut self, index: usize, value: String) {
+        let Some(field_value) = FieldElement::try_from_str(&value) else {
+            println!("Invalid register value {value}");
+            return;
+        };
+        match self.context.set_brillig_register(index, field_value) {
+            Ok(()) => println!("R{index} = {value}"),
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    fn show_brillig_memory(&self, range: Option<MemoryRange>) {
+        let Some(memory) = self.context.get_brillig_memory() else {
+            println!("Not executing a Brillig block");
+            return;
+        };
+        let range = range.map_or(0..memory.len(), |MemoryRange(range)| range);
+        if range.start >= memory.len() {
+            println!("Brillig VM memory has {} values", memory.len());
+            return;
+        }
+        let end = range.end.min(memory.len());
+        for (address, value) in memory[range.start..end].iter().enumerate() {
+            println!("{} = {}", range.start + address, value.to_field());
+        }
+    }
+
+    fn write_brillig_memory(&mut self, address: usize, value: String) {
+        let Some(field_value) = FieldElement::try_from_str(&value) else {
+            println!("Invalid memory value {value}");
+            return;
+        };
+        match self.context.write_brillig_memory(address, field_value) {
+            Ok(()) => println!("[{address}] = {value}"),
+            Err(error) => println!("{error}"),
+        }
+    }
+
     fn restart_session(&mut self) {
         let breakpoints: Vec<OpcodeLocation> =
             self.context.iterate_breakpoints().copied().collect();
+        let source_breakpoints: Vec<SourceLine> =
+            self.context.iterate_source_breakpoints().copied().collect();
+        let foreign_call_executor = self.context.take_foreign_call_executor();
         self.context = DebugContext::new(
             self.blackbox_solver,
             self.circuit,
+            self.functions,
             self.debug_artifact,
             self.initial_witness.clone(),
+            foreign_call_executor,
         );
         for opcode_location in breakpoints {
             self.context.add_breakpoint(opcode_location);
         }
+        for source_line in source_breakpoints {
+            self.context.add_source_breakpoint(source_line);
+        }
         self.last_result = DebugCommandResult::Ok;
         println!("Restarted debugging session.");
         self.show_current_vm_status();
@@ -303,11 +664,19 @@ fn print_dimmed_line(line_number: usize, line: &str) {
 pub fn run<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     circuit: &Circuit,
+    functions: &[Circuit],
     debug_artifact: &DebugArtifact,
     initial_witness: WitnessMap,
+    foreign_call_executor: ForeignCallExecutor,
 ) -> Result<Option<WitnessMap>, NargoError> {
-    let context =
-        RefCell::new(ReplDebugger::new(blackbox_solver, circuit, debug_artifact, initial_witness));
+    let context = RefCell::new(ReplDebugger::new(
+        blackbox_solver,
+        circuit,
+        functions,
+        debug_artifact,
+        initial_witness,
+        foreign_call_executor,
+    ));
     let ref_context = &context;
 
     ref_context.borrow().show_current_vm_status();
@@ -336,13 +705,33 @@ pub fn run<B: BlackBoxFunctionSolver>(
         .add(
             "next",
             command! {
-                "step until a new source location is reached",
+                "step to the next source line, stepping over function calls",
                 () => || {
                     ref_context.borrow_mut().next();
                     Ok(CommandStatus::Done)
                 }
             },
         )
+        .add(
+            "step-in",
+            command! {
+                "step to the next source line, stepping into function calls",
+                () => || {
+                    ref_context.borrow_mut().step_in();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "step-out",
+            command! {
+                "step until the current function returns to its caller",
+                () => || {
+                    ref_context.borrow_mut().step_out();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "continue",
             command! {
@@ -353,6 +742,56 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "finish",
+            command! {
+                "continue execution until the end of the program, ignoring breakpoints",
+                () => || {
+                    ref_context.borrow_mut().finish();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-into",
+            command! {
+                "step back to the previously executed opcode",
+                () => || {
+                    ref_context.borrow_mut().reverse_step_into_opcode();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-step",
+            command! {
+                "step back to the start of the previously executed source line",
+                () => || {
+                    ref_context.borrow_mut().reverse_step();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-continue",
+            command! {
+                "run backwards until the previous breakpoint or the start of the program",
+                () => || {
+                    ref_context.borrow_mut().reverse_continue();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "goto",
+            command! {
+                "travel to the first execution of an opcode location, backwards or forwards",
+                (LOCATION:OpcodeLocation) => |location| {
+                    ref_context.borrow_mut().jump_to_opcode(location);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "restart",
             command! {
@@ -373,12 +812,144 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "vars",
+            command! {
+                "show the variables in the current scope",
+                () => || {
+                    ref_context.borrow().show_vars();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "print",
+            command! {
+                "show the value of a variable or a path within it, such as a.b[2]",
+                (PATH:String) => |path| {
+                    ref_context.borrow().print_expression(&path);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "watch",
+            command! {
+                "show the value of a variable or a path within it whenever execution stops",
+                (PATH:String) => |path| {
+                    ref_context.borrow_mut().add_watch(path);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "stop watching a variable or a path within it",
+                (PATH:String) => |path| {
+                    ref_context.borrow_mut().delete_watch(&path);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "witness",
+            command! {
+                "show the witness map",
+                () => || {
+                    ref_context.borrow().show_witness_map();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "witness",
+            command! {
+                "show the value of a witness",
+                (INDEX:u32) => |index| {
+                    ref_context.borrow().show_witness(index);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "witness",
+            command! {
+                "set the value of a witness: witness set <index> <value>",
+                (SET:String, INDEX:u32, VALUE:String) => |set, index, value| {
+                    if set == "set" {
+                        ref_context.borrow_mut().update_witness(index, value);
+                    } else {
+                        println!("Unknown witness command {set}, expected `witness set`");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "registers",
+            command! {
+                "show the registers of the Brillig VM",
+                () => || {
+                    ref_context.borrow().show_brillig_registers();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "registers",
+            command! {
+                "set the value of a register of the Brillig VM: registers set <index> <value>",
+                (SET:String, INDEX:usize, VALUE:String) => |set, index, value| {
+                    if set == "set" {
+                        ref_context.borrow_mut().set_brillig_register(index, value);
+                    } else {
+                        println!("Unknown registers command {set}, expected `registers set`");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory",
+            command! {
+                "show the memory of the Brillig VM",
+                () => || {
+                    ref_context.borrow().show_brillig_memory(None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory",
+            command! {
+                "show a range of the memory of the Brillig VM, such as 4..8",
+                (RANGE:MemoryRange) => |range| {
+                    ref_context.borrow().show_brillig_memory(Some(range));
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory",
+            command! {
+                "write to the memory of the Brillig VM: memory set <address> <value>",
+                (SET:String, ADDRESS:usize, VALUE:String) => |set, address, value| {
+                    if set == "set" {
+                        ref_context.borrow_mut().write_brillig_memory(address, value);
+                    } else {
+                        println!("Unknown memory command {set}, expected `memory set`");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "break",
             command! {
-                "add a breakpoint at an opcode location",
-                (LOCATION:OpcodeLocation) => |location| {
-                    ref_context.borrow_mut().add_breakpoint_at(location);
+                "add a breakpoint at an opcode location or a source line (<file>:<line>)",
+                (LOCATION:BreakpointLocation) => |location| {
+                    ref_context.borrow_mut().add_breakpoint(location);
                     Ok(CommandStatus::Done)
                 }
             },
@@ -386,9 +957,9 @@ pub fn run<B: BlackBoxFunctionSolver>(
         .add(
             "delete",
             command! {
-                "delete breakpoint at an opcode location",
-                (LOCATION:OpcodeLocation) => |location| {
-                    ref_context.borrow_mut().delete_breakpoint_at(location);
+                "delete breakpoint at an opcode location or a source line (<file>:<line>)",
+                (LOCATION:BreakpointLocation) => |location| {
+                    ref_context.borrow_mut().delete_breakpoint(location);
                     Ok(CommandStatus::Done)
                 }
             },
@@ -409,3 +980,81 @@ pub fn run<B: BlackBoxFunctionSolver>(
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::brillig::RegisterIndex;
+    use acvm::FieldElement;
+    use nargo::ops::ForeignCallExecutor;
+    use noirc_driver::CompileOptions;
+
+    use crate::test_utils::{compile_program, initial_witness, StubbedSolver};
+
+    use super::{MemoryRange, ReplDebugger};
+
+    /// Fails unless `x` is 3.
+    const PROGRAM: &str = "unconstrained fn double(x: Field) -> Field {
+    x * 2
+}
+
+fn main(x: Field) {
+    assert(double(x) == 6);
+}
+";
+
+    #[test]
+    fn memory_ranges_are_parsed() {
+        assert_eq!("4".parse::<MemoryRange>().unwrap().0, 4..5);
+        assert_eq!("4..8".parse::<MemoryRange>().unwrap().0, 4..8);
+        assert!("4..".parse::<MemoryRange>().is_err());
+        assert!("four".parse::<MemoryRange>().is_err());
+    }
+
+    #[test]
+    fn state_editing_commands_update_the_program_state() {
+        let (program, debug_artifact) = compile_program(PROGRAM, &CompileOptions::default());
+        let initial_witness = initial_witness(&program, &[("x", 2)]);
+        let x_witness = program.abi.param_witnesses["x"][0].start;
+        let mut repl = ReplDebugger::new(
+            &StubbedSolver,
+            &program.circuit,
+            &program.functions,
+            &debug_artifact,
+            initial_witness,
+            ForeignCallExecutor::default(),
+        );
+
+        // `registers set` and `memory set` require a Brillig block to be executing.
+        repl.set_brillig_register(100, "7".to_string());
+        repl.write_brillig_memory(1000, "9".to_string());
+        assert!(repl.context.get_brillig_registers().is_none());
+        assert_eq!(repl.context.executed_steps(), 0);
+
+        repl.step_into_opcode();
+        repl.set_brillig_register(100, "7".to_string());
+        repl.set_brillig_register(100, "seven".to_string());
+        repl.set_brillig_register(70000, "7".to_string());
+        let registers = repl.context.get_brillig_registers().unwrap();
+        assert_eq!(registers.get(RegisterIndex(100)).to_field(), FieldElement::from(7_u128));
+        repl.show_brillig_registers();
+
+        repl.write_brillig_memory(1000, "9".to_string());
+        repl.write_brillig_memory(1000, "nine".to_string());
+        repl.write_brillig_memory(usize::MAX, "9".to_string());
+        let memory = repl.context.get_brillig_memory().unwrap();
+        assert_eq!(memory[1000].to_field(), FieldElement::from(9_u128));
+        repl.show_brillig_memory(Some(MemoryRange(999..1001)));
+        repl.show_brillig_memory(Some(MemoryRange(5000..5001)));
+
+        // `witness set` makes the program succeed once the session is restarted.
+        repl.restart_session();
+        repl.update_witness(x_witness.witness_index(), "3".to_string());
+        repl.update_witness(x_witness.witness_index(), "three".to_string());
+        assert_eq!(repl.context.get_witness_map()[&x_witness], FieldElement::from(3_u128));
+        repl.show_witness(x_witness.witness_index());
+        repl.show_witness_map();
+
+        repl.cont();
+        assert!(repl.is_solved());
+    }
+}
@@ -0,0 +1,73 @@
+//! Helpers for debugging small Noir programs within unit tests.
+use std::path::Path;
+
+use acvm::acir::native_types::WitnessMap;
+use acvm::{acir::BlackBoxFunc, BlackBoxFunctionSolver, BlackBoxResolutionError, FieldElement};
+use fm::FileManager;
+use nargo::artifacts::debug::DebugArtifact;
+use noirc_abi::{input_parser::InputValue, InputMap};
+use noirc_driver::{compile_main, prepare_crate, CompileOptions, CompiledProgram};
+use noirc_frontend::{graph::CrateGraph, hir::Context};
+
+/// A solver for programs which do not make use of any black box functions.
+pub(crate) struct StubbedSolver;
+
+impl BlackBoxFunctionSolver for StubbedSolver {
+    fn schnorr_verify(
+        &self,
+        _public_key_x: &FieldElement,
+        _public_key_y: &FieldElement,
+        _signature: &[u8],
+        _message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::SchnorrVerify))
+    }
+    fn pedersen_commitment(
+        &self,
+        _inputs: &[FieldElement],
+        _domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::PedersenCommitment))
+    }
+    fn pedersen_hash(
+        &self,
+        _inputs: &[FieldElement],
+        _domain_separator: u32,
+    ) -> Result<FieldElement, BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::PedersenHash))
+    }
+    fn fixed_base_scalar_mul(
+        &self,
+        _low: &FieldElement,
+        _high: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::FixedBaseScalarMul))
+    }
+}
+
+/// Compiles `source` as the `main.nr` of a binary crate, returning the unoptimized program
+/// along with a debug artifact holding the source of `main.nr`.
+pub(crate) fn compile_program(
+    source: &str,
+    options: &CompileOptions,
+) -> (CompiledProgram, DebugArtifact) {
+    let source = source.to_string();
+    let file_manager =
+        FileManager::new(Path::new("/test"), Box::new(move |_path| Ok(source.clone())));
+    let mut context = Context::new(file_manager, CrateGraph::default());
+    let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+
+    let (program, _) = compile_main(&mut context, crate_id, options, None, true)
+        .expect("test program should compile");
+    let debug_artifact = DebugArtifact::new(vec![program.debug.clone()], &context.file_manager);
+    (program, debug_artifact)
+}
+
+/// Encodes the given values of `main`'s parameters as the program's initial witness.
+pub(crate) fn initial_witness(program: &CompiledProgram, inputs: &[(&str, u128)]) -> WitnessMap {
+    let inputs: InputMap = inputs
+        .iter()
+        .map(|(name, value)| (name.to_string(), InputValue::Field(FieldElement::from(*value))))
+        .collect();
+    program.abi.encode(&inputs, None).expect("test inputs should match the program's ABI")
+}
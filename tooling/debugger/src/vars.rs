@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult, Value};
+use acvm::acir::circuit::OpcodeLocation;
+use acvm::acir::native_types::WitnessMap;
+use acvm::brillig_vm::Registers;
+use acvm::pwg::{get_value, ForeignCallWaitInfo};
+use acvm::FieldElement;
+use noirc_errors::debug_info::{
+    DebugInfo, DebugValueSource, DebugVarAssignment, DebugVariable, DEBUG_FN_ENTER_ORACLE,
+    DEBUG_FN_EXIT_ORACLE, DEBUG_VAR_ASSIGN_ORACLE,
+};
+use noirc_errors::Location;
+use noirc_printable_type::{decode_value, PrintableType, PrintableValue, PrintableValueDisplay};
+
+/// A function which is being executed.
+#[derive(Clone)]
+struct StackFrame {
+    function: String,
+    /// The location of the call to the function, for programs compiled with variable locations
+    call_site: Option<Location>,
+    /// The current values of the variables of the function which have been assigned, keyed by id
+    values: BTreeMap<u32, PrintableValue>,
+}
+
+/// Tracks the values of the variables of a program, either using the calls which a program
+/// compiled with debug instrumentation makes to the debug oracles, or by reading the values from
+/// the locations recorded in the debug info of a program compiled with variable locations.
+#[derive(Clone)]
+pub(super) struct DebugVars<'a> {
+    debug_info: &'a DebugInfo,
+    frames: Vec<StackFrame>,
+    /// The variable assignments recorded at ACIR opcodes along with the index of the opcode,
+    /// in order of execution
+    acir_assignments: Vec<(usize, &'a DebugVarAssignment)>,
+    /// The number of variable assignments recorded at ACIR opcodes which have been applied
+    applied_acir_assignments: usize,
+}
+
+/// A step in a path to a value nested within a variable, e.g. `.x` or `[2]` in `point.x` or `points[2]`.
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+impl<'a> DebugVars<'a> {
+    pub(super) fn new(debug_info: &'a DebugInfo) -> Self {
+        let acir_assignments = debug_info
+            .variable_assignments
+            .iter()
+            .filter_map(|(location, assignments)| match location {
+                OpcodeLocation::Acir(acir_index) => Some((*acir_index, assignments)),
+                OpcodeLocation::Brillig { .. } => None,
+            })
+            .flat_map(|(acir_index, assignments)| {
+                assignments.iter().map(move |assignment| (acir_index, assignment))
+            })
+            .collect();
+        Self { debug_info, frames: Vec::new(), acir_assignments, applied_acir_assignments: 0 }
+    }
+
+    /// Whether the program was compiled with information about its variables.
+    pub(super) fn has_variable_info(&self) -> bool {
+        !self.debug_info.variables.is_empty() || !self.debug_info.functions.is_empty()
+    }
+
+    /// Updates the tracked variables if `foreign_call` is a call to one of the debug oracles,
+    /// returning the result of the call. Returns `None` for any other foreign call.
+    pub(super) fn handle_foreign_call(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Option<ForeignCallResult> {
+        match foreign_call.function.as_str() {
+            DEBUG_FN_ENTER_ORACLE => {
+                let function_id = foreign_call.inputs.first().map(param_to_u32)?;
+                let function = self
+                    .debug_info
+                    .functions
+                    .get(&function_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("<function {function_id}>"));
+                self.frames.push(StackFrame { function, call_site: None, values: BTreeMap::new() });
+            }
+            DEBUG_FN_EXIT_ORACLE => {
+                self.frames.pop();
+            }
+            DEBUG_VAR_ASSIGN_ORACLE => {
+                let (var_id, value_params) = foreign_call.inputs.split_first()?;
+                let var_id = param_to_u32(var_id);
+                if let Some(DebugVariable { typ, .. }) = self.debug_info.variables.get(&var_id) {
+                    let mut fields = value_params
+                        .iter()
+                        .flat_map(|param| param.values().into_iter().map(|value| value.to_field()));
+                    let value = decode_value(&mut fields, typ);
+                    self.current_frame_mut().values.insert(var_id, value);
+                }
+            }
+            _ => return None,
+        }
+        Some(ForeignCallResult { values: vec![] })
+    }
+
+    fn current_frame_mut(&mut self) -> &mut StackFrame {
+        if self.frames.is_empty() {
+            self.frames.push(StackFrame {
+                function: "<unknown>".to_string(),
+                call_site: None,
+                values: BTreeMap::new(),
+            });
+        }
+        self.frames.last_mut().expect("a frame was just pushed")
+    }
+
+    /// Applies the variable assignments recorded in the debug info which take place before
+    /// execution reaches `location`, or all of the remaining assignments if execution has finished.
+    ///
+    /// ACIR opcodes are executed in order, so the assignments recorded at ACIR opcodes are applied
+    /// once each. Brillig opcodes may be executed repeatedly by loops and calls, so the assignments
+    /// recorded at a Brillig opcode are applied each time execution reaches the opcode, reading the
+    /// assigned values from the `brillig` registers and memory. Assignments recorded at the Brillig
+    /// opcodes of a block which was executed all at once are skipped, as are assignments whose
+    /// values are unavailable.
+    pub(super) fn apply_assignments(
+        &mut self,
+        location: Option<OpcodeLocation>,
+        witness_map: &WitnessMap,
+        brillig: Option<(&Registers, &[Value])>,
+    ) {
+        let current_acir_index = location.map(|location| match location {
+            OpcodeLocation::Acir(acir_index) | OpcodeLocation::Brillig { acir_index, .. } => {
+                acir_index
+            }
+        });
+        while let Some((acir_index, assignment)) =
+            self.acir_assignments.get(self.applied_acir_assignments).copied()
+        {
+            if current_acir_index.map_or(false, |current| acir_index > current) {
+                break;
+            }
+            self.applied_acir_assignments += 1;
+            self.apply_assignment(&OpcodeLocation::Acir(acir_index), assignment, witness_map, None);
+        }
+
+        if let Some(location @ OpcodeLocation::Brillig { .. }) = location {
+            let debug_info = self.debug_info;
+            for assignment in debug_info.variable_assignments.get(&location).into_iter().flatten() {
+                self.apply_assignment(&location, assignment, witness_map, brillig);
+            }
+        }
+
+        if let Some(location) = location {
+            let call_stack = self.debug_info.locations.get(&location);
+            if let Some((_, opcode_call_sites)) = call_stack.and_then(|stack| stack.split_last()) {
+                let mut call_sites = self.brillig_call_sites(&location);
+                call_sites.extend(opcode_call_sites.iter().copied());
+                self.enter_call_sites(&call_sites);
+            }
+        }
+    }
+
+    /// Updates the value of the variable assigned by `assignment` at `location`,
+    /// unless its value is unavailable.
+    fn apply_assignment(
+        &mut self,
+        location: &OpcodeLocation,
+        assignment: &DebugVarAssignment,
+        witness_map: &WitnessMap,
+        brillig: Option<(&Registers, &[Value])>,
+    ) {
+        let Some(fields) = assignment
+            .sources
+            .iter()
+            .map(|source| read_source(source, witness_map, brillig))
+            .collect::<Option<Vec<Vec<FieldElement>>>>()
+        else {
+            return;
+        };
+        let debug_info = self.debug_info;
+        let Some(variable) = debug_info.variables.get(&assignment.var_id) else {
+            return;
+        };
+
+        let mut call_sites = self.brillig_call_sites(location);
+        call_sites.extend(assignment.call_sites.iter().copied());
+        self.enter_call_sites(&call_sites);
+
+        let function = self.function_name(variable.function);
+        let value = decode_value(&mut fields.into_iter().flatten(), &variable.typ);
+        let frame = self.current_frame_mut();
+        frame.function = function;
+        frame.values.insert(assignment.var_id, value);
+    }
+
+    /// Returns the call stack of the ACIR opcode executing a Brillig block if `location` is within
+    /// the block, as the call stacks of Brillig opcodes only cover the Brillig function.
+    fn brillig_call_sites(&self, location: &OpcodeLocation) -> Vec<Location> {
+        match location {
+            OpcodeLocation::Brillig { acir_index, .. } => self
+                .debug_info
+                .locations
+                .get(&OpcodeLocation::Acir(*acir_index))
+                .cloned()
+                .unwrap_or_default(),
+            OpcodeLocation::Acir(_) => Vec::new(),
+        }
+    }
+
+    /// Updates the stack frames to match the given call sites, popping the frames of the
+    /// functions which have returned and pushing a frame for each function which has been called.
+    fn enter_call_sites(&mut self, call_sites: &[Location]) {
+        let unchanged_frames = self
+            .frames
+            .iter()
+            .skip(1)
+            .zip(call_sites)
+            .take_while(|(frame, call_site)| frame.call_site.as_ref() == Some(*call_site))
+            .count();
+        self.frames.truncate(unchanged_frames + 1);
+        self.current_frame_mut();
+        for call_site in &call_sites[unchanged_frames..] {
+            self.frames.push(StackFrame {
+                function: "<unknown>".to_string(),
+                call_site: Some(*call_site),
+                values: BTreeMap::new(),
+            });
+        }
+    }
+
+    fn function_name(&self, function_id: u32) -> String {
+        self.debug_info
+            .functions
+            .get(&function_id)
+            .cloned()
+            .unwrap_or_else(|| format!("<function {function_id}>"))
+    }
+
+    /// Returns the name of the instrumented function which is currently being executed.
+    pub(super) fn current_function(&self) -> Option<&str> {
+        self.frames.last().map(|frame| frame.function.as_str())
+    }
+
+    /// Returns the variables of the current function which have been assigned, in the order in
+    /// which they are declared. Variables which have been shadowed by a later declaration are omitted.
+    pub(super) fn current_variables(&self) -> Vec<(&str, &PrintableValue, &PrintableType)> {
+        let Some(frame) = self.frames.last() else { return Vec::new() };
+
+        let mut latest_ids: BTreeMap<&str, u32> = BTreeMap::new();
+        for var_id in frame.values.keys() {
+            let name = self.debug_info.variables[var_id].name.as_str();
+            latest_ids.insert(name, *var_id);
+        }
+
+        frame
+            .values
+            .iter()
+            .filter_map(|(var_id, value)| {
+                let DebugVariable { name, typ, .. } = &self.debug_info.variables[var_id];
+                (latest_ids[name.as_str()] == *var_id).then_some((name.as_str(), value, typ))
+            })
+            .collect()
+    }
+
+    /// Evaluates a path such as `point.x` or `points[2].y` to a value nested within
+    /// one of the variables of the current function.
+    pub(super) fn evaluate_path(
+        &self,
+        path: &str,
+    ) -> Result<(PrintableValue, PrintableType), String> {
+        let (name, segments) = parse_path(path)?;
+        let (_, mut value, mut typ) = self
+            .current_variables()
+            .into_iter()
+            .find(|(var_name, ..)| *var_name == name)
+            .ok_or_else(|| format!("No variable named `{name}` in the current scope"))?;
+
+        for segment in segments {
+            (value, typ) = match (segment, value, typ) {
+                (
+                    PathSegment::Field(field),
+                    PrintableValue::Struct(values),
+                    PrintableType::Struct { name, fields },
+                ) => {
+                    let field_type = fields
+                        .iter()
+                        .find_map(|(field_name, typ)| (*field_name == field).then_some(typ))
+                        .ok_or_else(|| format!("Struct `{name}` has no field named `{field}`"))?;
+                    (&values[&field], field_type)
+                }
+                (
+                    PathSegment::Index(index),
+                    PrintableValue::Vec(values),
+                    PrintableType::Array { length, typ },
+                ) => {
+                    let value = values.get(index).ok_or_else(|| {
+                        format!("Index {index} is out of bounds for an array of length {length}")
+                    })?;
+                    (value, typ.as_ref())
+                }
+                (PathSegment::Field(field), ..) => {
+                    return Err(format!("Cannot access field `{field}` of a non-struct value"))
+                }
+                (PathSegment::Index(index), ..) => {
+                    return Err(format!("Cannot index a non-array value with [{index}]"))
+                }
+            };
+        }
+        Ok((value.clone(), typ.clone()))
+    }
+}
+
+/// Formats a value for display in the debugger.
+pub(super) fn display_value(value: &PrintableValue, typ: &PrintableType) -> String {
+    PrintableValueDisplay::Plain(value.clone(), typ.clone()).to_string()
+}
+
+/// Reads the fields held by `source`, returning `None` if they are not available.
+fn read_source(
+    source: &DebugValueSource,
+    witness_map: &WitnessMap,
+    brillig: Option<(&Registers, &[Value])>,
+) -> Option<Vec<FieldElement>> {
+    match source {
+        DebugValueSource::Expression(expression) => {
+            get_value(expression, witness_map).ok().map(|value| vec![value])
+        }
+        DebugValueSource::Register(register) => {
+            let (registers, _) = brillig?;
+            Some(vec![registers.get(*register).to_field()])
+        }
+        DebugValueSource::Array { pointer, size } => {
+            let (registers, memory) = brillig?;
+            let start = registers.get(*pointer).to_usize();
+            let values = memory.get(start..start + size)?;
+            Some(values.iter().map(|value| value.to_field()).collect())
+        }
+    }
+}
+
+fn param_to_u32(param: &ForeignCallParam) -> u32 {
+    param.unwrap_value().to_field().to_u128() as u32
+}
+
+fn parse_path(path: &str) -> Result<(&str, Vec<PathSegment>), String> {
+    let invalid_path = || format!("Invalid expression `{path}`, expected a path such as `a.b[2]`");
+    let is_identifier_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let path = path.trim();
+    let name_end = path.find(|c| !is_identifier_char(c)).unwrap_or(path.len());
+    let (name, mut rest) = path.split_at(name_end);
+    if name.is_empty() {
+        return Err(invalid_path());
+    }
+
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let field_end = after_dot.find(|c| !is_identifier_char(c)).unwrap_or(after_dot.len());
+            let (field, remaining) = after_dot.split_at(field_end);
+            if field.is_empty() {
+                return Err(invalid_path());
+            }
+            segments.push(PathSegment::Field(field.to_string()));
+            rest = remaining;
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let (index, remaining) = after_bracket.split_once(']').ok_or_else(invalid_path)?;
+            let index = index.trim().parse().map_err(|_| invalid_path())?;
+            segments.push(PathSegment::Index(index));
+            rest = remaining;
+        } else {
+            return Err(invalid_path());
+        }
+    }
+    Ok((name, segments))
+}
@@ -0,0 +1,174 @@
+#![forbid(unsafe_code)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
+
+//! A differential fuzzer for the Noir compiler.
+//!
+//! Random Noir programs are generated and executed once compiled to ACIR and once compiled
+//! entirely to Brillig. Any disagreement between the two is reported along with the program
+//! and inputs which caused it so that it can be reproduced.
+
+mod program;
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use fm::FileManager;
+use nargo::ops::{run_differential, DifferentialStatus};
+use noirc_abi::{input_parser::InputValue, InputMap};
+use noirc_driver::{check_crate, prepare_crate, CompileOptions};
+use noirc_errors::FileDiagnostic;
+use noirc_frontend::{graph::CrateGraph, hir::Context};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use program::{GeneratedProgram, GeneratorConfig, ProgramGenerator};
+
+/// Generate random Noir programs and check that they behave identically
+/// when compiled to ACIR and to Brillig
+#[derive(Debug, Parser)]
+#[command(name = "noir-differential-fuzzer", version)]
+struct FuzzerArgs {
+    /// Seed for the first generated program. A random seed is used if not provided
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Number of programs to generate
+    #[arg(long, default_value_t = 100)]
+    iterations: u64,
+
+    /// Directory in which to save programs which produce a mismatch
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Maximum number of helper functions in each program
+    #[arg(long, default_value_t = 3)]
+    max_functions: usize,
+
+    /// Maximum number of statements in each function
+    #[arg(long, default_value_t = 6)]
+    max_statements: usize,
+
+    /// Maximum nesting depth of generated expressions
+    #[arg(long, default_value_t = 3)]
+    max_depth: usize,
+}
+
+/// The result of fuzzing a single generated program.
+enum FuzzOutcome {
+    Match,
+    /// The generated program was rejected by the frontend. This is a bug in the generator.
+    Invalid(Vec<FileDiagnostic>),
+    /// ACIR and Brillig execution disagree, or one of them failed to compile.
+    Failure(String),
+}
+
+fn main() {
+    let args = FuzzerArgs::parse();
+
+    let first_seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let config = GeneratorConfig {
+        max_functions: args.max_functions,
+        max_parameters: 4,
+        max_statements: args.max_statements,
+        max_expression_depth: args.max_depth,
+        max_loop_iterations: 8,
+    };
+
+    #[allow(deprecated)]
+    let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
+
+    let mut failures = 0;
+    let mut invalid = 0;
+    for iteration in 0..args.iterations {
+        let seed = first_seed.wrapping_add(iteration);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let program = ProgramGenerator::new(&mut rng, config).generate();
+
+        match fuzz_program(&blackbox_solver, &program) {
+            FuzzOutcome::Match => {}
+            FuzzOutcome::Invalid(diagnostics) => {
+                eprintln!("[seed {seed}] Generated program failed to type check:");
+                for diagnostic in diagnostics {
+                    eprintln!("    {}", diagnostic.diagnostic.message);
+                }
+                eprintln!("{}", program.source);
+                invalid += 1;
+            }
+            FuzzOutcome::Failure(message) => {
+                eprintln!("[seed {seed}] {message}");
+                eprintln!("inputs: {:?}", program.inputs);
+                eprintln!("{}", program.source);
+                if let Some(output_dir) = &args.output_dir {
+                    save_program(output_dir, seed, &program);
+                }
+                failures += 1;
+            }
+        }
+    }
+
+    println!(
+        "Fuzzed {} programs starting from seed {first_seed}: {failures} mismatches, {invalid} invalid programs",
+        args.iterations
+    );
+    if failures != 0 || invalid != 0 {
+        std::process::exit(1);
+    }
+}
+
+fn fuzz_program<B: acvm::BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    program: &GeneratedProgram,
+) -> FuzzOutcome {
+    let root = Path::new("/fuzz");
+    let source = program.source.clone();
+    let file_manager = FileManager::new(root, Box::new(move |_path| Ok(source.clone())));
+    let mut context = Context::new(file_manager, CrateGraph::default());
+    let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+
+    if let Err(diagnostics) = check_crate(&mut context, crate_id, false) {
+        return FuzzOutcome::Invalid(diagnostics);
+    }
+    let main = context.get_main_function(&crate_id).expect("generated programs contain `main`");
+
+    match run_differential(
+        blackbox_solver,
+        &context,
+        main,
+        &program.inputs,
+        &CompileOptions::default(),
+    ) {
+        DifferentialStatus::Match => FuzzOutcome::Match,
+        DifferentialStatus::Mismatch { acir, brillig } => {
+            FuzzOutcome::Failure(format!("mismatch: ACIR {acir}, Brillig {brillig}"))
+        }
+        DifferentialStatus::CompileError(diagnostic) => {
+            FuzzOutcome::Failure(format!("failed to compile: {}", diagnostic.diagnostic.message))
+        }
+    }
+}
+
+/// Saves a failing program as a Nargo package so that it can be reproduced with `nargo test --differential`.
+fn save_program(output_dir: &Path, seed: u64, program: &GeneratedProgram) {
+    let package_dir = output_dir.join(format!("fuzz_{seed}"));
+    let source_dir = package_dir.join("src");
+    std::fs::create_dir_all(&source_dir).expect("Failed to create output directory");
+
+    let manifest = format!(
+        "[package]\nname = \"fuzz_{seed}\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]\n"
+    );
+    std::fs::write(package_dir.join("Nargo.toml"), manifest).expect("Failed to write Nargo.toml");
+    std::fs::write(source_dir.join("main.nr"), &program.source).expect("Failed to write main.nr");
+    std::fs::write(package_dir.join("Prover.toml"), format_inputs(&program.inputs))
+        .expect("Failed to write Prover.toml");
+}
+
+fn format_inputs(inputs: &InputMap) -> String {
+    inputs
+        .iter()
+        .map(|(name, value)| match value {
+            InputValue::Field(field) => format!("{name} = \"0x{}\"\n", field.to_hex()),
+            _ => unreachable!("generated programs only take scalar inputs"),
+        })
+        .collect()
+}
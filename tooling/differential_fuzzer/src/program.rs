@@ -0,0 +1,367 @@
+//! Generation of random, well-typed Noir programs.
+//!
+//! Programs consist of a number of helper functions followed by a `main` function. Each function
+//! is a sequence of `let` statements, bounded loops and conditional assignments built up from
+//! its parameters, finishing with an expression of the function's return type.
+//! Functions may only call functions which were generated before them so there is no recursion.
+
+use std::fmt::Write;
+
+use acvm::FieldElement;
+use noirc_abi::{input_parser::InputValue, InputMap};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+/// Limits on the size of generated programs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GeneratorConfig {
+    pub(crate) max_functions: usize,
+    pub(crate) max_parameters: usize,
+    pub(crate) max_statements: usize,
+    pub(crate) max_expression_depth: usize,
+    pub(crate) max_loop_iterations: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Field,
+    U8,
+    U32,
+    U64,
+    Bool,
+}
+
+impl Type {
+    const ALL: [Type; 5] = [Type::Field, Type::U8, Type::U32, Type::U64, Type::Bool];
+    const NUMERIC: [Type; 4] = [Type::Field, Type::U8, Type::U32, Type::U64];
+
+    fn is_integer(self) -> bool {
+        matches!(self, Type::U8 | Type::U32 | Type::U64)
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Field => write!(f, "Field"),
+            Type::U8 => write!(f, "u8"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    name: String,
+    typ: Type,
+    mutable: bool,
+}
+
+#[derive(Debug, Clone)]
+struct FunctionSignature {
+    name: String,
+    parameters: Vec<Type>,
+    return_type: Type,
+}
+
+/// A generated program along with a set of inputs for its `main` function.
+pub(crate) struct GeneratedProgram {
+    pub(crate) source: String,
+    pub(crate) inputs: InputMap,
+}
+
+pub(crate) struct ProgramGenerator<'rng> {
+    rng: &'rng mut StdRng,
+    config: GeneratorConfig,
+    functions: Vec<FunctionSignature>,
+    /// Counter used to give every local variable a unique name.
+    next_variable: usize,
+}
+
+impl<'rng> ProgramGenerator<'rng> {
+    pub(crate) fn new(rng: &'rng mut StdRng, config: GeneratorConfig) -> Self {
+        Self { rng, config, functions: Vec::new(), next_variable: 0 }
+    }
+
+    pub(crate) fn generate(mut self) -> GeneratedProgram {
+        let mut source = String::new();
+
+        let helper_count = self.rng.gen_range(0..=self.config.max_functions);
+        for index in 0..helper_count {
+            let signature = FunctionSignature {
+                name: format!("func_{index}"),
+                parameters: self.random_parameters(),
+                return_type: self.random_type(),
+            };
+            self.write_function(&mut source, &signature, false);
+            self.functions.push(signature);
+        }
+
+        let main = FunctionSignature {
+            name: "main".to_string(),
+            parameters: self.random_parameters(),
+            return_type: *Type::NUMERIC.choose(self.rng).unwrap(),
+        };
+        self.write_function(&mut source, &main, true);
+
+        let inputs = main
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, typ)| (format!("p{index}"), self.random_input(*typ)))
+            .collect();
+
+        GeneratedProgram { source, inputs }
+    }
+
+    fn random_type(&mut self) -> Type {
+        *Type::ALL.choose(self.rng).unwrap()
+    }
+
+    fn random_parameters(&mut self) -> Vec<Type> {
+        let count = self.rng.gen_range(1..=self.config.max_parameters.max(1));
+        (0..count).map(|_| self.random_type()).collect()
+    }
+
+    fn random_input(&mut self, typ: Type) -> InputValue {
+        let value: u128 = match typ {
+            Type::Field => self.rng.gen::<u64>().into(),
+            Type::U8 => self.rng.gen::<u8>().into(),
+            Type::U32 => self.rng.gen::<u32>().into(),
+            Type::U64 => self.rng.gen::<u64>().into(),
+            Type::Bool => self.rng.gen::<bool>().into(),
+        };
+        InputValue::Field(FieldElement::from(value))
+    }
+
+    fn fresh_name(&mut self) -> String {
+        let name = format!("v{}", self.next_variable);
+        self.next_variable += 1;
+        name
+    }
+
+    fn write_function(
+        &mut self,
+        source: &mut String,
+        signature: &FunctionSignature,
+        is_main: bool,
+    ) {
+        let mut scope: Vec<Variable> = signature
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, typ)| Variable { name: format!("p{index}"), typ: *typ, mutable: false })
+            .collect();
+
+        let parameters: Vec<String> =
+            scope.iter().map(|variable| format!("{}: {}", variable.name, variable.typ)).collect();
+        let visibility = if is_main { "pub " } else { "" };
+        writeln!(
+            source,
+            "fn {}({}) -> {visibility}{} {{",
+            signature.name,
+            parameters.join(", "),
+            signature.return_type
+        )
+        .unwrap();
+
+        let statement_count = self.rng.gen_range(1..=self.config.max_statements.max(1));
+        for _ in 0..statement_count {
+            self.write_statement(source, &mut scope);
+        }
+
+        let result =
+            self.expression(signature.return_type, &scope, self.config.max_expression_depth);
+        writeln!(source, "    {result}\n}}\n").unwrap();
+    }
+
+    fn write_statement(&mut self, source: &mut String, scope: &mut Vec<Variable>) {
+        let depth = self.config.max_expression_depth;
+        match self.rng.gen_range(0..4) {
+            // let v: T = expr;
+            0 | 1 => {
+                let typ = self.random_type();
+                let value = self.expression(typ, scope, depth);
+                let mutable = self.rng.gen_bool(0.5);
+                let name = self.fresh_name();
+                let keyword = if mutable { "let mut" } else { "let" };
+                writeln!(source, "    {keyword} {name}: {typ} = {value};").unwrap();
+                scope.push(Variable { name, typ, mutable });
+            }
+            // for i in 0..N { v = expr; }
+            2 => {
+                let Some(target) = self.random_mutable_variable(scope) else { return };
+                let iterations = self.rng.gen_range(1..=self.config.max_loop_iterations.max(1));
+                let index = self.fresh_name();
+
+                let mut loop_scope = scope.clone();
+                loop_scope.push(Variable { name: index.clone(), typ: Type::U32, mutable: false });
+                let value = self.expression(target.typ, &loop_scope, depth);
+                writeln!(
+                    source,
+                    "    for {index} in 0..{iterations} {{\n        {} = {value};\n    }}",
+                    target.name
+                )
+                .unwrap();
+            }
+            // if cond { v = expr; } else { v = expr; }
+            _ => {
+                let Some(target) = self.random_mutable_variable(scope) else { return };
+                let condition = self.expression(Type::Bool, scope, depth);
+                let then_value = self.expression(target.typ, scope, depth);
+                let else_value = self.expression(target.typ, scope, depth);
+                writeln!(
+                    source,
+                    "    if {condition} {{\n        {name} = {then_value};\n    }} else {{\n        {name} = {else_value};\n    }}",
+                    name = target.name
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn random_mutable_variable(&mut self, scope: &[Variable]) -> Option<Variable> {
+        let mutable: Vec<&Variable> = scope.iter().filter(|variable| variable.mutable).collect();
+        mutable.choose(self.rng).map(|variable| (*variable).clone())
+    }
+
+    fn expression(&mut self, typ: Type, scope: &[Variable], depth: usize) -> String {
+        if depth == 0 || self.rng.gen_bool(0.3) {
+            return self.leaf(typ, scope);
+        }
+
+        match self.rng.gen_range(0..4) {
+            0 => self.binary_expression(typ, scope, depth - 1),
+            1 => self.cast_expression(typ, scope, depth - 1),
+            2 => {
+                let condition = self.expression(Type::Bool, scope, depth - 1);
+                let then_value = self.expression(typ, scope, depth - 1);
+                let else_value = self.expression(typ, scope, depth - 1);
+                format!("if {condition} {{ {then_value} }} else {{ {else_value} }}")
+            }
+            _ => self
+                .call_expression(typ, scope, depth - 1)
+                .unwrap_or_else(|| self.binary_expression(typ, scope, depth - 1)),
+        }
+    }
+
+    fn leaf(&mut self, typ: Type, scope: &[Variable]) -> String {
+        let candidates: Vec<&Variable> =
+            scope.iter().filter(|variable| variable.typ == typ).collect();
+        if !candidates.is_empty() && self.rng.gen_bool(0.7) {
+            return candidates.choose(self.rng).unwrap().name.clone();
+        }
+        self.literal(typ)
+    }
+
+    fn literal(&mut self, typ: Type) -> String {
+        match typ {
+            Type::Field => self.rng.gen::<u32>().to_string(),
+            Type::U8 => self.rng.gen_range(0..16u8).to_string(),
+            Type::U32 => self.rng.gen_range(0..1024u32).to_string(),
+            Type::U64 => self.rng.gen_range(0..65536u64).to_string(),
+            Type::Bool => self.rng.gen::<bool>().to_string(),
+        }
+    }
+
+    fn binary_expression(&mut self, typ: Type, scope: &[Variable], depth: usize) -> String {
+        let (lhs_type, operators): (Type, &[&str]) = match typ {
+            Type::Field => (Type::Field, &["+", "-", "*", "/"]),
+            Type::Bool => {
+                let operand_type = *Type::NUMERIC.choose(self.rng).unwrap();
+                if self.rng.gen_bool(0.3) {
+                    (Type::Bool, &["&", "|", "^", "=="])
+                } else if operand_type.is_integer() {
+                    (operand_type, &["==", "!=", "<", "<=", ">", ">="])
+                } else {
+                    (operand_type, &["==", "!="])
+                }
+            }
+            _ => (typ, &["+", "-", "*", "/", "%", "&", "|", "^"]),
+        };
+
+        let operator = operators.choose(self.rng).unwrap();
+        let lhs = self.expression(lhs_type, scope, depth);
+        let rhs = self.expression(lhs_type, scope, depth);
+        if typ == Type::Bool && lhs_type != Type::Bool {
+            // Comparisons don't constrain the type of their operands so integer literals would
+            // otherwise default to `Field`, which cannot be ordered.
+            format!("(({lhs} as {lhs_type}) {operator} {rhs})")
+        } else {
+            format!("({lhs} {operator} {rhs})")
+        }
+    }
+
+    fn cast_expression(&mut self, typ: Type, scope: &[Variable], depth: usize) -> String {
+        match typ {
+            // Noir only allows casting to numeric types.
+            Type::Bool => {
+                let value = self.expression(Type::Bool, scope, depth);
+                format!("!{value}")
+            }
+            _ => {
+                let source_type = *Type::ALL.choose(self.rng).unwrap();
+                let value = self.expression(source_type, scope, depth);
+                format!("({value} as {typ})")
+            }
+        }
+    }
+
+    fn call_expression(&mut self, typ: Type, scope: &[Variable], depth: usize) -> Option<String> {
+        let candidates: Vec<FunctionSignature> =
+            self.functions.iter().filter(|function| function.return_type == typ).cloned().collect();
+        let function = candidates.choose(self.rng)?.clone();
+
+        let arguments: Vec<String> = function
+            .parameters
+            .iter()
+            .map(|parameter| self.expression(*parameter, scope, depth))
+            .collect();
+        Some(format!("{}({})", function.name, arguments.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fm::FileManager;
+    use noirc_driver::{check_crate, prepare_crate};
+    use noirc_frontend::{graph::CrateGraph, hir::Context};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{GeneratorConfig, ProgramGenerator};
+
+    #[test]
+    fn generated_programs_type_check() {
+        let config = GeneratorConfig {
+            max_functions: 3,
+            max_parameters: 4,
+            max_statements: 6,
+            max_expression_depth: 3,
+            max_loop_iterations: 8,
+        };
+
+        for seed in 0..25 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let program = ProgramGenerator::new(&mut rng, config).generate();
+
+            let source = program.source.clone();
+            let file_manager =
+                FileManager::new(Path::new("/fuzz"), Box::new(move |_path| Ok(source.clone())));
+            let mut context = Context::new(file_manager, CrateGraph::default());
+            let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+
+            if let Err(diagnostics) = check_crate(&mut context, crate_id, false) {
+                let messages: Vec<_> =
+                    diagnostics.iter().map(|diagnostic| &diagnostic.diagnostic.message).collect();
+                panic!(
+                    "program generated from seed {seed} failed to type check: {messages:?}\n{}",
+                    program.source
+                );
+            }
+            assert!(context.get_main_function(&crate_id).is_some());
+        }
+    }
+}
@@ -9,6 +9,8 @@ pub use noirc_errors::Location;
 use noirc_printable_type::ForeignCallError;
 use thiserror::Error;
 
+use crate::ops::{OracleResolverError, TranscriptError};
+
 #[derive(Debug, Error)]
 pub enum NargoError {
     /// Error while compiling Noir into ACIR.
@@ -22,6 +24,20 @@ pub enum NargoError {
     /// Oracle handling error
     #[error(transparent)]
     ForeignCallError(#[from] ForeignCallError),
+
+    /// Divergence from a transcript of oracle calls being replayed
+    #[error(transparent)]
+    TranscriptError(#[from] TranscriptError),
+
+    /// Error from the external oracle resolver
+    #[error(transparent)]
+    OracleResolverError(#[from] OracleResolverError),
+
+    #[error("Unknown foreign call `{0}`: no mock or oracle resolver is available")]
+    UnresolvedForeignCall(String),
+
+    #[error("Failed to write execution trace: {0}")]
+    TraceWriteError(#[from] std::io::Error),
 }
 
 impl From<acvm::compiler::CompileError> for NargoError {
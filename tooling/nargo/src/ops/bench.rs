@@ -0,0 +1,318 @@
+use std::time::{Duration, Instant};
+
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use acvm::pwg::{ACVMStatus, BrilligSolverStatus, StepResult, ACVM};
+use acvm::{acir::native_types::WitnessMap, BlackBoxFunctionSolver};
+use noirc_driver::{compile_no_check, CompileOptions};
+use noirc_errors::FileDiagnostic;
+use noirc_frontend::{hir::Context, node_interner::FuncId};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{try_to_diagnose_runtime_error, ExecutionError};
+use crate::NargoError;
+
+use super::execute::{
+    acir_call_initial_witness, acir_call_return_values, acir_callee, attribute_to_call,
+};
+use super::{execute_circuit, foreign_calls::ForeignCallExecutor};
+
+/// Controls how many times a benchmark is executed.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Number of executions which are run before any measurements are taken.
+    pub warmup_iterations: usize,
+    /// Number of executions which are timed.
+    pub iterations: usize,
+}
+
+pub enum BenchStatus {
+    Pass(BenchReport),
+    Fail { message: String, error_diagnostic: Option<FileDiagnostic> },
+    CompileError(FileDiagnostic),
+}
+
+/// The measurements taken while benchmarking a single `#[bench]` function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// Number of ACIR opcodes solved by the ACVM during a single execution.
+    pub acvm_opcodes_solved: usize,
+    /// Number of Brillig opcodes executed by the Brillig VM during a single execution.
+    pub brillig_opcodes_executed: usize,
+    /// Time taken to generate the witness for the circuit.
+    pub witness_generation: TimingStatistics,
+}
+
+/// Summary statistics over a set of timing samples, in nanoseconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingStatistics {
+    pub samples: usize,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub mean_ns: u64,
+    pub median_ns: u64,
+    pub std_dev_ns: u64,
+}
+
+impl TimingStatistics {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut nanos: Vec<u64> = samples.iter().map(|sample| sample.as_nanos() as u64).collect();
+        nanos.sort_unstable();
+
+        let count = nanos.len();
+        if count == 0 {
+            return Self::default();
+        }
+
+        let mean = nanos.iter().sum::<u64>() / count as u64;
+        let median = if count % 2 == 0 {
+            (nanos[count / 2 - 1] + nanos[count / 2]) / 2
+        } else {
+            nanos[count / 2]
+        };
+        let variance = nanos
+            .iter()
+            .map(|sample| {
+                let deviation = *sample as f64 - mean as f64;
+                deviation * deviation
+            })
+            .sum::<f64>()
+            / count as f64;
+
+        Self {
+            samples: count,
+            min_ns: nanos[0],
+            max_ns: nanos[count - 1],
+            mean_ns: mean,
+            median_ns: median,
+            std_dev_ns: variance.sqrt() as u64,
+        }
+    }
+}
+
+pub fn run_bench<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    context: &Context,
+    bench_function: FuncId,
+    config: &CompileOptions,
+    options: BenchOptions,
+) -> BenchStatus {
+    let program = match compile_no_check(context, config, bench_function, None, false) {
+        Ok(program) => program,
+        Err(err) => return BenchStatus::CompileError(err.into()),
+    };
+
+    let execute = || {
+        execute_circuit(
+            blackbox_solver,
+            &program.circuit,
+            &program.functions,
+            WitnessMap::new(),
+            &mut ForeignCallExecutor::default(),
+        )
+    };
+    let execution_failed = |err: NargoError| BenchStatus::Fail {
+        message: err.to_string(),
+        error_diagnostic: try_to_diagnose_runtime_error(&err, &program.debug),
+    };
+
+    // We always execute the circuit at least once before timing it so that failures
+    // are reported with the same diagnostics as `nargo test`.
+    for _ in 0..options.warmup_iterations.max(1) {
+        if let Err(err) = execute() {
+            return execution_failed(err);
+        }
+    }
+
+    let mut samples = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        let execution = execute();
+        samples.push(start.elapsed());
+
+        if let Err(err) = execution {
+            return execution_failed(err);
+        }
+    }
+
+    let mut counts = OpcodeCounts::default();
+    if let Err(err) = count_executed_opcodes(
+        blackbox_solver,
+        &program.circuit,
+        &program.functions,
+        WitnessMap::new(),
+        &mut ForeignCallExecutor::default(),
+        &mut counts,
+    ) {
+        return execution_failed(err);
+    }
+    let OpcodeCounts { acvm_opcodes_solved, brillig_opcodes_executed } = counts;
+
+    BenchStatus::Pass(BenchReport {
+        acvm_opcodes_solved,
+        brillig_opcodes_executed,
+        witness_generation: TimingStatistics::from_samples(&samples),
+    })
+}
+
+#[derive(Default)]
+struct OpcodeCounts {
+    acvm_opcodes_solved: usize,
+    brillig_opcodes_executed: usize,
+}
+
+/// Executes the circuit opcode by opcode, adding the number of ACIR opcodes solved
+/// and the number of Brillig opcodes executed to `counts`, including those of any called circuits.
+/// Returns the solved witness.
+///
+/// This is kept separate from the timed executions as stepping through the circuit is much slower.
+fn count_executed_opcodes<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    initial_witness: WitnessMap,
+    foreign_call_executor: &mut ForeignCallExecutor,
+    counts: &mut OpcodeCounts,
+) -> Result<WitnessMap, NargoError> {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+
+    while matches!(acvm.get_status(), ACVMStatus::InProgress) {
+        let mut status = match acvm.step_into_brillig_opcode() {
+            StepResult::Status(status) => status,
+            StepResult::IntoBrillig(mut solver) => loop {
+                match solver.step() {
+                    Ok(BrilligSolverStatus::InProgress) => counts.brillig_opcodes_executed += 1,
+                    Ok(BrilligSolverStatus::Finished) => {
+                        counts.brillig_opcodes_executed += 1;
+                        break acvm.finish_brillig_with_solver(solver);
+                    }
+                    Ok(BrilligSolverStatus::ForeignCallWait(foreign_call)) => {
+                        let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
+                        solver.resolve_pending_foreign_call(foreign_call_result);
+                    }
+                    Err(error) => break ACVMStatus::Failure(error),
+                }
+            },
+        };
+
+        if let ACVMStatus::RequiresAcirCall(acir_call) = status {
+            let call_location = OpcodeLocation::Acir(acvm.instruction_pointer());
+            let callee = acir_callee(functions, &acir_call);
+            let solved_witness = count_executed_opcodes(
+                blackbox_solver,
+                callee,
+                functions,
+                acir_call_initial_witness(callee, &acir_call),
+                foreign_call_executor,
+                counts,
+            )
+            .map_err(|error| attribute_to_call(error, call_location))?;
+            acvm.resolve_pending_acir_call(acir_call_return_values(callee, &solved_witness)?);
+            status = acvm.get_status().clone();
+        }
+
+        match status {
+            ACVMStatus::Solved | ACVMStatus::InProgress => counts.acvm_opcodes_solved += 1,
+            ACVMStatus::Failure(error) => {
+                return Err(NargoError::ExecutionError(ExecutionError::SolvingError(error)))
+            }
+            ACVMStatus::RequiresForeignCall(_) => {
+                unreachable!("Foreign calls are resolved while stepping through Brillig")
+            }
+            ACVMStatus::RequiresAcirCall(_) => {
+                unreachable!("ACIR calls are resolved as soon as they are made")
+            }
+        }
+    }
+
+    Ok(acvm.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use acvm::acir::circuit::Opcode;
+    use noirc_driver::{compile_no_check, CompileOptions};
+
+    use crate::ops::test_utils::{check_program, StubbedSolver};
+
+    use super::{run_bench, BenchOptions, BenchStatus, TimingStatistics};
+
+    fn statistics_of(nanos: &[u64]) -> TimingStatistics {
+        let samples: Vec<_> = nanos.iter().copied().map(Duration::from_nanos).collect();
+        TimingStatistics::from_samples(&samples)
+    }
+
+    #[test]
+    fn statistics_of_no_samples() {
+        assert_eq!(statistics_of(&[]), TimingStatistics::default());
+    }
+
+    #[test]
+    fn statistics_of_an_odd_number_of_samples() {
+        let expected = TimingStatistics {
+            samples: 3,
+            min_ns: 10,
+            max_ns: 30,
+            mean_ns: 20,
+            median_ns: 20,
+            std_dev_ns: 8,
+        };
+        assert_eq!(statistics_of(&[30, 10, 20]), expected);
+    }
+
+    #[test]
+    fn statistics_of_an_even_number_of_samples() {
+        // The median is the mean of the two middle samples.
+        let expected = TimingStatistics {
+            samples: 4,
+            min_ns: 10,
+            max_ns: 40,
+            mean_ns: 25,
+            median_ns: 25,
+            std_dev_ns: 11,
+        };
+        assert_eq!(statistics_of(&[40, 10, 30, 20]), expected);
+    }
+
+    #[test]
+    fn counts_executed_opcodes() {
+        let (context, main) = check_program(
+            "unconstrained fn sum(n: u32) -> u32 {
+                let mut total = 0;
+                for i in 0..n {
+                    total += i;
+                }
+                total
+            }
+
+            fn main() {
+                assert(sum(100) == 4950);
+            }",
+        );
+        let config = CompileOptions::default();
+        let options = BenchOptions { warmup_iterations: 0, iterations: 3 };
+
+        let BenchStatus::Pass(report) = run_bench(&StubbedSolver, &context, main, &config, options)
+        else {
+            panic!("benchmark should pass");
+        };
+        assert_eq!(report.witness_generation.samples, 3);
+
+        // Each ACIR opcode is solved once, while the loop in `sum` executes its Brillig opcodes
+        // many times over.
+        let program = compile_no_check(&context, &config, main, None, false).unwrap();
+        let brillig_opcodes: usize = program
+            .circuit
+            .opcodes
+            .iter()
+            .map(|opcode| match opcode {
+                Opcode::Brillig(brillig) => brillig.bytecode.len(),
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(report.acvm_opcodes_solved, program.circuit.opcodes.len());
+        assert!(brillig_opcodes > 0);
+        assert!(report.brillig_opcodes_executed > 2 * brillig_opcodes);
+    }
+}
@@ -0,0 +1,172 @@
+use acvm::BlackBoxFunctionSolver;
+use noirc_abi::{input_parser::InputValue, InputMap};
+use noirc_driver::{compile_no_check, CompileOptions};
+use noirc_errors::FileDiagnostic;
+use noirc_evaluator::errors::RuntimeError;
+use noirc_frontend::{hir::Context, node_interner::FuncId};
+
+use super::{execute_circuit, ForeignCallExecutor};
+
+/// The result of executing a function once compiled to ACIR and once compiled
+/// with every function forced to be unconstrained Brillig.
+pub enum DifferentialStatus {
+    /// Both executions either returned the same values or both failed.
+    Match,
+    /// The executions disagree on the returned values or on whether execution succeeded.
+    Mismatch {
+        acir: ExecutionOutcome,
+        brillig: ExecutionOutcome,
+    },
+    CompileError(FileDiagnostic),
+}
+
+/// The observable result of executing a compiled function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionOutcome {
+    Returned(Option<InputValue>),
+    Failed(String),
+}
+
+impl std::fmt::Display for ExecutionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionOutcome::Returned(None) => write!(f, "returned successfully"),
+            ExecutionOutcome::Returned(Some(value)) => write!(f, "returned {value:?}"),
+            ExecutionOutcome::Failed(message) => write!(f, "failed with '{message}'"),
+        }
+    }
+}
+
+impl ExecutionOutcome {
+    /// Failure messages are not compared as ACIR and Brillig report the same failure differently.
+    fn agrees_with(&self, other: &ExecutionOutcome) -> bool {
+        match (self, other) {
+            (ExecutionOutcome::Returned(lhs), ExecutionOutcome::Returned(rhs)) => lhs == rhs,
+            (ExecutionOutcome::Failed(_), ExecutionOutcome::Failed(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Compiles `function` both normally and with every function forced to be unconstrained,
+/// executes both programs with the same `inputs` and compares the results.
+pub fn run_differential<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    context: &Context,
+    function: FuncId,
+    inputs: &InputMap,
+    config: &CompileOptions,
+) -> DifferentialStatus {
+    let acir_config = CompileOptions { force_brillig: false, ..config.clone() };
+    let brillig_config = CompileOptions { force_brillig: true, ..config.clone() };
+
+    let acir = match execute_function(blackbox_solver, context, function, inputs, &acir_config) {
+        Ok(outcome) => outcome,
+        Err(diagnostic) => return DifferentialStatus::CompileError(diagnostic),
+    };
+    let brillig =
+        match execute_function(blackbox_solver, context, function, inputs, &brillig_config) {
+            Ok(outcome) => outcome,
+            Err(diagnostic) => return DifferentialStatus::CompileError(diagnostic),
+        };
+
+    if acir.agrees_with(&brillig) {
+        DifferentialStatus::Match
+    } else {
+        DifferentialStatus::Mismatch { acir, brillig }
+    }
+}
+
+fn execute_function<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    context: &Context,
+    function: FuncId,
+    inputs: &InputMap,
+    config: &CompileOptions,
+) -> Result<ExecutionOutcome, FileDiagnostic> {
+    let program = match compile_no_check(context, config, function, None, true) {
+        Ok(program) => program,
+        // Constraints which can never be satisfied are caught while compiling to ACIR
+        // so we treat these the same as a failure during execution.
+        Err(err @ RuntimeError::FailedConstraint { .. }) => {
+            return Ok(ExecutionOutcome::Failed(err.to_string()))
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let initial_witness = match program.abi.encode(inputs, None) {
+        Ok(initial_witness) => initial_witness,
+        Err(err) => return Ok(ExecutionOutcome::Failed(err.to_string())),
+    };
+
+    let outcome = match execute_circuit(
+        blackbox_solver,
+        &program.circuit,
+        &program.functions,
+        initial_witness,
+        &mut ForeignCallExecutor::default(),
+    ) {
+        Ok(solved_witness) => match program.abi.decode(&solved_witness) {
+            Ok((_, return_value)) => ExecutionOutcome::Returned(return_value),
+            Err(err) => ExecutionOutcome::Failed(err.to_string()),
+        },
+        Err(err) => ExecutionOutcome::Failed(err.to_string()),
+    };
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+    use noirc_abi::{input_parser::InputValue, InputMap};
+    use noirc_driver::CompileOptions;
+
+    use crate::ops::test_utils::{check_program, StubbedSolver};
+
+    use super::{run_differential, DifferentialStatus, ExecutionOutcome};
+
+    const DIVISION_PROGRAM: &str = "fn main(x: Field, y: Field) -> pub Field { x / y }";
+
+    fn division_inputs(x: u128, y: u128) -> InputMap {
+        InputMap::from([
+            ("x".to_string(), InputValue::Field(FieldElement::from(x))),
+            ("y".to_string(), InputValue::Field(FieldElement::from(y))),
+        ])
+    }
+
+    #[test]
+    fn matching_executions() {
+        let (context, main) = check_program(DIVISION_PROGRAM);
+        let status = run_differential(
+            &StubbedSolver,
+            &context,
+            main,
+            &division_inputs(6, 3),
+            &CompileOptions::default(),
+        );
+        assert!(matches!(status, DifferentialStatus::Match));
+    }
+
+    #[test]
+    fn mismatching_executions() {
+        // ACIR constrains the inverse of a field divisor while Brillig treats
+        // the inverse of zero as zero, so dividing by zero only fails in ACIR.
+        let (context, main) = check_program(DIVISION_PROGRAM);
+        let status = run_differential(
+            &StubbedSolver,
+            &context,
+            main,
+            &division_inputs(6, 0),
+            &CompileOptions::default(),
+        );
+
+        let DifferentialStatus::Mismatch { acir, brillig } = status else {
+            panic!("expected ACIR and Brillig execution to disagree");
+        };
+        assert!(matches!(acir, ExecutionOutcome::Failed(_)));
+        assert_eq!(
+            brillig,
+            ExecutionOutcome::Returned(Some(InputValue::Field(FieldElement::zero())))
+        );
+    }
+}
@@ -1,6 +1,11 @@
-use acvm::pwg::{ACVMStatus, ErrorLocation, OpcodeResolutionError, ACVM};
-use acvm::BlackBoxFunctionSolver;
-use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use acvm::acir::native_types::WitnessMap;
+use acvm::pwg::{
+    ACVMStatus, AcirCallWaitInfo, ErrorLocation, ForeignCallWaitInfo, OpcodeNotSolvable,
+    OpcodeResolutionError, ACVM,
+};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
 
 use crate::errors::ExecutionError;
 use crate::NargoError;
@@ -10,12 +15,29 @@ use super::foreign_calls::ForeignCallExecutor;
 pub fn execute_circuit<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     circuit: &Circuit,
+    functions: &[Circuit],
     initial_witness: WitnessMap,
-    show_output: bool,
+    foreign_call_executor: &mut ForeignCallExecutor,
 ) -> Result<WitnessMap, NargoError> {
-    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    let mut resolve_foreign_call =
+        |foreign_call: &ForeignCallWaitInfo| foreign_call_executor.execute(foreign_call);
+    solve_circuit(blackbox_solver, circuit, functions, initial_witness, &mut resolve_foreign_call)
+}
 
-    let mut foreign_call_executor = ForeignCallExecutor::default();
+/// Solves `circuit`, resolving the foreign calls made by it or by the circuits it calls
+/// with `resolve_foreign_call`.
+fn solve_circuit<B, F>(
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    initial_witness: WitnessMap,
+    resolve_foreign_call: &mut F,
+) -> Result<WitnessMap, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    F: FnMut(&ForeignCallWaitInfo) -> Result<ForeignCallResult, NargoError>,
+{
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
 
     loop {
         let solver_status = acvm.solve();
@@ -25,38 +47,149 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver>(
             ACVMStatus::InProgress => {
                 unreachable!("Execution should not stop while in `InProgress` state.")
             }
-            ACVMStatus::Failure(error) => {
-                let call_stack = match &error {
-                    OpcodeResolutionError::UnsatisfiedConstrain {
-                        opcode_location: ErrorLocation::Resolved(opcode_location),
-                    } => Some(vec![*opcode_location]),
-                    OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => {
-                        Some(call_stack.clone())
-                    }
-                    _ => None,
-                };
-
-                return Err(NargoError::ExecutionError(match call_stack {
-                    Some(call_stack) => {
-                        if let Some(assert_message) = circuit.get_assert_message(
-                            *call_stack.last().expect("Call stacks should not be empty"),
-                        ) {
-                            ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
-                        } else {
-                            ExecutionError::SolvingError(error)
-                        }
-                    }
-                    None => ExecutionError::SolvingError(error),
-                }));
-            }
+            ACVMStatus::Failure(error) => return Err(execution_failure(circuit, error)),
             ACVMStatus::RequiresForeignCall(foreign_call) => {
-                let foreign_call_result =
-                    foreign_call_executor.execute(&foreign_call, show_output)?;
+                let foreign_call_result = resolve_foreign_call(&foreign_call)?;
                 acvm.resolve_pending_foreign_call(foreign_call_result);
             }
+            ACVMStatus::RequiresAcirCall(acir_call) => {
+                let call_location = OpcodeLocation::Acir(acvm.instruction_pointer());
+                let return_values = execute_acir_call(
+                    blackbox_solver,
+                    functions,
+                    &acir_call,
+                    call_location,
+                    resolve_foreign_call,
+                )?;
+                acvm.resolve_pending_acir_call(return_values);
+            }
         }
     }
 
     let solved_witness = acvm.finalize();
     Ok(solved_witness)
 }
+
+/// Executes the callee of an ACIR call made from `call_location`, returning its return values.
+///
+/// The foreign calls made by the callee are resolved with `resolve_foreign_call`, e.g. by passing
+/// them to a [`ForeignCallExecutor`].
+///
+/// The debug information of the caller does not cover the opcodes of the callee,
+/// so failures within the callee are reported at the location of the call instead.
+pub fn execute_acir_call<B, F>(
+    blackbox_solver: &B,
+    functions: &[Circuit],
+    acir_call: &AcirCallWaitInfo,
+    call_location: OpcodeLocation,
+    resolve_foreign_call: &mut F,
+) -> Result<Vec<FieldElement>, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    F: FnMut(&ForeignCallWaitInfo) -> Result<ForeignCallResult, NargoError>,
+{
+    let callee = acir_callee(functions, acir_call);
+    let initial_witness = acir_call_initial_witness(callee, acir_call);
+    let solved_witness =
+        solve_circuit(blackbox_solver, callee, functions, initial_witness, resolve_foreign_call)
+            .map_err(|error| attribute_to_call(error, call_location))?;
+    acir_call_return_values(callee, &solved_witness)
+}
+
+pub(super) fn acir_callee<'a>(
+    functions: &'a [Circuit],
+    acir_call: &AcirCallWaitInfo,
+) -> &'a Circuit {
+    functions
+        .get(acir_call.id as usize)
+        .unwrap_or_else(|| panic!("ACIR call to unknown function {}", acir_call.id))
+}
+
+/// Binds the inputs of an ACIR call to the arguments of its `callee`.
+pub(super) fn acir_call_initial_witness(
+    callee: &Circuit,
+    acir_call: &AcirCallWaitInfo,
+) -> WitnessMap {
+    let mut initial_witness = WitnessMap::new();
+    for (witness, value) in callee.circuit_arguments().into_iter().zip(&acir_call.inputs) {
+        initial_witness.insert(witness, *value);
+    }
+    initial_witness
+}
+
+/// Reads the return values of `callee` from its solved witness.
+pub(super) fn acir_call_return_values(
+    callee: &Circuit,
+    solved_witness: &WitnessMap,
+) -> Result<Vec<FieldElement>, NargoError> {
+    callee
+        .return_values
+        .0
+        .iter()
+        .map(|witness| {
+            solved_witness.get(witness).copied().ok_or_else(|| {
+                let error = OpcodeNotSolvable::MissingAssignment(witness.witness_index());
+                NargoError::from(ExecutionError::SolvingError(error.into()))
+            })
+        })
+        .collect()
+}
+
+/// Re-attributes an error raised within the callee of an ACIR call to the call itself.
+pub(super) fn attribute_to_call(error: NargoError, call_location: OpcodeLocation) -> NargoError {
+    let NargoError::ExecutionError(error) = error else {
+        return error;
+    };
+    let error = match error {
+        ExecutionError::AssertionFailed(message, _) => {
+            ExecutionError::AssertionFailed(message, vec![call_location])
+        }
+        ExecutionError::SolvingError(error) => ExecutionError::SolvingError(match error {
+            OpcodeResolutionError::UnsatisfiedConstrain { .. } => {
+                OpcodeResolutionError::UnsatisfiedConstrain {
+                    opcode_location: ErrorLocation::Resolved(call_location),
+                }
+            }
+            OpcodeResolutionError::IndexOutOfBounds { index, array_size, .. } => {
+                OpcodeResolutionError::IndexOutOfBounds {
+                    opcode_location: ErrorLocation::Resolved(call_location),
+                    index,
+                    array_size,
+                }
+            }
+            OpcodeResolutionError::BrilligFunctionFailed { message, .. } => {
+                OpcodeResolutionError::BrilligFunctionFailed {
+                    message,
+                    call_stack: vec![call_location],
+                }
+            }
+            error => error,
+        }),
+    };
+    NargoError::ExecutionError(error)
+}
+
+/// Converts an error encountered while solving `circuit` into a [`NargoError`],
+/// attaching the assertion message of the failing opcode if there is one.
+pub(super) fn execution_failure(circuit: &Circuit, error: OpcodeResolutionError) -> NargoError {
+    let call_stack = match &error {
+        OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Resolved(opcode_location),
+        } => Some(vec![*opcode_location]),
+        OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => Some(call_stack.clone()),
+        _ => None,
+    };
+
+    NargoError::ExecutionError(match call_stack {
+        Some(call_stack) => {
+            if let Some(assert_message) = circuit
+                .get_assert_message(*call_stack.last().expect("Call stacks should not be empty"))
+            {
+                ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
+            } else {
+                ExecutionError::SolvingError(error)
+            }
+        }
+        None => ExecutionError::SolvingError(error),
+    })
+}
@@ -1,12 +1,16 @@
-use acvm::{
-    acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
-    pwg::ForeignCallWaitInfo,
-};
-use iter_extended::vecmap;
-use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
+use acvm::{acir::brillig::ForeignCallResult, pwg::ForeignCallWaitInfo};
 
 use crate::NargoError;
 
+mod builtins;
+mod rpc;
+mod transcript;
+
+pub use builtins::{MockHandler, PrintHandler, SequenceHandler};
+pub use rpc::{OracleResolver, OracleResolverError};
+use transcript::TranscriptReplay;
+pub use transcript::{ForeignCallTranscript, RecordedForeignCall, TranscriptError};
+
 /// This enumeration represents the Brillig foreign calls that are natively supported by nargo.
 /// After resolution of a foreign call, nargo will restart execution of the ACVM
 pub(crate) enum ForeignCall {
@@ -55,174 +59,291 @@ impl ForeignCall {
     }
 }
 
-/// This struct represents an oracle mock. It can be used for testing programs that use oracles.
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct MockedCall {
-    /// The id of the mock, used to update or remove it
-    id: usize,
-    /// The oracle it's mocking
-    name: String,
-    /// Optionally match the parameters
-    params: Option<Vec<ForeignCallParam>>,
-    /// The result to return when this mock is called
-    result: ForeignCallResult,
-    /// How many times should this mock be called before it is removed
-    times_left: Option<u64>,
-}
+/// A handler for the foreign calls made by a program during execution.
+///
+/// Handlers are arranged into a chain by a [`ForeignCallExecutor`], which passes each foreign call
+/// to its handlers in turn until one of them resolves it.
+pub trait ForeignCallHandler {
+    /// Resolves `foreign_call`, or returns `Ok(None)` if this handler does not handle it
+    /// so that it is passed on to the next handler in the chain.
+    fn handle(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, NargoError>;
 
-impl MockedCall {
-    fn new(id: usize, name: String) -> Self {
-        Self {
-            id,
-            name,
-            params: None,
-            result: ForeignCallResult { values: vec![] },
-            times_left: None,
-        }
+    /// Whether the results of this handler depend only on the foreign calls made by the program.
+    ///
+    /// Calls resolved by deterministic handlers are not recorded in oracle transcripts
+    /// and are still passed to the handler when a transcript is replayed.
+    fn is_deterministic(&self) -> bool {
+        false
     }
-}
 
-impl MockedCall {
-    fn matches(&self, name: &str, params: &Vec<ForeignCallParam>) -> bool {
-        self.name == name && (self.params.is_none() || self.params.as_ref() == Some(params))
+    /// Discards any state built up during an execution, such as registered mocks,
+    /// so that the handler can be reused when execution is restarted.
+    fn reset(&mut self) {}
+
+    /// Returns a copy of the handler which can replace it later on to restore its current state,
+    /// or `None` if the handler has no state which can be restored.
+    fn snapshot(&self) -> Option<Box<dyn ForeignCallHandler>> {
+        None
     }
 }
 
-#[derive(Debug, Default)]
+/// The handlers for the foreign calls which are natively supported by nargo.
+pub fn default_handlers(show_output: bool) -> Vec<Box<dyn ForeignCallHandler>> {
+    vec![
+        Box::new(PrintHandler::new(show_output)),
+        Box::new(SequenceHandler),
+        Box::new(MockHandler::default()),
+    ]
+}
+
+/// Whether the results of oracle calls are being recorded or replayed from a previous execution.
+#[derive(Debug, Clone)]
+enum TranscriptMode {
+    Record(ForeignCallTranscript),
+    Replay(TranscriptReplay),
+}
+
+/// Resolves foreign calls by passing them along a chain of [`ForeignCallHandler`]s.
 pub struct ForeignCallExecutor {
-    /// Mocks have unique ids used to identify them in Noir, allowing to update or remove them.
-    last_mock_id: usize,
-    /// The registered mocks
-    mocked_responses: Vec<MockedCall>,
+    /// The handlers which foreign calls are passed to, in order
+    handlers: Vec<Box<dyn ForeignCallHandler>>,
+    /// Set when oracle calls are being recorded to, or replayed from, a transcript
+    transcript: Option<TranscriptMode>,
+}
+
+/// The state of a [`ForeignCallExecutor`] at some point of an execution,
+/// which the executor can be restored to when travelling back in time.
+pub struct ForeignCallExecutorSnapshot {
+    /// The copies of the executor's handlers which have state which can be restored
+    handlers: Vec<Option<Box<dyn ForeignCallHandler>>>,
+    transcript: Option<TranscriptMode>,
+}
+
+impl Default for ForeignCallExecutor {
+    fn default() -> Self {
+        Self::new(false)
+    }
 }
 
 impl ForeignCallExecutor {
+    /// Creates an executor which handles the foreign calls natively supported by nargo,
+    /// printing the output of `println` if `show_output` is set.
+    pub fn new(show_output: bool) -> Self {
+        Self::from_handlers(default_handlers(show_output))
+    }
+
+    /// Creates an executor which passes foreign calls to `handlers` in order.
+    pub fn from_handlers(handlers: Vec<Box<dyn ForeignCallHandler>>) -> Self {
+        Self { handlers, transcript: None }
+    }
+
+    /// Adds `handler` to the end of the chain so that it is passed any foreign calls
+    /// which are not resolved by the existing handlers.
+    pub fn with_handler(mut self, handler: impl ForeignCallHandler + 'static) -> Self {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Forwards any oracle calls which are not resolved by the existing handlers
+    /// to the JSON-RPC server at `resolver_url`.
+    pub fn with_oracle_resolver(self, resolver_url: String) -> Self {
+        self.with_handler(OracleResolver::new(resolver_url))
+    }
+
+    /// Records the result of every oracle call resolved by a non-deterministic handler.
+    ///
+    /// The recorded calls can be retrieved with [`ForeignCallExecutor::into_recorded_transcript`].
+    pub fn with_transcript_recording(mut self) -> Self {
+        self.transcript = Some(TranscriptMode::Record(ForeignCallTranscript::default()));
+        self
+    }
+
+    /// Resolves oracle calls using the results stored in `transcript` rather than passing them
+    /// to non-deterministic handlers.
+    ///
+    /// Execution fails if the program makes an oracle call which differs from the next recorded call.
+    pub fn with_transcript_replay(mut self, transcript: ForeignCallTranscript) -> Self {
+        self.transcript = Some(TranscriptMode::Replay(TranscriptReplay::new(transcript)));
+        self
+    }
+
+    /// Returns the oracle calls recorded by an executor created with
+    /// [`ForeignCallExecutor::with_transcript_recording`].
+    pub fn into_recorded_transcript(self) -> Option<ForeignCallTranscript> {
+        match self.transcript {
+            Some(TranscriptMode::Record(transcript)) => Some(transcript),
+            _ => None,
+        }
+    }
+
+    /// Checks that every call in the transcript being replayed has been made.
+    ///
+    /// This should be called once execution has finished as a program which makes fewer
+    /// oracle calls than were recorded has also diverged from the transcript.
+    pub fn ensure_replay_completed(&self) -> Result<(), TranscriptError> {
+        match &self.transcript {
+            Some(TranscriptMode::Replay(replay)) => replay.ensure_completed(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Resets every handler, along with any transcript being recorded or replayed,
+    /// so that the executor can be used to execute the program again from the start.
+    pub fn reset(&mut self) {
+        for handler in &mut self.handlers {
+            handler.reset();
+        }
+        match &mut self.transcript {
+            Some(TranscriptMode::Record(transcript)) => transcript.calls.clear(),
+            Some(TranscriptMode::Replay(replay)) => replay.restart(),
+            None => (),
+        }
+    }
+
+    /// Captures the current state of the executor's handlers, such as the registered mocks,
+    /// along with the state of any transcript being recorded or replayed.
+    pub fn snapshot(&self) -> ForeignCallExecutorSnapshot {
+        ForeignCallExecutorSnapshot {
+            handlers: self.handlers.iter().map(|handler| handler.snapshot()).collect(),
+            transcript: self.transcript.clone(),
+        }
+    }
+
+    /// Restores the state captured by [`snapshot`][Self::snapshot]. Handlers without state which
+    /// can be restored, such as those forwarding calls to an external oracle resolver, are left as is.
+    pub fn restore(&mut self, snapshot: &ForeignCallExecutorSnapshot) {
+        for (handler, saved_handler) in self.handlers.iter_mut().zip(&snapshot.handlers) {
+            if let Some(saved_handler) = saved_handler.as_ref().and_then(|saved| saved.snapshot()) {
+                *handler = saved_handler;
+            }
+        }
+        self.transcript = snapshot.transcript.clone();
+    }
+
     pub fn execute(
         &mut self,
         foreign_call: &ForeignCallWaitInfo,
-        show_output: bool,
     ) -> Result<ForeignCallResult, NargoError> {
-        let foreign_call_name = foreign_call.function.as_str();
-        match ForeignCall::lookup(foreign_call_name) {
-            Some(ForeignCall::Println) => {
-                if show_output {
-                    Self::execute_println(&foreign_call.inputs)?;
-                }
-                Ok(ForeignCallResult { values: vec![] })
-            }
-            Some(ForeignCall::Sequence) => {
-                let sequence_length: u128 =
-                    foreign_call.inputs[0].unwrap_value().to_field().to_u128();
-                let sequence = vecmap(0..sequence_length, Value::from);
-
-                Ok(ForeignCallResult {
-                    values: vec![
-                        ForeignCallParam::Single(sequence_length.into()),
-                        ForeignCallParam::Array(sequence),
-                    ],
-                })
-            }
-            Some(ForeignCall::ReverseSequence) => {
-                let sequence_length: u128 =
-                    foreign_call.inputs[0].unwrap_value().to_field().to_u128();
-                let sequence = vecmap((0..sequence_length).rev(), Value::from);
-
-                Ok(ForeignCallResult {
-                    values: vec![
-                        ForeignCallParam::Single(sequence_length.into()),
-                        ForeignCallParam::Array(sequence),
-                    ],
-                })
-            }
-            Some(ForeignCall::CreateMock) => {
-                let mock_oracle_name = Self::parse_string(&foreign_call.inputs[0]);
-                assert!(ForeignCall::lookup(&mock_oracle_name).is_none());
-                let id = self.last_mock_id;
-                self.mocked_responses.push(MockedCall::new(id, mock_oracle_name));
-                self.last_mock_id += 1;
-
-                Ok(ForeignCallResult { values: vec![Value::from(id).into()] })
-            }
-            Some(ForeignCall::SetMockParams) => {
-                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
-                self.find_mock_by_id(id)
-                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
-                    .params = Some(params.to_vec());
+        let replaying = matches!(self.transcript, Some(TranscriptMode::Replay(_)));
 
-                Ok(ForeignCallResult { values: vec![] })
+        for handler in &mut self.handlers {
+            let deterministic = handler.is_deterministic();
+            if replaying && !deterministic {
+                continue;
             }
-            Some(ForeignCall::SetMockReturns) => {
-                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
-                self.find_mock_by_id(id)
-                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
-                    .result = ForeignCallResult { values: params.to_vec() };
 
-                Ok(ForeignCallResult { values: vec![] })
-            }
-            Some(ForeignCall::SetMockTimes) => {
-                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
-                let times = params[0]
-                    .unwrap_value()
-                    .to_field()
-                    .try_to_u64()
-                    .expect("Invalid bit size of times");
-
-                self.find_mock_by_id(id)
-                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
-                    .times_left = Some(times);
-
-                Ok(ForeignCallResult { values: vec![] })
-            }
-            Some(ForeignCall::ClearMock) => {
-                let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
-                self.mocked_responses.retain(|response| response.id != id);
-                Ok(ForeignCallResult { values: vec![] })
-            }
-            None => {
-                let response_position = self
-                    .mocked_responses
-                    .iter()
-                    .position(|response| response.matches(foreign_call_name, &foreign_call.inputs))
-                    .unwrap_or_else(|| panic!("Unknown foreign call {}", foreign_call_name));
-
-                let mock = self
-                    .mocked_responses
-                    .get_mut(response_position)
-                    .expect("Invalid position of mocked response");
-                let result = mock.result.values.clone();
-
-                if let Some(times_left) = &mut mock.times_left {
-                    *times_left -= 1;
-                    if *times_left == 0 {
-                        self.mocked_responses.remove(response_position);
-                    }
+            if let Some(result) = handler.handle(foreign_call)? {
+                if let (Some(TranscriptMode::Record(transcript)), false) =
+                    (&mut self.transcript, deterministic)
+                {
+                    transcript.calls.push(RecordedForeignCall {
+                        name: foreign_call.function.clone(),
+                        inputs: foreign_call.inputs.clone(),
+                        result: result.clone(),
+                    });
                 }
+                return Ok(result);
+            }
+        }
+
+        match &mut self.transcript {
+            Some(TranscriptMode::Replay(replay)) => Ok(replay.next_result(foreign_call)?),
+            _ => Err(NargoError::UnresolvedForeignCall(foreign_call.function.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
+        pwg::ForeignCallWaitInfo,
+    };
+
+    use crate::NargoError;
+
+    use super::{ForeignCallExecutor, ForeignCallHandler};
+
+    /// Resolves `double` by doubling its single input.
+    struct DoubleHandler;
 
-                Ok(ForeignCallResult { values: result })
+    impl ForeignCallHandler for DoubleHandler {
+        fn handle(
+            &mut self,
+            foreign_call: &ForeignCallWaitInfo,
+        ) -> Result<Option<ForeignCallResult>, NargoError> {
+            if foreign_call.function != "double" {
+                return Ok(None);
             }
+            let input = foreign_call.inputs[0].unwrap_value().to_field();
+            Ok(Some(ForeignCallResult {
+                values: vec![ForeignCallParam::Single(Value::from(input + input))],
+            }))
         }
     }
 
-    fn extract_mock_id(
-        foreign_call_inputs: &[ForeignCallParam],
-    ) -> Result<(usize, &[ForeignCallParam]), ForeignCallError> {
-        let (id, params) =
-            foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
-        Ok((id.unwrap_value().to_usize(), params))
+    fn double_call() -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo {
+            function: "double".to_string(),
+            inputs: vec![ForeignCallParam::Single(Value::from(21_u128))],
+        }
     }
 
-    fn find_mock_by_id(&mut self, id: usize) -> Option<&mut MockedCall> {
-        self.mocked_responses.iter_mut().find(|response| response.id == id)
+    #[test]
+    fn custom_handlers_resolve_unknown_oracles() {
+        let mut executor = ForeignCallExecutor::default();
+        assert!(matches!(
+            executor.execute(&double_call()),
+            Err(NargoError::UnresolvedForeignCall(_))
+        ));
+
+        let mut executor = ForeignCallExecutor::default().with_handler(DoubleHandler);
+        let result = executor.execute(&double_call()).unwrap();
+        assert_eq!(result.values, vec![ForeignCallParam::Single(Value::from(42_u128))]);
+    }
+
+    #[test]
+    fn replayed_transcripts_resolve_calls_without_handlers() {
+        let mut recorder =
+            ForeignCallExecutor::default().with_handler(DoubleHandler).with_transcript_recording();
+        let recorded_result = recorder.execute(&double_call()).unwrap();
+        let transcript = recorder.into_recorded_transcript().unwrap();
+        assert_eq!(transcript.calls.len(), 1);
+
+        let mut replayer = ForeignCallExecutor::default().with_transcript_replay(transcript);
+        assert_eq!(replayer.execute(&double_call()).unwrap(), recorded_result);
+        assert!(replayer.ensure_replay_completed().is_ok());
     }
 
-    fn parse_string(param: &ForeignCallParam) -> String {
-        let fields: Vec<_> = param.values().into_iter().map(|value| value.to_field()).collect();
-        decode_string_value(&fields)
+    fn call(function: &str, inputs: Vec<ForeignCallParam>) -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo { function: function.to_string(), inputs }
     }
 
-    fn execute_println(foreign_call_inputs: &[ForeignCallParam]) -> Result<(), NargoError> {
-        let display_values: PrintableValueDisplay = foreign_call_inputs.try_into()?;
-        println!("{display_values}");
-        Ok(())
+    fn mock_returns(id: u128, value: u128) -> ForeignCallWaitInfo {
+        call(
+            "set_mock_returns",
+            vec![ForeignCallParam::Single(id.into()), ForeignCallParam::Single(value.into())],
+        )
+    }
+
+    #[test]
+    fn restoring_a_snapshot_restores_mocks() {
+        let mut executor = ForeignCallExecutor::default();
+        let name = "double".bytes().map(|byte| Value::from(byte as u128)).collect();
+        let create_mock = call("create_mock", vec![ForeignCallParam::Array(name)]);
+        let id = executor.execute(&create_mock).unwrap().values[0].unwrap_value().to_u128();
+        executor.execute(&mock_returns(id, 1)).unwrap();
+        let snapshot = executor.snapshot();
+
+        executor.execute(&mock_returns(id, 2)).unwrap();
+        let result = executor.execute(&double_call()).unwrap();
+        assert_eq!(result.values, vec![ForeignCallParam::Single(Value::from(2_u128))]);
+
+        executor.restore(&snapshot);
+        let result = executor.execute(&double_call()).unwrap();
+        assert_eq!(result.values, vec![ForeignCallParam::Single(Value::from(1_u128))]);
     }
 }
@@ -0,0 +1,244 @@
+use acvm::{
+    acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
+    pwg::ForeignCallWaitInfo,
+};
+use iter_extended::vecmap;
+use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
+
+use crate::NargoError;
+
+use super::{ForeignCall, ForeignCallHandler};
+
+/// Handles calls to `println`, printing to stdout if `show_output` is set.
+#[derive(Debug, Default)]
+pub struct PrintHandler {
+    show_output: bool,
+}
+
+impl PrintHandler {
+    pub fn new(show_output: bool) -> Self {
+        Self { show_output }
+    }
+}
+
+impl ForeignCallHandler for PrintHandler {
+    fn handle(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, NargoError> {
+        if !matches!(ForeignCall::lookup(&foreign_call.function), Some(ForeignCall::Println)) {
+            return Ok(None);
+        }
+
+        if self.show_output {
+            let display_values: PrintableValueDisplay =
+                foreign_call.inputs.as_slice().try_into()?;
+            println!("{display_values}");
+        }
+        Ok(Some(ForeignCallResult { values: vec![] }))
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+/// Handles the `get_number_sequence` and `get_reverse_number_sequence` oracles used in tests.
+#[derive(Debug, Default)]
+pub struct SequenceHandler;
+
+impl ForeignCallHandler for SequenceHandler {
+    fn handle(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, NargoError> {
+        let reverse = match ForeignCall::lookup(&foreign_call.function) {
+            Some(ForeignCall::Sequence) => false,
+            Some(ForeignCall::ReverseSequence) => true,
+            _ => return Ok(None),
+        };
+
+        let sequence_length: u128 = foreign_call
+            .inputs
+            .first()
+            .ok_or(ForeignCallError::MissingForeignCallInputs)?
+            .unwrap_value()
+            .to_field()
+            .to_u128();
+        let sequence = if reverse {
+            vecmap((0..sequence_length).rev(), Value::from)
+        } else {
+            vecmap(0..sequence_length, Value::from)
+        };
+
+        Ok(Some(ForeignCallResult {
+            values: vec![
+                ForeignCallParam::Single(sequence_length.into()),
+                ForeignCallParam::Array(sequence),
+            ],
+        }))
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+/// This struct represents an oracle mock. It can be used for testing programs that use oracles.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct MockedCall {
+    /// The id of the mock, used to update or remove it
+    id: usize,
+    /// The oracle it's mocking
+    name: String,
+    /// Optionally match the parameters
+    params: Option<Vec<ForeignCallParam>>,
+    /// The result to return when this mock is called
+    result: ForeignCallResult,
+    /// How many times should this mock be called before it is removed
+    times_left: Option<u64>,
+}
+
+impl MockedCall {
+    fn new(id: usize, name: String) -> Self {
+        Self {
+            id,
+            name,
+            params: None,
+            result: ForeignCallResult { values: vec![] },
+            times_left: None,
+        }
+    }
+}
+
+impl MockedCall {
+    fn matches(&self, name: &str, params: &Vec<ForeignCallParam>) -> bool {
+        self.name == name && (self.params.is_none() || self.params.as_ref() == Some(params))
+    }
+}
+
+/// Handles the oracles used by `std::test::OracleMock` to register mocks,
+/// along with calls to any oracle which has been mocked.
+#[derive(Debug, Default, Clone)]
+pub struct MockHandler {
+    /// Mocks have unique ids used to identify them in Noir, allowing to update or remove them.
+    last_mock_id: usize,
+    /// The registered mocks
+    mocked_responses: Vec<MockedCall>,
+}
+
+impl ForeignCallHandler for MockHandler {
+    fn handle(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, NargoError> {
+        let foreign_call_name = foreign_call.function.as_str();
+        let result = match ForeignCall::lookup(foreign_call_name) {
+            Some(ForeignCall::CreateMock) => {
+                let mock_oracle_name = Self::parse_string(&foreign_call.inputs[0]);
+                assert!(ForeignCall::lookup(&mock_oracle_name).is_none());
+                let id = self.last_mock_id;
+                self.mocked_responses.push(MockedCall::new(id, mock_oracle_name));
+                self.last_mock_id += 1;
+
+                ForeignCallResult { values: vec![Value::from(id).into()] }
+            }
+            Some(ForeignCall::SetMockParams) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                self.find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .params = Some(params.to_vec());
+
+                ForeignCallResult { values: vec![] }
+            }
+            Some(ForeignCall::SetMockReturns) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                self.find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .result = ForeignCallResult { values: params.to_vec() };
+
+                ForeignCallResult { values: vec![] }
+            }
+            Some(ForeignCall::SetMockTimes) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let times = params[0]
+                    .unwrap_value()
+                    .to_field()
+                    .try_to_u64()
+                    .expect("Invalid bit size of times");
+
+                self.find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .times_left = Some(times);
+
+                ForeignCallResult { values: vec![] }
+            }
+            Some(ForeignCall::ClearMock) => {
+                let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
+                self.mocked_responses.retain(|response| response.id != id);
+                ForeignCallResult { values: vec![] }
+            }
+            Some(_) => return Ok(None),
+            None => match self.execute_mock(foreign_call) {
+                Some(result) => result,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(result))
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn ForeignCallHandler>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl MockHandler {
+    /// Returns the result of the first registered mock which matches `foreign_call`, if any.
+    fn execute_mock(&mut self, foreign_call: &ForeignCallWaitInfo) -> Option<ForeignCallResult> {
+        let foreign_call_name = foreign_call.function.as_str();
+        let response_position = self
+            .mocked_responses
+            .iter()
+            .position(|response| response.matches(foreign_call_name, &foreign_call.inputs))?;
+
+        let mock = self
+            .mocked_responses
+            .get_mut(response_position)
+            .expect("Invalid position of mocked response");
+        let result = mock.result.values.clone();
+
+        if let Some(times_left) = &mut mock.times_left {
+            *times_left -= 1;
+            if *times_left == 0 {
+                self.mocked_responses.remove(response_position);
+            }
+        }
+
+        Some(ForeignCallResult { values: result })
+    }
+
+    fn extract_mock_id(
+        foreign_call_inputs: &[ForeignCallParam],
+    ) -> Result<(usize, &[ForeignCallParam]), ForeignCallError> {
+        let (id, params) =
+            foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        Ok((id.unwrap_value().to_usize(), params))
+    }
+
+    fn find_mock_by_id(&mut self, id: usize) -> Option<&mut MockedCall> {
+        self.mocked_responses.iter_mut().find(|response| response.id == id)
+    }
+
+    fn parse_string(param: &ForeignCallParam) -> String {
+        let fields: Vec<_> = param.values().into_iter().map(|value| value.to_field()).collect();
+        decode_string_value(&fields)
+    }
+}
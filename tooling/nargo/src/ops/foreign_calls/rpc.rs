@@ -0,0 +1,209 @@
+//! Resolution of oracle calls by an external JSON-RPC 2.0 server.
+//!
+//! Each oracle call is sent as a request whose `method` is the name of the oracle and whose `params`
+//! are the oracle's inputs. A single value is encoded as a `0x`-prefixed hex string and an array
+//! as a JSON array of such strings. The server responds with a `result` of the form
+//! `{ "values": [...] }`, where the outputs of the oracle use the same encoding as its inputs.
+
+use acvm::{
+    acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
+    pwg::ForeignCallWaitInfo,
+    FieldElement,
+};
+use iter_extended::{try_vecmap, vecmap};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::NargoError;
+
+use super::ForeignCallHandler;
+
+#[derive(Debug, Error)]
+pub enum OracleResolverError {
+    #[error("Failed to send oracle call `{name}` to {url}: {reason}")]
+    Transport { name: String, url: String, reason: String },
+
+    #[error("Oracle resolver returned an error for `{name}`: {message} (code {code})")]
+    Rpc { name: String, code: i64, message: String },
+
+    #[error("Oracle resolver returned an invalid response for `{name}`: {reason}")]
+    InvalidResponse { name: String, reason: String },
+}
+
+/// The JSON encoding of a [`ForeignCallParam`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum EncodedParam {
+    Single(String),
+    Array(Vec<String>),
+}
+
+impl From<&ForeignCallParam> for EncodedParam {
+    fn from(param: &ForeignCallParam) -> Self {
+        match param {
+            ForeignCallParam::Single(value) => EncodedParam::Single(encode_value(value)),
+            ForeignCallParam::Array(values) => EncodedParam::Array(vecmap(values, encode_value)),
+        }
+    }
+}
+
+impl TryFrom<EncodedParam> for ForeignCallParam {
+    type Error = String;
+
+    fn try_from(param: EncodedParam) -> Result<Self, Self::Error> {
+        match param {
+            EncodedParam::Single(value) => decode_value(&value).map(ForeignCallParam::Single),
+            EncodedParam::Array(values) => {
+                try_vecmap(values, |value| decode_value(&value)).map(ForeignCallParam::Array)
+            }
+        }
+    }
+}
+
+fn encode_value(value: &Value) -> String {
+    format!("0x{}", value.to_field().to_hex())
+}
+
+fn decode_value(value: &str) -> Result<Value, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    // Hex decoding requires an even number of digits.
+    let padded_digits =
+        if digits.len() % 2 == 0 { digits.to_string() } else { format!("0{digits}") };
+    FieldElement::from_hex(&padded_digits)
+        .map(Value::from)
+        .ok_or_else(|| format!("`{value}` is not a hex encoded field element"))
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Vec<EncodedParam>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    result: Option<EncodedResult>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Deserialize)]
+struct EncodedResult {
+    values: Vec<EncodedParam>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+/// Forwards oracle calls to a JSON-RPC server.
+#[derive(Debug)]
+pub struct OracleResolver {
+    url: String,
+    next_id: u64,
+    #[cfg(not(target_arch = "wasm32"))]
+    client: reqwest::blocking::Client,
+}
+
+impl OracleResolver {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            next_id: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    pub fn resolve(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, OracleResolverError> {
+        let name = &foreign_call.function;
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: self.next_id,
+            method: name,
+            params: vecmap(&foreign_call.inputs, EncodedParam::from),
+        };
+        self.next_id += 1;
+
+        let response_body = self.send(&request).map_err(|reason| {
+            OracleResolverError::Transport { name: name.clone(), url: self.url.clone(), reason }
+        })?;
+        let invalid_response =
+            |reason: String| OracleResolverError::InvalidResponse { name: name.clone(), reason };
+
+        let response: JsonRpcResponse = serde_json::from_str(&response_body)
+            .map_err(|err| invalid_response(err.to_string()))?;
+        match (response.result, response.error) {
+            (_, Some(JsonRpcError { code, message })) => {
+                Err(OracleResolverError::Rpc { name: name.clone(), code, message })
+            }
+            (Some(result), None) => {
+                let values = try_vecmap(result.values, ForeignCallParam::try_from)
+                    .map_err(invalid_response)?;
+                Ok(ForeignCallResult { values })
+            }
+            (None, None) => Err(invalid_response("response contains no result".to_string())),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn send(&self, request: &JsonRpcRequest) -> Result<String, String> {
+        let body = serde_json::to_string(request).map_err(|err| err.to_string())?;
+        self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .map_err(|err| err.to_string())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn send(&self, _request: &JsonRpcRequest) -> Result<String, String> {
+        Err("oracle resolvers are not supported when targeting WebAssembly".to_string())
+    }
+}
+
+impl ForeignCallHandler for OracleResolver {
+    /// Every foreign call which reaches the resolver is forwarded to the server.
+    fn handle(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, NargoError> {
+        Ok(Some(self.resolve(foreign_call)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::brillig::{ForeignCallParam, Value},
+        FieldElement,
+    };
+
+    use super::EncodedParam;
+
+    #[test]
+    fn foreign_call_params_round_trip_through_json() {
+        let params = vec![
+            ForeignCallParam::Single(Value::from(FieldElement::from(42_u128))),
+            ForeignCallParam::Array(vec![Value::from(1_u128), Value::from(2_u128)]),
+        ];
+
+        let encoded: Vec<EncodedParam> = params.iter().map(EncodedParam::from).collect();
+        let json = serde_json::to_string(&encoded).unwrap();
+        assert!(json.starts_with(r#"["0x"#));
+
+        let decoded: Vec<EncodedParam> = serde_json::from_str(&json).unwrap();
+        let decoded: Vec<ForeignCallParam> =
+            decoded.into_iter().map(|param| param.try_into().unwrap()).collect();
+        assert_eq!(decoded, params);
+    }
+}
@@ -0,0 +1,96 @@
+use acvm::{
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A single oracle call made during execution along with the result which was returned to the program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedForeignCall {
+    pub name: String,
+    pub inputs: Vec<ForeignCallParam>,
+    pub result: ForeignCallResult,
+}
+
+/// The oracle calls made during an execution, in the order in which they were made.
+///
+/// Foreign calls resolved by deterministic handlers (e.g. `println`) are not included
+/// as they do not depend on anything outside of the program.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignCallTranscript {
+    pub calls: Vec<RecordedForeignCall>,
+}
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error("Oracle call {position} to `{actual}` diverged from the transcript, which expected a call to `{expected}`")]
+    NameMismatch { position: usize, expected: String, actual: String },
+
+    #[error("Oracle call {position} to `{name}` was made with different inputs than those in the transcript")]
+    InputsMismatch { position: usize, name: String },
+
+    #[error("Oracle call to `{name}` was made but the transcript only contains {recorded} calls")]
+    TranscriptExhausted { name: String, recorded: usize },
+
+    #[error("Execution finished after {made} of the {recorded} oracle calls in the transcript")]
+    UnreplayedCalls { made: usize, recorded: usize },
+}
+
+/// Feeds the results stored in a [`ForeignCallTranscript`] back to a program,
+/// checking that the program makes exactly the same oracle calls as when it was recorded.
+#[derive(Debug, Clone)]
+pub(super) struct TranscriptReplay {
+    transcript: ForeignCallTranscript,
+    position: usize,
+}
+
+impl TranscriptReplay {
+    pub(super) fn new(transcript: ForeignCallTranscript) -> Self {
+        Self { transcript, position: 0 }
+    }
+
+    pub(super) fn next_result(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, TranscriptError> {
+        let recorded_calls = self.transcript.calls.len();
+        let expected = self.transcript.calls.get(self.position).ok_or_else(|| {
+            TranscriptError::TranscriptExhausted {
+                name: foreign_call.function.clone(),
+                recorded: recorded_calls,
+            }
+        })?;
+
+        if expected.name != foreign_call.function {
+            return Err(TranscriptError::NameMismatch {
+                position: self.position,
+                expected: expected.name.clone(),
+                actual: foreign_call.function.clone(),
+            });
+        }
+        if expected.inputs != foreign_call.inputs {
+            return Err(TranscriptError::InputsMismatch {
+                position: self.position,
+                name: expected.name.clone(),
+            });
+        }
+
+        self.position += 1;
+        Ok(expected.result.clone())
+    }
+
+    /// Replays the transcript again from the first recorded call.
+    pub(super) fn restart(&mut self) {
+        self.position = 0;
+    }
+
+    pub(super) fn ensure_completed(&self) -> Result<(), TranscriptError> {
+        let recorded = self.transcript.calls.len();
+        if self.position == recorded {
+            Ok(())
+        } else {
+            Err(TranscriptError::UnreplayedCalls { made: self.position, recorded })
+        }
+    }
+}
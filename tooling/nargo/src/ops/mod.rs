@@ -1,9 +1,21 @@
-pub use self::execute::execute_circuit;
-pub use self::foreign_calls::ForeignCallExecutor;
+pub use self::bench::{run_bench, BenchOptions, BenchReport, BenchStatus, TimingStatistics};
+pub use self::differential::{run_differential, DifferentialStatus, ExecutionOutcome};
+pub use self::execute::{execute_acir_call, execute_circuit};
+pub use self::foreign_calls::{
+    default_handlers, ForeignCallExecutor, ForeignCallExecutorSnapshot, ForeignCallHandler,
+    ForeignCallTranscript, MockHandler, OracleResolver, OracleResolverError, PrintHandler,
+    RecordedForeignCall, SequenceHandler, TranscriptError,
+};
 pub use self::optimize::{optimize_contract, optimize_program};
 pub use self::test::{run_test, TestStatus};
+pub use self::trace::execute_circuit_with_trace;
 
+mod bench;
+mod differential;
 mod execute;
 mod foreign_calls;
 mod optimize;
 mod test;
+#[cfg(test)]
+mod test_utils;
+mod trace;
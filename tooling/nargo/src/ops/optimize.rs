@@ -14,6 +14,11 @@ pub fn optimize_program(
 
     program.circuit = optimized_circuit;
     program.debug.update_acir(location_map);
+    program.functions = try_vecmap(program.functions, |function| {
+        let (optimized_function, _) =
+            acvm::compiler::compile(function, np_language, is_opcode_supported)?;
+        Ok::<_, NargoError>(optimized_function)
+    })?;
     Ok(program)
 }
 
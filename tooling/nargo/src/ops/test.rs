@@ -6,7 +6,7 @@ use noirc_frontend::hir::{def_map::TestFunction, Context};
 
 use crate::{errors::try_to_diagnose_runtime_error, NargoError};
 
-use super::execute_circuit;
+use super::{execute_circuit, ForeignCallExecutor};
 
 pub enum TestStatus {
     Pass,
@@ -18,7 +18,7 @@ pub fn run_test<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     context: &Context,
     test_function: TestFunction,
-    show_output: bool,
+    foreign_call_executor: &mut ForeignCallExecutor,
     config: &CompileOptions,
 ) -> TestStatus {
     let program = compile_no_check(context, config, test_function.get_id(), None, false);
@@ -26,8 +26,13 @@ pub fn run_test<B: BlackBoxFunctionSolver>(
         Ok(program) => {
             // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
             // otherwise constraints involving these expressions will not error.
-            let circuit_execution =
-                execute_circuit(blackbox_solver, &program.circuit, WitnessMap::new(), show_output);
+            let circuit_execution = execute_circuit(
+                blackbox_solver,
+                &program.circuit,
+                &program.functions,
+                WitnessMap::new(),
+                foreign_call_executor,
+            );
             test_status_program_compile_pass(test_function, program.debug, circuit_execution)
         }
         Err(err) => test_status_program_compile_fail(err, test_function),
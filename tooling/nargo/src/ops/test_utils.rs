@@ -0,0 +1,57 @@
+//! Helpers for compiling and executing small Noir programs within unit tests.
+use std::path::Path;
+
+use acvm::{acir::BlackBoxFunc, BlackBoxFunctionSolver, BlackBoxResolutionError, FieldElement};
+use fm::FileManager;
+use noirc_driver::{check_crate, prepare_crate};
+use noirc_frontend::{graph::CrateGraph, hir::Context, node_interner::FuncId};
+
+/// A solver for programs which do not make use of any black box functions.
+pub(crate) struct StubbedSolver;
+
+impl BlackBoxFunctionSolver for StubbedSolver {
+    fn schnorr_verify(
+        &self,
+        _public_key_x: &FieldElement,
+        _public_key_y: &FieldElement,
+        _signature: &[u8],
+        _message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::SchnorrVerify))
+    }
+    fn pedersen_commitment(
+        &self,
+        _inputs: &[FieldElement],
+        _domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::PedersenCommitment))
+    }
+    fn pedersen_hash(
+        &self,
+        _inputs: &[FieldElement],
+        _domain_separator: u32,
+    ) -> Result<FieldElement, BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::PedersenHash))
+    }
+    fn fixed_base_scalar_mul(
+        &self,
+        _low: &FieldElement,
+        _high: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::FixedBaseScalarMul))
+    }
+}
+
+/// Type checks `source` as the `main.nr` of a binary crate, returning the context
+/// along with the crate's `main` function.
+pub(crate) fn check_program(source: &str) -> (Context, FuncId) {
+    let source = source.to_string();
+    let file_manager =
+        FileManager::new(Path::new("/test"), Box::new(move |_path| Ok(source.clone())));
+    let mut context = Context::new(file_manager, CrateGraph::default());
+    let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+
+    check_crate(&mut context, crate_id, false).expect("test program should type check");
+    let main = context.get_main_function(&crate_id).expect("test program should contain `main`");
+    (context, main)
+}
@@ -0,0 +1,333 @@
+use std::io::Write;
+
+use acvm::acir::circuit::brillig::BrilligOutputs;
+use acvm::acir::circuit::directives::{Directive, QuotientDirective};
+use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+use acvm::acir::native_types::{Expression, Witness, WitnessMap};
+use acvm::pwg::{ACVMStatus, BrilligSolverStatus, StepResult, ACVM};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use codespan_reporting::files::Files;
+use serde::Serialize;
+
+use crate::artifacts::debug::DebugArtifact;
+use crate::NargoError;
+
+use super::execute::{execute_acir_call, execution_failure};
+use super::foreign_calls::ForeignCallExecutor;
+
+/// A single step of execution, written to the trace as a line of JSON.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum TraceRecord {
+    /// An ACIR opcode which has been solved, along with the witnesses it assigned.
+    Acir {
+        acir_index: usize,
+        opcode: String,
+        witnesses: Vec<AssignedWitness>,
+        source: Vec<SourceLocation>,
+    },
+    /// A Brillig instruction which has been executed, along with the registers it changed.
+    Brillig {
+        acir_index: usize,
+        brillig_index: usize,
+        opcode: String,
+        registers: Vec<ChangedRegister>,
+        source: Vec<SourceLocation>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct AssignedWitness {
+    witness: u32,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangedRegister {
+    register: usize,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SourceLocation {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+/// Executes `circuit` like [`execute_circuit`][super::execute_circuit], writing a trace of every
+/// solved ACIR opcode and every executed Brillig instruction to `trace` as they are executed.
+///
+/// The trace is written in the JSON Lines format, with one record per line. Each record is
+/// annotated with the source locations of its opcode, innermost last, using `debug_artifact`.
+/// Calls into the other `functions` of the program are recorded as a single ACIR opcode.
+pub fn execute_circuit_with_trace<B: BlackBoxFunctionSolver, W: Write>(
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    functions: &[Circuit],
+    initial_witness: WitnessMap,
+    foreign_call_executor: &mut ForeignCallExecutor,
+    debug_artifact: &DebugArtifact,
+    trace: &mut W,
+) -> Result<WitnessMap, NargoError> {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+
+    loop {
+        match acvm.get_status() {
+            ACVMStatus::Solved => break,
+            ACVMStatus::InProgress => (),
+            ACVMStatus::Failure(error) => return Err(execution_failure(circuit, error.clone())),
+            ACVMStatus::RequiresForeignCall(foreign_call) => {
+                let foreign_call_result = foreign_call_executor.execute(foreign_call)?;
+                acvm.resolve_pending_foreign_call(foreign_call_result);
+                continue;
+            }
+            ACVMStatus::RequiresAcirCall(_) => {
+                unreachable!("ACIR calls are resolved as soon as they are made")
+            }
+        }
+
+        let acir_index = acvm.instruction_pointer();
+        let opcode = &circuit.opcodes[acir_index];
+        let unassigned_witnesses: Vec<Witness> = written_witnesses(opcode)
+            .into_iter()
+            .filter(|witness| !acvm.witness_map().contains_key(witness))
+            .collect();
+
+        if let StepResult::IntoBrillig(mut solver) = acvm.step_into_brillig_opcode() {
+            let Opcode::Brillig(brillig) = opcode else {
+                unreachable!("Stepped into a Brillig block from a non-Brillig opcode");
+            };
+            loop {
+                let brillig_index = solver.program_counter();
+                let registers_before = solver.get_registers().inner.clone();
+                let status = solver.step().map_err(|error| execution_failure(circuit, error))?;
+
+                if let BrilligSolverStatus::ForeignCallWait(foreign_call) = &status {
+                    // The instruction is executed again once the foreign call has been resolved.
+                    let foreign_call_result = foreign_call_executor.execute(foreign_call)?;
+                    solver.resolve_pending_foreign_call(foreign_call_result);
+                    continue;
+                }
+
+                let registers = solver.get_registers().inner.iter().enumerate();
+                let registers = registers
+                    .filter(|(register, value)| registers_before.get(*register) != Some(value))
+                    .map(|(register, value)| ChangedRegister {
+                        register,
+                        value: format_field(value.to_field()),
+                    })
+                    .collect();
+                let location = OpcodeLocation::Brillig { acir_index, brillig_index };
+                write_record(
+                    trace,
+                    &TraceRecord::Brillig {
+                        acir_index,
+                        brillig_index,
+                        opcode: format!("{:?}", brillig.bytecode[brillig_index]),
+                        registers,
+                        source: source_locations(debug_artifact, &location),
+                    },
+                )?;
+
+                if let BrilligSolverStatus::Finished = status {
+                    acvm.finish_brillig_with_solver(solver);
+                    break;
+                }
+            }
+        }
+
+        if let Some(acir_call) = acvm.get_pending_acir_call().cloned() {
+            let return_values = execute_acir_call(
+                blackbox_solver,
+                functions,
+                &acir_call,
+                OpcodeLocation::Acir(acir_index),
+                &mut |foreign_call| foreign_call_executor.execute(foreign_call),
+            )?;
+            acvm.resolve_pending_acir_call(return_values);
+        }
+
+        // The opcode is only solved once execution has moved on to the next opcode.
+        if acvm.instruction_pointer() != acir_index {
+            let witnesses = unassigned_witnesses
+                .into_iter()
+                .filter_map(|witness| {
+                    let value = acvm.witness_map().get(&witness)?;
+                    Some(AssignedWitness {
+                        witness: witness.witness_index(),
+                        value: format_field(*value),
+                    })
+                })
+                .collect();
+            let location = OpcodeLocation::Acir(acir_index);
+            write_record(
+                trace,
+                &TraceRecord::Acir {
+                    acir_index,
+                    opcode: opcode.to_string(),
+                    witnesses,
+                    source: source_locations(debug_artifact, &location),
+                },
+            )?;
+        }
+    }
+
+    Ok(acvm.finalize())
+}
+
+fn write_record<W: Write>(trace: &mut W, record: &TraceRecord) -> Result<(), NargoError> {
+    serde_json::to_writer(&mut *trace, record).map_err(std::io::Error::from)?;
+    writeln!(trace)?;
+    Ok(())
+}
+
+fn format_field(value: FieldElement) -> String {
+    format!("0x{}", value.to_hex())
+}
+
+fn source_locations(
+    debug_artifact: &DebugArtifact,
+    opcode_location: &OpcodeLocation,
+) -> Vec<SourceLocation> {
+    let Some(call_stack) = debug_artifact
+        .debug_symbols
+        .first()
+        .and_then(|debug_info| debug_info.opcode_location(opcode_location))
+    else {
+        return Vec::new();
+    };
+    call_stack
+        .into_iter()
+        .filter_map(|location| {
+            Some(SourceLocation {
+                file: debug_artifact.name(location.file).ok()?.to_string(),
+                line: debug_artifact.location_line_number(location).ok()?,
+                column: debug_artifact.location_column_number(location).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the witnesses which may be assigned by solving `opcode`.
+fn written_witnesses(opcode: &Opcode) -> Vec<Witness> {
+    let expression_witnesses = |expression: &Expression| {
+        let mul_witnesses = expression.mul_terms.iter().flat_map(|(_, lhs, rhs)| [*lhs, *rhs]);
+        let linear_witnesses = expression.linear_combinations.iter().map(|(_, witness)| *witness);
+        mul_witnesses.chain(linear_witnesses).collect::<Vec<_>>()
+    };
+
+    match opcode {
+        Opcode::Arithmetic(expression) => expression_witnesses(expression),
+        Opcode::BlackBoxFuncCall(call) => call.get_outputs_vec(),
+        Opcode::Directive(Directive::Quotient(QuotientDirective { q, r, .. })) => vec![*q, *r],
+        Opcode::Directive(Directive::ToLeRadix { b, .. }) => b.clone(),
+        Opcode::Directive(Directive::PermutationSort { bits, .. }) => bits.clone(),
+        Opcode::Brillig(brillig) => brillig
+            .outputs
+            .iter()
+            .flat_map(|output| match output {
+                BrilligOutputs::Simple(witness) => vec![*witness],
+                BrilligOutputs::Array(witnesses) => witnesses.clone(),
+            })
+            .collect(),
+        Opcode::MemoryOp { op, .. } => expression_witnesses(&op.value),
+        Opcode::MemoryInit { .. } => Vec::new(),
+        Opcode::Call { outputs, .. } => outputs.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::FieldElement;
+    use noirc_abi::{input_parser::InputValue, InputMap};
+    use noirc_driver::{compile_no_check, CompileOptions};
+    use serde_json::Value;
+
+    use crate::artifacts::debug::DebugArtifact;
+    use crate::ops::test_utils::{check_program, StubbedSolver};
+    use crate::ops::ForeignCallExecutor;
+
+    use super::{execute_circuit_with_trace, format_field};
+
+    /// `double` is executed as a Brillig block which assigns the witness of `y`.
+    const PROGRAM: &str = "unconstrained fn double(x: Field) -> Field {
+    x * 2
+}
+
+fn main(x: Field) -> pub Field {
+    let y = double(x);
+    assert(y == x + x);
+    y * x
+}
+";
+
+    #[test]
+    fn trace_records_assigned_witnesses_and_changed_registers() {
+        let (context, main) = check_program(PROGRAM);
+        let program =
+            compile_no_check(&context, &CompileOptions::default(), main, None, false).unwrap();
+        let debug_artifact = DebugArtifact::new(vec![program.debug.clone()], &context.file_manager);
+        let inputs = InputMap::from([("x".to_string(), InputValue::Field(3_u128.into()))]);
+        let initial_witness = program.abi.encode(&inputs, None).unwrap();
+
+        let mut trace = Vec::new();
+        let solved_witness = execute_circuit_with_trace(
+            &StubbedSolver,
+            &program.circuit,
+            &program.functions,
+            initial_witness.clone(),
+            &mut ForeignCallExecutor::default(),
+            &debug_artifact,
+            &mut trace,
+        )
+        .unwrap();
+        let records: Vec<Value> = String::from_utf8(trace)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // Every ACIR opcode is recorded once, with the witnesses which it newly assigned.
+        let acir_records: Vec<_> =
+            records.iter().filter(|record| record["kind"] == "acir").collect();
+        assert_eq!(acir_records.len(), program.circuit.opcodes.len());
+        let mut assigned_witnesses: BTreeSet<u32> =
+            initial_witness.into_iter().map(|(witness, _)| witness.witness_index()).collect();
+        for record in &acir_records {
+            for assigned in record["witnesses"].as_array().unwrap() {
+                let witness = assigned["witness"].as_u64().unwrap() as u32;
+                assert!(assigned_witnesses.insert(witness), "witness {witness} assigned twice");
+            }
+        }
+        let solved_witnesses: BTreeSet<u32> =
+            solved_witness.into_iter().map(|(witness, _)| witness.witness_index()).collect();
+        assert_eq!(assigned_witnesses, solved_witnesses);
+
+        // The Brillig instructions are recorded before the ACIR opcode executing their block.
+        let first_acir_record = records.iter().position(|record| record["kind"] == "acir").unwrap();
+        assert!(first_acir_record > 0);
+        let brillig_records = &records[..first_acir_record];
+        assert!(brillig_records.iter().all(|record| record["kind"] == "brillig"));
+        assert!(brillig_records.iter().all(|record| record["acir_index"] == 0));
+
+        // The block only records the registers changed by each instruction, including
+        // the result of `x * 2`, which it outputs to the witness of `y`.
+        let six = format_field(FieldElement::from(6_u128));
+        let changed_values: Vec<&Value> = brillig_records
+            .iter()
+            .flat_map(|record| record["registers"].as_array().unwrap())
+            .map(|register| &register["value"])
+            .collect();
+        assert!(changed_values.iter().any(|value| **value == six));
+        let brillig_output = &records[first_acir_record];
+        assert_eq!(brillig_output["acir_index"], 0);
+        let outputs = brillig_output["witnesses"].as_array().unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0]["value"], six);
+        let source = brillig_output["source"].as_array().unwrap();
+        assert_eq!(source.last().unwrap()["line"], 6);
+    }
+}
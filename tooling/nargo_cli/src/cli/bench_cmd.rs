@@ -0,0 +1,268 @@
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use acvm::BlackBoxFunctionSolver;
+use clap::Args;
+use nargo::{
+    ops::{run_bench, BenchOptions, BenchReport, BenchStatus},
+    package::Package,
+    prepare_package,
+};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_driver::{CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::{graph::CrateName, hir::FunctionNameMatch};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
+
+use super::fs::bench::{read_bench_reports_from_dir, save_bench_reports_to_dir, BenchReports};
+use super::NargoConfig;
+
+/// Run the benchmarks for this program
+///
+/// Each function marked with `#[bench]` is executed repeatedly and the time taken to generate
+/// its witness is reported along with the number of ACIR and Brillig opcodes executed.
+/// Results are saved to the target directory and compared against on the next run.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct BenchCommand {
+    /// If given, only benchmarks with names containing this string will be run
+    bench_name: Option<String>,
+
+    /// Only run benchmarks that match exactly
+    #[clap(long)]
+    exact: bool,
+
+    /// The name of the package to benchmark
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Benchmark all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Number of times each benchmark is executed before measurements are taken
+    #[clap(long, default_value_t = 3)]
+    warmup: usize,
+
+    /// Number of times each benchmark is executed and measured
+    #[clap(long, default_value_t = 20)]
+    iterations: usize,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(
+    _backend: &Backend,
+    args: BenchCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+    let bench_dir = workspace.target_directory_path().join("bench");
+
+    let pattern = match &args.bench_name {
+        Some(name) => {
+            if args.exact {
+                FunctionNameMatch::Exact(name)
+            } else {
+                FunctionNameMatch::Contains(name)
+            }
+        }
+        None => FunctionNameMatch::Anything,
+    };
+    let options = BenchOptions { warmup_iterations: args.warmup, iterations: args.iterations };
+
+    #[allow(deprecated)]
+    let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
+    for package in &workspace {
+        let previous_reports = read_bench_reports_from_dir(&package.name, &bench_dir);
+        let reports = run_benches(
+            &blackbox_solver,
+            package,
+            pattern,
+            &args.compile_options,
+            options,
+            &previous_reports,
+        )?;
+
+        if !reports.is_empty() {
+            save_merged_reports(reports, &package.name, &bench_dir);
+        }
+    }
+
+    Ok(())
+}
+
+/// Saves `reports` along with the previously saved reports of any benchmarks which were not run,
+/// so that running a subset of benchmarks doesn't discard the results of the others.
+fn save_merged_reports(reports: BenchReports, package_name: &CrateName, bench_dir: &Path) {
+    let mut saved_reports = read_bench_reports_from_dir(package_name, bench_dir);
+    saved_reports.extend(reports);
+    save_bench_reports_to_dir(&saved_reports, package_name, bench_dir);
+}
+
+fn run_benches<S: BlackBoxFunctionSolver>(
+    blackbox_solver: &S,
+    package: &Package,
+    bench_name: FunctionNameMatch,
+    compile_options: &CompileOptions,
+    options: BenchOptions,
+    previous_reports: &BenchReports,
+) -> Result<BenchReports, CliError> {
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(
+        &mut context,
+        crate_id,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
+
+    let bench_functions = context.get_all_bench_functions_in_crate_matching(&crate_id, bench_name);
+
+    println!("[{}] Running {} benchmark functions", package.name, bench_functions.len());
+    let mut failing = 0;
+    let mut reports = BenchReports::new();
+
+    let writer = StandardStream::stderr(ColorChoice::Always);
+    let mut writer = writer.lock();
+
+    for (bench_name, bench_function) in bench_functions {
+        write!(writer, "[{}] Benchmarking {bench_name}... ", package.name)
+            .expect("Failed to write to stdout");
+        writer.flush().expect("Failed to flush writer");
+
+        match run_bench(blackbox_solver, &context, bench_function, compile_options, options) {
+            BenchStatus::Pass(report) => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
+                    .expect("Failed to set color");
+                writeln!(writer, "ok").expect("Failed to write to stdout");
+                writer.reset().expect("Failed to reset writer");
+                writeln!(writer, "{}", format_report(&report, previous_reports.get(&bench_name)))
+                    .expect("Failed to write to stdout");
+                reports.insert(bench_name, report);
+            }
+            BenchStatus::Fail { message, error_diagnostic } => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
+                    .expect("Failed to set color");
+                writeln!(writer, "{message}").expect("Failed to write to stdout");
+                writer.reset().expect("Failed to reset writer");
+                if let Some(diag) = error_diagnostic {
+                    noirc_errors::reporter::report_all(
+                        context.file_manager.as_file_map(),
+                        &[diag],
+                        compile_options.deny_warnings,
+                        compile_options.silence_warnings,
+                    );
+                }
+                failing += 1;
+            }
+            BenchStatus::CompileError(err) => {
+                noirc_errors::reporter::report_all(
+                    context.file_manager.as_file_map(),
+                    &[err],
+                    compile_options.deny_warnings,
+                    compile_options.silence_warnings,
+                );
+                failing += 1;
+            }
+        }
+        writer.reset().expect("Failed to reset writer");
+    }
+
+    if failing != 0 {
+        let plural = if failing == 1 { "" } else { "s" };
+        return Err(CliError::Generic(format!(
+            "[{}] {failing} benchmark{plural} failed",
+            package.name
+        )));
+    }
+
+    Ok(reports)
+}
+
+fn format_report(report: &BenchReport, previous_report: Option<&BenchReport>) -> String {
+    let timing = &report.witness_generation;
+    let nanos = Duration::from_nanos;
+
+    let mut output = format!(
+        "    witness generation: {:?} ± {:?} (median {:?}, min {:?}, max {:?}, {} samples)",
+        nanos(timing.mean_ns),
+        nanos(timing.std_dev_ns),
+        nanos(timing.median_ns),
+        nanos(timing.min_ns),
+        nanos(timing.max_ns),
+        timing.samples,
+    );
+    if let Some(previous_report) = previous_report {
+        let previous_mean = previous_report.witness_generation.mean_ns;
+        if previous_mean != 0 {
+            let change = (timing.mean_ns as f64 - previous_mean as f64) / previous_mean as f64;
+            output.push_str(&format!(" [{:+.2}% vs previous run]", change * 100.0));
+        }
+    }
+
+    output.push_str(&format!(
+        "\n    ACVM opcodes solved: {}, Brillig opcodes executed: {}",
+        report.acvm_opcodes_solved, report.brillig_opcodes_executed
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use nargo::ops::{BenchReport, TimingStatistics};
+    use noirc_frontend::graph::CrateName;
+
+    use super::{format_report, read_bench_reports_from_dir, save_merged_reports, BenchReports};
+
+    fn report(mean_ns: u64) -> BenchReport {
+        BenchReport {
+            acvm_opcodes_solved: 10,
+            brillig_opcodes_executed: 20,
+            witness_generation: TimingStatistics { samples: 1, mean_ns, ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn saved_reports_are_merged_with_previous_reports() {
+        let bench_dir = tempfile::tempdir().unwrap();
+        let package_name: CrateName = "foo".parse().unwrap();
+        assert!(read_bench_reports_from_dir(&package_name, bench_dir.path()).is_empty());
+
+        let first_run = BenchReports::from([
+            ("bench_a".to_string(), report(100)),
+            ("bench_b".to_string(), report(200)),
+        ]);
+        save_merged_reports(first_run, &package_name, bench_dir.path());
+
+        // Only `bench_b` is run the second time.
+        let second_run = BenchReports::from([("bench_b".to_string(), report(300))]);
+        save_merged_reports(second_run, &package_name, bench_dir.path());
+
+        let saved_reports = read_bench_reports_from_dir(&package_name, bench_dir.path());
+        assert_eq!(saved_reports.len(), 2);
+        assert_eq!(saved_reports["bench_a"].witness_generation.mean_ns, 100);
+        assert_eq!(saved_reports["bench_b"].witness_generation.mean_ns, 300);
+    }
+
+    #[test]
+    fn reports_are_compared_with_the_previous_run() {
+        let output = format_report(&report(150), Some(&report(100)));
+        assert!(output.contains("[+50.00% vs previous run]"));
+        assert!(output.contains("ACVM opcodes solved: 10, Brillig opcodes executed: 20"));
+
+        assert!(!format_report(&report(150), None).contains("previous run"));
+    }
+}
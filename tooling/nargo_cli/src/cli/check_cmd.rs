@@ -7,7 +7,7 @@ use nargo::{package::Package, prepare_package};
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
 use noirc_driver::{
-    check_crate, compute_function_abi, CompileOptions, NOIR_ARTIFACT_VERSION_STRING,
+    audit_crate, check_crate, compute_function_abi, CompileOptions, NOIR_ARTIFACT_VERSION_STRING,
 };
 use noirc_frontend::{
     graph::{CrateId, CrateName},
@@ -28,6 +28,11 @@ pub(crate) struct CheckCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Compile the packages and warn about values computed by unconstrained code which are
+    /// never sufficiently constrained
+    #[clap(long)]
+    audit: bool,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -48,13 +53,17 @@ pub(crate) fn run(
     )?;
 
     for package in &workspace {
-        check_package(package, &args.compile_options)?;
+        check_package(package, &args.compile_options, args.audit)?;
         println!("[{}] Constraint system successfully built!", package.name);
     }
     Ok(())
 }
 
-fn check_package(package: &Package, compile_options: &CompileOptions) -> Result<(), CompileError> {
+fn check_package(
+    package: &Package,
+    compile_options: &CompileOptions,
+    audit: bool,
+) -> Result<(), CompileError> {
     let (mut context, crate_id) =
         prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
     check_crate_and_report_errors(
@@ -64,6 +73,15 @@ fn check_package(package: &Package, compile_options: &CompileOptions) -> Result<
         compile_options.silence_warnings,
     )?;
 
+    if audit {
+        super::compile_cmd::report_errors(
+            audit_crate(&context, crate_id, compile_options),
+            &context.file_manager,
+            compile_options.deny_warnings,
+            compile_options.silence_warnings,
+        )?;
+    }
+
     if package.is_library() || package.is_contract() {
         // Libraries do not have ABIs while contracts have many, so we cannot generate a `Prover.toml` file.
         Ok(())
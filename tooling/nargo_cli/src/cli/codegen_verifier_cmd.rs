@@ -36,6 +36,10 @@ pub(crate) fn run(
     args: CodegenVerifierCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    if args.compile_options.force_brillig {
+        return Err(CliError::ForceBrilligNotProvable);
+    }
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
@@ -83,6 +87,9 @@ fn smart_contract_for_package(
         np_language,
         &is_opcode_supported,
     )?;
+    if !program.functions.is_empty() {
+        return Err(CliError::FoldedFunctionsNotProvable(package.name.clone()));
+    }
 
     let mut smart_contract_string = backend.eth_contract(&program.circuit)?;
 
@@ -189,6 +189,7 @@ fn compile_program(
         Some(CompiledProgram {
             hash: preprocessed_program.hash,
             circuit: preprocessed_program.bytecode,
+            functions: preprocessed_program.functions,
             abi: preprocessed_program.abi,
             noir_version: preprocessed_program.noir_version,
             debug: debug_artifact.debug_symbols.remove(0),
@@ -263,6 +264,7 @@ fn save_program(program: CompiledProgram, package: &Package, circuit_dir: &Path)
         abi: program.abi,
         noir_version: program.noir_version,
         bytecode: program.circuit,
+        functions: program.functions,
     };
 
     save_program_to_file(&preprocessed_program, &package.name, circuit_dir);
@@ -0,0 +1,152 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use acvm::acir::native_types::WitnessMap;
+use clap::Args;
+
+use nargo::constants::PROVER_INPUT_FILE;
+use nargo::ops::ForeignCallExecutor;
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noir_debugger::dap::{DapServer, LaunchArguments, Request};
+use noirc_abi::input_parser::Format;
+use noirc_abi::InputMap;
+use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING};
+
+use super::compile_cmd::compile_bin_package;
+use super::debug_cmd::{find_contract_function, find_test_function, program_debug_artifact};
+use super::fs::inputs::read_inputs_from_file;
+use super::NargoConfig;
+use crate::backends::Backend;
+use crate::errors::CliError;
+
+/// Starts a Debug Adapter Protocol server on stdin and stdout
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DapCommand {
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    args: DapCommand,
+    _config: NargoConfig,
+) -> Result<(), CliError> {
+    let mut server = DapServer::new(std::io::stdin().lock(), std::io::stdout());
+
+    while let Some(request) = server.next_request()? {
+        match request.command.as_str() {
+            "initialize" => server.respond_to_initialize(&request)?,
+            "launch" => {
+                if launch(backend, &mut server, &request, &args.compile_options)? {
+                    break;
+                }
+            }
+            "disconnect" => {
+                server.respond(&request, None)?;
+                break;
+            }
+            command => {
+                let message = format!("Unsupported request `{command}` before launching");
+                server.respond_error(&request, &message)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Loads the program requested by the client and debugs it until the client disconnects.
+///
+/// Returns `false` if the program could not be loaded, in which case the client may request
+/// another launch.
+fn launch<R: BufRead, W: Write>(
+    backend: &Backend,
+    server: &mut DapServer<R, W>,
+    request: &Request,
+    compile_options: &CompileOptions,
+) -> Result<bool, CliError> {
+    let loaded_program = request
+        .parse_arguments::<LaunchArguments>()
+        .map_err(|err| CliError::Generic(format!("Invalid launch arguments: {err}")))
+        .and_then(|arguments| {
+            let (program, initial_witness) = load_program(backend, &arguments, compile_options)?;
+            Ok((program, initial_witness, arguments.stop_on_entry))
+        });
+    let (program, initial_witness, stop_on_entry) = match loaded_program {
+        Ok(loaded_program) => loaded_program,
+        Err(err) => {
+            server.respond_error(request, &err.to_string())?;
+            return Ok(false);
+        }
+    };
+    server.respond(request, None)?;
+
+    #[allow(deprecated)]
+    let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
+
+    // Program output is not shown as stdout is used to communicate with the client.
+    noir_debugger::dap::run_session(
+        server,
+        &blackbox_solver,
+        &program.circuit,
+        &program.functions,
+        &program_debug_artifact(&program),
+        initial_witness,
+        ForeignCallExecutor::new(false),
+        stop_on_entry,
+    )?;
+    Ok(true)
+}
+
+/// Compiles the test function, contract function or `main` function requested by the client
+/// for the debugger, returning it along with its initial witness.
+fn load_program(
+    backend: &Backend,
+    arguments: &LaunchArguments,
+    compile_options: &CompileOptions,
+) -> Result<(CompiledProgram, WitnessMap), CliError> {
+    let toml_path = get_package_manifest(Path::new(&arguments.project_folder))?;
+    let selection = match &arguments.package {
+        Some(package) => PackageSelection::Selected(package.parse().map_err(CliError::Generic)?),
+        None => PackageSelection::DefaultOrAll,
+    };
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+    )?;
+
+    let compile_options = CompileOptions { debug_variables: true, ..compile_options.clone() };
+    if let Some(test_name) = &arguments.test_name {
+        let (_, program) = find_test_function(&workspace, test_name, &compile_options)?;
+        // Test functions do not take any inputs.
+        let initial_witness = program.abi.encode(&InputMap::new(), None)?;
+        return Ok((program, initial_witness));
+    }
+
+    let (package, program) = match &arguments.contract_function {
+        Some(contract_fn) => find_contract_function(&workspace, contract_fn, &compile_options)?,
+        None => {
+            let Some(package) = workspace.into_iter().find(|p| p.is_binary()) else {
+                return Err(CliError::Generic(
+                    "No matching binary packages found in workspace. Only binary packages can be debugged."
+                        .to_string(),
+                ));
+            };
+            let (np_language, opcode_support) = backend.get_backend_info()?;
+            let program = compile_bin_package(
+                &workspace,
+                package,
+                &compile_options,
+                np_language,
+                &|opcode| opcode_support.is_opcode_supported(opcode),
+            )?;
+            (package, program)
+        }
+    };
+
+    let prover_name = arguments.prover_name.as_deref().unwrap_or(PROVER_INPUT_FILE);
+    let (inputs_map, _) =
+        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
+    let initial_witness = program.abi.encode(&inputs_map, None)?;
+    Ok((program, initial_witness))
+}
@@ -3,13 +3,20 @@ use clap::Args;
 
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
+use nargo::ops::ForeignCallExecutor;
 use nargo::package::Package;
+use nargo::prepare_package;
+use nargo::workspace::Workspace;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
-use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_driver::{
+    compile_no_check, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
+};
 use noirc_frontend::graph::CrateName;
+use noirc_frontend::hir::FunctionNameMatch;
 
+use super::check_cmd::check_crate_and_report_errors;
 use super::compile_cmd::compile_bin_package;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
 use super::NargoConfig;
@@ -30,6 +37,14 @@ pub(crate) struct DebugCommand {
     #[clap(long)]
     package: Option<CrateName>,
 
+    /// Debug the test function with this name instead of `main`
+    #[clap(long, conflicts_with = "contract_fn")]
+    test: Option<String>,
+
+    /// Debug a function of a contract package, given as <contract>::<function>
+    #[clap(long)]
+    contract_fn: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -47,23 +62,48 @@ pub(crate) fn run(
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
     )?;
     let target_dir = &workspace.target_directory_path();
-    let (np_language, opcode_support) = backend.get_backend_info()?;
 
-    let Some(package) = workspace.into_iter().find(|p| p.is_binary()) else {
-        println!(
-            "No matching binary packages found in workspace. Only binary packages can be debugged."
-        );
-        return Ok(());
-    };
+    // Record where the values of variables are held so that the debugger can display them.
+    let compile_options = CompileOptions { debug_variables: true, ..args.compile_options };
+
+    let (package, compiled_program, inputs_map) = if let Some(test_name) = &args.test {
+        let (package, compiled_program) =
+            find_test_function(&workspace, test_name, &compile_options)?;
+        // Test functions do not take any inputs.
+        (package, compiled_program, InputMap::new())
+    } else {
+        let (package, compiled_program) = if let Some(contract_fn) = &args.contract_fn {
+            find_contract_function(&workspace, contract_fn, &compile_options)?
+        } else {
+            let Some(package) = workspace.into_iter().find(|p| p.is_binary()) else {
+                println!(
+                    "No matching binary packages found in workspace. Only binary packages can be debugged."
+                );
+                return Ok(());
+            };
+            let (np_language, opcode_support) = backend.get_backend_info()?;
+            let compiled_program = compile_bin_package(
+                &workspace,
+                package,
+                &compile_options,
+                np_language,
+                &|opcode| opcode_support.is_opcode_supported(opcode),
+            )?;
+            (package, compiled_program)
+        };
 
-    let compiled_program =
-        compile_bin_package(&workspace, package, &args.compile_options, np_language, &|opcode| {
-            opcode_support.is_opcode_supported(opcode)
-        })?;
+        // Parse the initial witness values from Prover.toml
+        let (inputs_map, _) = read_inputs_from_file(
+            &package.root_dir,
+            &args.prover_name,
+            Format::Toml,
+            &compiled_program.abi,
+        )?;
+        (package, compiled_program, inputs_map)
+    };
 
     println!("[{}] Starting debugger", package.name);
-    let (return_value, solved_witness) =
-        debug_program_and_decode(compiled_program, package, &args.prover_name)?;
+    let (return_value, solved_witness) = debug_program_and_decode(compiled_program, &inputs_map)?;
 
     if let Some(solved_witness) = solved_witness {
         println!("[{}] Circuit witness successfully solved", package.name);
@@ -84,15 +124,44 @@ pub(crate) fn run(
     Ok(())
 }
 
+pub(crate) fn find_test_function<'a>(
+    workspace: &'a Workspace,
+    test_name: &str,
+    compile_options: &CompileOptions,
+) -> Result<(&'a Package, CompiledProgram), CliError> {
+    for package in workspace {
+        if let Some((_, program)) = compile_test_function(package, test_name, compile_options)? {
+            return Ok((package, program));
+        }
+    }
+    Err(CliError::Generic(format!("No test function matching {test_name} found")))
+}
+
+pub(crate) fn find_contract_function<'a>(
+    workspace: &'a Workspace,
+    contract_fn: &str,
+    compile_options: &CompileOptions,
+) -> Result<(&'a Package, CompiledProgram), CliError> {
+    let Some((contract_name, function_name)) = contract_fn.rsplit_once("::") else {
+        return Err(CliError::Generic(format!(
+            "Invalid contract function {contract_fn}, expected <contract>::<function>"
+        )));
+    };
+    for package in workspace.into_iter().filter(|package| package.is_contract()) {
+        if let Some(program) =
+            compile_contract_function(package, contract_name, function_name, compile_options)?
+        {
+            return Ok((package, program));
+        }
+    }
+    Err(CliError::Generic(format!("No contract function {contract_fn} found in workspace")))
+}
+
 fn debug_program_and_decode(
     program: CompiledProgram,
-    package: &Package,
-    prover_name: &str,
+    inputs_map: &InputMap,
 ) -> Result<(Option<InputValue>, Option<WitnessMap>), CliError> {
-    // Parse the initial witness values from Prover.toml
-    let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = debug_program(&program, &inputs_map)?;
+    let solved_witness = debug_program(&program, inputs_map)?;
     let public_abi = program.abi.public_abi();
 
     match solved_witness {
@@ -113,17 +182,268 @@ pub(crate) fn debug_program(
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
-    let debug_artifact = DebugArtifact {
-        debug_symbols: vec![compiled_program.debug.clone()],
-        file_map: compiled_program.file_map.clone(),
-        warnings: compiled_program.warnings.clone(),
-    };
-
     noir_debugger::debug_circuit(
         &blackbox_solver,
         &compiled_program.circuit,
-        debug_artifact,
+        &compiled_program.functions,
+        program_debug_artifact(compiled_program),
         initial_witness,
+        ForeignCallExecutor::new(true),
     )
     .map_err(CliError::from)
 }
+
+pub(crate) fn program_debug_artifact(compiled_program: &CompiledProgram) -> DebugArtifact {
+    DebugArtifact {
+        debug_symbols: vec![compiled_program.debug.clone()],
+        file_map: compiled_program.file_map.clone(),
+        warnings: compiled_program.warnings.clone(),
+    }
+}
+
+/// Compiles the test function `test_name` of `package` for the debugger, returning
+/// its fully qualified name along with the compiled program.
+///
+/// A test whose name matches `test_name` exactly is preferred, otherwise `test_name` must be
+/// contained in the name of a single test. Returns `None` if no test of the package matches.
+fn compile_test_function(
+    package: &Package,
+    test_name: &str,
+    compile_options: &CompileOptions,
+) -> Result<Option<(String, CompiledProgram)>, CliError> {
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(
+        &mut context,
+        crate_id,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
+
+    let mut test_functions = context
+        .get_all_test_functions_in_crate_matching(&crate_id, FunctionNameMatch::Exact(test_name));
+    if test_functions.is_empty() {
+        test_functions = context.get_all_test_functions_in_crate_matching(
+            &crate_id,
+            FunctionNameMatch::Contains(test_name),
+        );
+    }
+    let (name, test_function) = match test_functions.len() {
+        0 => return Ok(None),
+        1 => test_functions.remove(0),
+        _ => {
+            let names: Vec<_> = test_functions.into_iter().map(|(name, _)| name).collect();
+            return Err(CliError::Generic(format!(
+                "[{}] Multiple tests match {test_name}: {}",
+                package.name,
+                names.join(", ")
+            )));
+        }
+    };
+
+    match compile_no_check(&context, compile_options, test_function.get_id(), None, false) {
+        Ok(program) => Ok(Some((name, program))),
+        Err(err) => {
+            noirc_errors::reporter::report_all(
+                context.file_manager.as_file_map(),
+                &[err.into()],
+                compile_options.deny_warnings,
+                compile_options.silence_warnings,
+            );
+            Err(CliError::Generic(format!("[{}] Failed to compile {name}", package.name)))
+        }
+    }
+}
+
+/// Compiles the entry point `function_name` of the contract `contract_name` in `package`
+/// for the debugger.
+///
+/// Returns `None` if the package has no such contract function.
+fn compile_contract_function(
+    package: &Package,
+    contract_name: &str,
+    function_name: &str,
+    compile_options: &CompileOptions,
+) -> Result<Option<CompiledProgram>, CliError> {
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(
+        &mut context,
+        crate_id,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
+
+    let function_id = context
+        .get_all_contracts(&crate_id)
+        .into_iter()
+        .filter(|contract| contract.name == contract_name)
+        .flat_map(|contract| contract.functions)
+        .find(|function| {
+            function.is_entry_point && context.function_name(&function.function_id) == function_name
+        })
+        .map(|function| function.function_id);
+    let Some(function_id) = function_id else { return Ok(None) };
+
+    match compile_no_check(&context, compile_options, function_id, None, true) {
+        Ok(program) => Ok(Some(program)),
+        Err(err) => {
+            noirc_errors::reporter::report_all(
+                context.file_manager.as_file_map(),
+                &[err.into()],
+                compile_options.deny_warnings,
+                compile_options.silence_warnings,
+            );
+            Err(CliError::Generic(format!(
+                "[{}] Failed to compile {contract_name}::{function_name}",
+                package.name
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use acvm::acir::native_types::WitnessMap;
+    use nargo::ops::{execute_circuit, ForeignCallExecutor};
+    use nargo::package::{Package, PackageType};
+    use nargo::workspace::Workspace;
+    use noirc_driver::CompileOptions;
+    use noirc_frontend::graph::CrateName;
+    use tempfile::TempDir;
+
+    use super::{find_contract_function, find_test_function};
+    use crate::errors::CliError;
+
+    const TESTS_SOURCE: &str = "use dep::std::test::OracleMock;
+
+#[oracle(get_value)]
+unconstrained fn get_value_oracle() -> Field {}
+
+unconstrained fn get_value() -> Field {
+    get_value_oracle()
+}
+
+#[test]
+fn test_add() {
+    assert(1 + 1 == 2);
+}
+
+#[test]
+fn test_add_twice() {
+    assert(1 + 1 + 1 == 3);
+}
+
+#[test]
+fn test_mocked_value() {
+    let _mock = OracleMock::mock(\"get_value\").returns(5);
+    assert(get_value() == 5);
+}
+";
+
+    const CONTRACT_SOURCE: &str = "contract Counter {
+    fn increment(count: Field) -> pub Field {
+        count + 1
+    }
+}
+";
+
+    /// Writes `source` as the entry point of a package named `name` within `root`.
+    fn write_package(root: &Path, name: &str, package_type: PackageType, source: &str) -> Package {
+        let root_dir = root.join(name);
+        let entry_name = if package_type == PackageType::Library { "lib.nr" } else { "main.nr" };
+        let entry_path = root_dir.join("src").join(entry_name);
+        std::fs::create_dir_all(entry_path.parent().unwrap()).unwrap();
+        std::fs::write(&entry_path, source).unwrap();
+        Package {
+            compiler_required_version: None,
+            root_dir,
+            package_type,
+            entry_path,
+            name: name.parse().unwrap(),
+            dependencies: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a workspace holding a library of tests and a contract.
+    fn test_workspace() -> (TempDir, Workspace) {
+        let root = tempfile::tempdir().unwrap();
+        let tests = write_package(root.path(), "tests", PackageType::Library, TESTS_SOURCE);
+        let contract =
+            write_package(root.path(), "counter", PackageType::Contract, CONTRACT_SOURCE);
+        let workspace = Workspace {
+            root_dir: root.path().to_path_buf(),
+            members: vec![tests, contract],
+            selected_package_index: None,
+        };
+        (root, workspace)
+    }
+
+    fn error_message<T>(result: Result<T, CliError>) -> String {
+        match result {
+            Ok(_) => panic!("expected the selection to fail"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_functions_are_selected_by_name() {
+        let (_root, workspace) = test_workspace();
+        let options = CompileOptions::default();
+
+        // An exact match is preferred over the other tests containing the name.
+        let (package, _) = find_test_function(&workspace, "test_add", &options).unwrap();
+        assert_eq!(package.name, "tests".parse::<CrateName>().unwrap());
+        assert!(find_test_function(&workspace, "twice", &options).is_ok());
+
+        let ambiguous = error_message(find_test_function(&workspace, "add", &options));
+        assert!(ambiguous.contains("Multiple tests match add"), "{ambiguous}");
+        assert!(ambiguous.contains("test_add_twice"), "{ambiguous}");
+
+        let missing = error_message(find_test_function(&workspace, "test_sub", &options));
+        assert_eq!(missing, "No test function matching test_sub found");
+    }
+
+    #[test]
+    fn selected_test_functions_resolve_their_mocks() {
+        let (_root, workspace) = test_workspace();
+        let options = CompileOptions { debug_variables: true, ..CompileOptions::default() };
+        let (_, program) = find_test_function(&workspace, "test_mocked_value", &options).unwrap();
+        assert!(!program.debug.variables.is_empty());
+
+        #[allow(deprecated)]
+        let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
+        let result = execute_circuit(
+            &blackbox_solver,
+            &program.circuit,
+            &program.functions,
+            WitnessMap::new(),
+            &mut ForeignCallExecutor::default(),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn contract_functions_are_selected_by_contract_and_name() {
+        let (_root, workspace) = test_workspace();
+        let options = CompileOptions::default();
+
+        let (package, program) =
+            find_contract_function(&workspace, "Counter::increment", &options).unwrap();
+        assert_eq!(package.name, "counter".parse::<CrateName>().unwrap());
+        assert_eq!(program.abi.parameters.len(), 1);
+
+        let invalid = error_message(find_contract_function(&workspace, "increment", &options));
+        assert_eq!(invalid, "Invalid contract function increment, expected <contract>::<function>");
+
+        let missing =
+            error_message(find_contract_function(&workspace, "Counter::decrement", &options));
+        assert_eq!(missing, "No contract function Counter::decrement found in workspace");
+        let missing =
+            error_message(find_contract_function(&workspace, "Tally::increment", &options));
+        assert_eq!(missing, "No contract function Tally::increment found in workspace");
+    }
+}
@@ -1,10 +1,15 @@
+use std::io::Write;
+use std::path::PathBuf;
+
 use acvm::acir::native_types::WitnessMap;
 use clap::Args;
 
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::errors::try_to_diagnose_runtime_error;
+use nargo::ops::ForeignCallExecutor;
 use nargo::package::Package;
+use nargo::NargoError;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
@@ -12,7 +17,12 @@ use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING
 use noirc_frontend::graph::CrateName;
 
 use super::compile_cmd::compile_bin_package;
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
+use super::fs::{
+    inputs::read_inputs_from_file,
+    trace::create_trace_file,
+    transcript::{read_transcript_from_file, save_transcript_to_file},
+    witness::save_witness_to_dir,
+};
 use super::NargoConfig;
 use crate::backends::Backend;
 use crate::errors::CliError;
@@ -35,6 +45,24 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Record the inputs and results of every oracle call to a JSON transcript at this path
+    #[clap(long, conflicts_with = "replay_oracles")]
+    record_oracles: Option<PathBuf>,
+
+    /// Resolve oracle calls using a transcript saved by `--record-oracles`,
+    /// failing if execution makes different oracle calls than those recorded
+    #[clap(long, conflicts_with = "record_oracles")]
+    replay_oracles: Option<PathBuf>,
+
+    /// JSON-RPC server used to resolve oracle calls which are not built in or mocked
+    #[clap(long, conflicts_with = "replay_oracles")]
+    oracle_resolver: Option<String>,
+
+    /// Write a trace of every executed ACIR opcode and Brillig instruction to this path,
+    /// as JSON with one record per line
+    #[clap(long)]
+    trace: Option<PathBuf>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -55,6 +83,14 @@ pub(crate) fn run(
     )?;
     let target_dir = &workspace.target_directory_path();
 
+    let uses_transcript = args.record_oracles.is_some() || args.replay_oracles.is_some();
+    if (uses_transcript || args.trace.is_some()) && workspace.into_iter().count() > 1 {
+        return Err(CliError::Generic(
+            "Oracle transcripts and execution traces can only be used when executing a single package"
+                .to_string(),
+        ));
+    }
+
     let (np_language, opcode_support) = backend.get_backend_info()?;
     for package in &workspace {
         let compiled_program = compile_bin_package(
@@ -65,8 +101,27 @@ pub(crate) fn run(
             &|opcode| opcode_support.is_opcode_supported(opcode),
         )?;
 
-        let (return_value, solved_witness) =
-            execute_program_and_decode(compiled_program, package, &args.prover_name)?;
+        let mut foreign_call_executor = ForeignCallExecutor::new(true);
+        if let Some(resolver_url) = &args.oracle_resolver {
+            foreign_call_executor =
+                foreign_call_executor.with_oracle_resolver(resolver_url.clone());
+        }
+        if let Some(transcript_path) = &args.replay_oracles {
+            let transcript = read_transcript_from_file(transcript_path)?;
+            foreign_call_executor = foreign_call_executor.with_transcript_replay(transcript);
+        } else if args.record_oracles.is_some() {
+            foreign_call_executor = foreign_call_executor.with_transcript_recording();
+        }
+
+        let mut trace = args.trace.as_deref().map(create_trace_file).transpose()?;
+        let (return_value, solved_witness) = execute_program_and_decode(
+            compiled_program,
+            package,
+            &args.prover_name,
+            &mut foreign_call_executor,
+            trace.as_mut().map(|trace| trace as &mut dyn Write),
+        )?;
+        foreign_call_executor.ensure_replay_completed().map_err(NargoError::from)?;
 
         println!("[{}] Circuit witness successfully solved", package.name);
         if let Some(return_value) = return_value {
@@ -77,6 +132,19 @@ pub(crate) fn run(
 
             println!("[{}] Witness saved to {}", package.name, witness_path.display());
         }
+        if let Some(transcript_path) = &args.record_oracles {
+            let transcript = foreign_call_executor
+                .into_recorded_transcript()
+                .expect("Executor was created to record oracle calls");
+            save_transcript_to_file(&transcript, transcript_path);
+
+            println!("[{}] Oracle transcript saved to {}", package.name, transcript_path.display());
+        }
+        if let (Some(mut trace), Some(trace_path)) = (trace, &args.trace) {
+            trace.flush().map_err(NargoError::from)?;
+
+            println!("[{}] Execution trace saved to {}", package.name, trace_path.display());
+        }
     }
     Ok(())
 }
@@ -85,11 +153,13 @@ fn execute_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
+    foreign_call_executor: &mut ForeignCallExecutor,
+    trace: Option<&mut dyn Write>,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = execute_program(&program, &inputs_map)?;
+    let solved_witness = execute_program(&program, &inputs_map, foreign_call_executor, trace)?;
     let public_abi = program.abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
 
@@ -99,26 +169,41 @@ fn execute_program_and_decode(
 pub(crate) fn execute_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
+    foreign_call_executor: &mut ForeignCallExecutor,
+    trace: Option<&mut dyn Write>,
 ) -> Result<WitnessMap, CliError> {
     #[allow(deprecated)]
     let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
-
-    let solved_witness_err = nargo::ops::execute_circuit(
-        &blackbox_solver,
-        &compiled_program.circuit,
-        initial_witness,
-        true,
-    );
+    let debug_artifact = || DebugArtifact {
+        debug_symbols: vec![compiled_program.debug.clone()],
+        file_map: compiled_program.file_map.clone(),
+        warnings: compiled_program.warnings.clone(),
+    };
+
+    let solved_witness_err = match trace {
+        Some(mut trace) => nargo::ops::execute_circuit_with_trace(
+            &blackbox_solver,
+            &compiled_program.circuit,
+            &compiled_program.functions,
+            initial_witness,
+            foreign_call_executor,
+            &debug_artifact(),
+            &mut trace,
+        ),
+        None => nargo::ops::execute_circuit(
+            &blackbox_solver,
+            &compiled_program.circuit,
+            &compiled_program.functions,
+            initial_witness,
+            foreign_call_executor,
+        ),
+    };
     match solved_witness_err {
         Ok(solved_witness) => Ok(solved_witness),
         Err(err) => {
-            let debug_artifact = DebugArtifact {
-                debug_symbols: vec![compiled_program.debug.clone()],
-                file_map: compiled_program.file_map.clone(),
-                warnings: compiled_program.warnings.clone(),
-            };
+            let debug_artifact = debug_artifact();
 
             if let Some(diagnostic) = try_to_diagnose_runtime_error(&err, &compiled_program.debug) {
                 diagnostic.report(&debug_artifact, false);
@@ -0,0 +1,43 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use nargo::ops::BenchReport;
+use noirc_frontend::graph::CrateName;
+
+use super::{create_named_dir, write_to_file};
+
+/// The benchmark reports of a single package, keyed by the name of the `#[bench]` function.
+pub(crate) type BenchReports = BTreeMap<String, BenchReport>;
+
+pub(crate) fn save_bench_reports_to_dir<P: AsRef<Path>>(
+    reports: &BenchReports,
+    crate_name: &CrateName,
+    bench_dir: P,
+) -> PathBuf {
+    create_named_dir(bench_dir.as_ref(), "bench");
+    let bench_path = bench_file_path(crate_name, bench_dir);
+
+    write_to_file(&serde_json::to_vec(reports).unwrap(), &bench_path);
+
+    bench_path
+}
+
+/// Reads the benchmark reports saved by a previous run of `nargo bench`.
+///
+/// Returns an empty set of reports if there is no previous run or if it cannot be read.
+pub(crate) fn read_bench_reports_from_dir<P: AsRef<Path>>(
+    crate_name: &CrateName,
+    bench_dir: P,
+) -> BenchReports {
+    std::fs::read(bench_file_path(crate_name, bench_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn bench_file_path<P: AsRef<Path>>(crate_name: &CrateName, bench_dir: P) -> PathBuf {
+    let bench_name: String = crate_name.into();
+    bench_dir.as_ref().join(bench_name).with_extension("json")
+}
@@ -6,9 +6,12 @@ use std::{
 
 use crate::errors::FilesystemError;
 
+pub(super) mod bench;
 pub(super) mod inputs;
 pub(super) mod program;
 pub(super) mod proof;
+pub(super) mod trace;
+pub(super) mod transcript;
 pub(super) mod witness;
 
 pub(super) fn create_named_dir(named_dir: &Path, name: &str) -> PathBuf {
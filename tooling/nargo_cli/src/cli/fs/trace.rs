@@ -0,0 +1,12 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::errors::FilesystemError;
+
+pub(crate) fn create_trace_file(trace_path: &Path) -> Result<BufWriter<File>, FilesystemError> {
+    let file = File::create(trace_path).map_err(|err| {
+        FilesystemError::TraceFileNotCreated(trace_path.to_path_buf(), err.to_string())
+    })?;
+    Ok(BufWriter::new(file))
+}
@@ -0,0 +1,21 @@
+use std::path::Path;
+
+use nargo::ops::ForeignCallTranscript;
+
+use super::write_to_file;
+use crate::errors::FilesystemError;
+
+pub(crate) fn save_transcript_to_file(transcript: &ForeignCallTranscript, transcript_path: &Path) {
+    write_to_file(&serde_json::to_vec_pretty(transcript).unwrap(), transcript_path);
+}
+
+pub(crate) fn read_transcript_from_file(
+    transcript_path: &Path,
+) -> Result<ForeignCallTranscript, FilesystemError> {
+    let contents = std::fs::read(transcript_path)
+        .map_err(|_| FilesystemError::PathNotValid(transcript_path.to_path_buf()))?;
+
+    serde_json::from_slice(&contents).map_err(|err| {
+        FilesystemError::InvalidOracleTranscript(transcript_path.to_path_buf(), err.to_string())
+    })
+}
@@ -1,5 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
+use acvm::acir::circuit::{Circuit, Opcode};
 use acvm::Language;
 use backend_interface::BackendError;
 use clap::Args;
@@ -13,10 +15,10 @@ use noirc_errors::{debug_info::OpCodesCount, Location};
 use noirc_frontend::graph::CrateName;
 use prettytable::{row, table, Row};
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::backends::Backend;
-use crate::errors::CliError;
+use crate::errors::{CliError, FilesystemError};
 
 use super::{compile_cmd::compile_workspace, NargoConfig};
 
@@ -24,7 +26,12 @@ use super::{compile_cmd::compile_workspace, NargoConfig};
 ///
 /// Current information provided:
 /// 1. The number of ACIR opcodes
-/// 2. Counts the final number gates in the circuit used by a backend
+/// 2. The number of Brillig opcodes
+/// 3. Counts the final number gates in the circuit used by a backend
+///
+/// The circuits of a program's `#[fold]` functions are included in the counts of the program.
+///
+/// These counts can be compared against a baseline report produced by `nargo info --json`.
 #[derive(Debug, Clone, Args)]
 pub(crate) struct InfoCommand {
     /// The name of the package to detail
@@ -42,6 +49,15 @@ pub(crate) struct InfoCommand {
     #[clap(long, hide = true)]
     profile_info: bool,
 
+    /// Compare against a baseline report produced by `nargo info --json` and print the deltas
+    #[clap(long, value_name = "BASELINE")]
+    compare: Option<PathBuf>,
+
+    /// Fail if the backend circuit size of any function grows by more than this many gates
+    /// compared to the baseline
+    #[clap(long, requires = "compare")]
+    threshold: Option<u32>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -51,6 +67,8 @@ pub(crate) fn run(
     args: InfoCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    let baseline = args.compare.as_deref().map(read_baseline_report).transpose()?;
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
@@ -108,10 +126,13 @@ pub(crate) fn run(
         .collect::<Result<_, _>>()?;
 
     let info_report = InfoReport { programs: program_info, contracts: contract_info };
+    let deltas = baseline.map(|baseline| compare_reports(&baseline, &info_report));
 
     if args.json {
         // Expose machine-readable JSON data.
         println!("{}", serde_json::to_string(&info_report).unwrap());
+    } else if let Some(deltas) = &deltas {
+        print_deltas(deltas);
     } else {
         // Otherwise print human-readable table.
         if !info_report.programs.is_empty() {
@@ -141,6 +162,10 @@ pub(crate) fn run(
         }
     }
 
+    if let (Some(deltas), Some(threshold)) = (&deltas, args.threshold) {
+        check_threshold(deltas, threshold)?;
+    }
+
     Ok(())
 }
 
@@ -205,6 +230,7 @@ struct ProgramInfo {
     #[serde(skip)]
     language: Language,
     acir_opcodes: usize,
+    brillig_opcodes: usize,
     circuit_size: u32,
 }
 
@@ -231,6 +257,7 @@ struct ContractInfo {
 struct FunctionInfo {
     name: String,
     acir_opcodes: usize,
+    brillig_opcodes: usize,
     circuit_size: u32,
 }
 
@@ -254,12 +281,21 @@ fn count_opcodes_and_gates_in_program(
     package: &Package,
     language: Language,
 ) -> Result<ProgramInfo, CliError> {
-    Ok(ProgramInfo {
+    // The circuits of `#[fold]` functions are counted once each, however many times they are called.
+    let circuits = std::iter::once(&compiled_program.circuit).chain(&compiled_program.functions);
+    let mut program_info = ProgramInfo {
         name: package.name.to_string(),
         language,
-        acir_opcodes: compiled_program.circuit.opcodes.len(),
-        circuit_size: backend.get_exact_circuit_size(&compiled_program.circuit)?,
-    })
+        acir_opcodes: 0,
+        brillig_opcodes: 0,
+        circuit_size: 0,
+    };
+    for circuit in circuits {
+        program_info.acir_opcodes += circuit.opcodes.len();
+        program_info.brillig_opcodes += count_brillig_opcodes(circuit);
+        program_info.circuit_size += backend.get_exact_circuit_size(circuit)?;
+    }
+    Ok(program_info)
 }
 
 fn count_opcodes_and_gates_in_contract(
@@ -274,6 +310,7 @@ fn count_opcodes_and_gates_in_contract(
             Ok(FunctionInfo {
                 name: function.name,
                 acir_opcodes: function.bytecode.opcodes.len(),
+                brillig_opcodes: count_brillig_opcodes(&function.bytecode),
                 circuit_size: backend.get_exact_circuit_size(&function.bytecode)?,
             })
         })
@@ -281,3 +318,250 @@ fn count_opcodes_and_gates_in_contract(
 
     Ok(ContractInfo { name: contract.name, language, functions })
 }
+
+fn count_brillig_opcodes(circuit: &Circuit) -> usize {
+    circuit
+        .opcodes
+        .iter()
+        .map(|opcode| match opcode {
+            Opcode::Brillig(brillig) => brillig.bytecode.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// A report previously emitted by `nargo info --json`.
+///
+/// Brillig opcode counts are optional so that reports from older versions of nargo can be used.
+#[derive(Debug, Deserialize)]
+struct BaselineReport {
+    #[serde(default)]
+    programs: Vec<BaselineProgram>,
+    #[serde(default)]
+    contracts: Vec<BaselineContract>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineProgram {
+    name: String,
+    #[serde(flatten)]
+    counts: CircuitCounts,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineContract {
+    name: String,
+    functions: Vec<BaselineFunction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineFunction {
+    name: String,
+    #[serde(flatten)]
+    counts: CircuitCounts,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+struct CircuitCounts {
+    acir_opcodes: usize,
+    #[serde(default)]
+    brillig_opcodes: usize,
+    circuit_size: u32,
+}
+
+/// The change in size of a single circuit relative to the baseline.
+///
+/// `baseline` is `None` if the circuit does not appear in the baseline report.
+#[derive(Debug, PartialEq, Eq)]
+struct CircuitDelta {
+    package: String,
+    function: String,
+    current: CircuitCounts,
+    baseline: Option<CircuitCounts>,
+}
+
+impl CircuitDelta {
+    fn circuit_size_increase(&self) -> i64 {
+        let baseline_size = self.baseline.map_or(0, |baseline| baseline.circuit_size);
+        i64::from(self.current.circuit_size) - i64::from(baseline_size)
+    }
+}
+
+impl From<&CircuitDelta> for Row {
+    fn from(delta: &CircuitDelta) -> Self {
+        let format_count = |current: usize, baseline: Option<usize>| match baseline {
+            Some(baseline) => format!("{current} ({:+})", current as i64 - baseline as i64),
+            None => format!("{current} (new)"),
+        };
+        let baseline = delta.baseline;
+        row![
+            Fm->format!("{}", delta.package),
+            Fc->format!("{}", delta.function),
+            Fc->format_count(delta.current.acir_opcodes, baseline.map(|b| b.acir_opcodes)),
+            Fc->format_count(delta.current.brillig_opcodes, baseline.map(|b| b.brillig_opcodes)),
+            Fc->format_count(
+                delta.current.circuit_size as usize,
+                baseline.map(|b| b.circuit_size as usize)
+            ),
+        ]
+    }
+}
+
+fn read_baseline_report(path: &Path) -> Result<BaselineReport, FilesystemError> {
+    let invalid_baseline =
+        |err: String| FilesystemError::InvalidBaselineReport(path.to_path_buf(), err);
+
+    let contents =
+        std::fs::read_to_string(path).map_err(|err| invalid_baseline(err.to_string()))?;
+    serde_json::from_str(&contents).map_err(|err| invalid_baseline(err.to_string()))
+}
+
+fn compare_reports(baseline: &BaselineReport, report: &InfoReport) -> Vec<CircuitDelta> {
+    // Binary packages only expose a single circuit so we treat them as a package containing `main`.
+    let mut baseline_counts: BTreeMap<(&str, &str), CircuitCounts> = BTreeMap::new();
+    for program in &baseline.programs {
+        baseline_counts.insert((program.name.as_str(), "main"), program.counts);
+    }
+    for contract in &baseline.contracts {
+        for function in &contract.functions {
+            baseline_counts
+                .insert((contract.name.as_str(), function.name.as_str()), function.counts);
+        }
+    }
+
+    let programs = report.programs.iter().map(|program| {
+        let counts = CircuitCounts {
+            acir_opcodes: program.acir_opcodes,
+            brillig_opcodes: program.brillig_opcodes,
+            circuit_size: program.circuit_size,
+        };
+        (program.name.as_str(), "main", counts)
+    });
+    let contract_functions = report.contracts.iter().flat_map(|contract| {
+        contract.functions.iter().map(move |function| {
+            let counts = CircuitCounts {
+                acir_opcodes: function.acir_opcodes,
+                brillig_opcodes: function.brillig_opcodes,
+                circuit_size: function.circuit_size,
+            };
+            (contract.name.as_str(), function.name.as_str(), counts)
+        })
+    });
+
+    programs
+        .chain(contract_functions)
+        .map(|(package, function, current)| CircuitDelta {
+            package: package.to_string(),
+            function: function.to_string(),
+            current,
+            baseline: baseline_counts.get(&(package, function)).copied(),
+        })
+        .collect()
+}
+
+fn print_deltas(deltas: &[CircuitDelta]) {
+    let mut delta_table = table!([
+        Fm->"Package",
+        Fm->"Function",
+        Fm->"ACIR Opcodes",
+        Fm->"Brillig Opcodes",
+        Fm->"Backend Circuit Size"
+    ]);
+    for delta in deltas {
+        delta_table.add_row(delta.into());
+    }
+    delta_table.printstd();
+}
+
+fn check_threshold(deltas: &[CircuitDelta], threshold: u32) -> Result<(), CliError> {
+    let regressions: Vec<String> = deltas
+        .iter()
+        .filter(|delta| delta.circuit_size_increase() > i64::from(threshold))
+        .map(|delta| {
+            format!(
+                "{}::{} (+{} gates)",
+                delta.package,
+                delta.function,
+                delta.circuit_size_increase()
+            )
+        })
+        .collect();
+
+    if regressions.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Generic(format!(
+            "Backend circuit size increased by more than {threshold} gates for: {}",
+            regressions.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::Language;
+
+    use super::{
+        check_threshold, compare_reports, BaselineReport, CircuitCounts, ContractInfo,
+        FunctionInfo, InfoReport, ProgramInfo,
+    };
+
+    #[test]
+    fn compares_against_baseline_report() {
+        let baseline: BaselineReport = serde_json::from_str(
+            r#"{
+                "programs": [{ "name": "foo", "acir_opcodes": 10, "circuit_size": 100 }],
+                "contracts": [{
+                    "name": "Bar",
+                    "functions": [
+                        { "name": "baz", "acir_opcodes": 5, "brillig_opcodes": 3, "circuit_size": 50 }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let language = Language::PLONKCSat { width: 3 };
+        let report = InfoReport {
+            programs: vec![ProgramInfo {
+                name: "foo".to_string(),
+                language,
+                acir_opcodes: 12,
+                brillig_opcodes: 0,
+                circuit_size: 1200,
+            }],
+            contracts: vec![ContractInfo {
+                name: "Bar".to_string(),
+                language,
+                functions: vec![
+                    FunctionInfo {
+                        name: "baz".to_string(),
+                        acir_opcodes: 5,
+                        brillig_opcodes: 3,
+                        circuit_size: 40,
+                    },
+                    FunctionInfo {
+                        name: "qux".to_string(),
+                        acir_opcodes: 1,
+                        brillig_opcodes: 0,
+                        circuit_size: 10,
+                    },
+                ],
+            }],
+        };
+
+        let deltas = compare_reports(&baseline, &report);
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(
+            deltas[0].baseline,
+            Some(CircuitCounts { acir_opcodes: 10, brillig_opcodes: 0, circuit_size: 100 })
+        );
+        assert_eq!(deltas[0].circuit_size_increase(), 1100);
+        assert_eq!(deltas[1].circuit_size_increase(), -10);
+        assert_eq!(deltas[2].baseline, None);
+        assert_eq!(deltas[2].circuit_size_increase(), 10);
+
+        assert!(check_threshold(&deltas, 1100).is_ok());
+        assert!(check_threshold(&deltas, 1000).is_err());
+    }
+}
@@ -11,9 +11,11 @@ use crate::backends::get_active_backend;
 mod fs;
 
 mod backend_cmd;
+mod bench_cmd;
 mod check_cmd;
 mod codegen_verifier_cmd;
 mod compile_cmd;
+mod dap_cmd;
 mod debug_cmd;
 mod execute_cmd;
 mod fmt_cmd;
@@ -70,9 +72,12 @@ enum NargoCommand {
     Execute(execute_cmd::ExecuteCommand),
     #[command(hide = true)] // Hidden while the feature is being built out
     Debug(debug_cmd::DebugCommand),
+    #[command(hide = true)] // Hidden while the feature is being built out
+    Dap(dap_cmd::DapCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
+    Bench(bench_cmd::BenchCommand),
     Info(info_cmd::InfoCommand),
     Lsp(lsp_cmd::LspCommand),
 }
@@ -91,6 +96,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::New(_)
             | NargoCommand::Init(_)
             | NargoCommand::Lsp(_)
+            | NargoCommand::Dap(_)
             | NargoCommand::Backend(_)
     ) {
         config.program_dir = find_package_root(&config.program_dir)?;
@@ -105,10 +111,12 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::Check(args) => check_cmd::run(&backend, args, config),
         NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
         NargoCommand::Debug(args) => debug_cmd::run(&backend, args, config),
+        NargoCommand::Dap(args) => dap_cmd::run(&backend, args, config),
         NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
         NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
         NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
         NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
+        NargoCommand::Bench(args) => bench_cmd::run(&backend, args, config),
         NargoCommand::Info(args) => info_cmd::run(&backend, args, config),
         NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
         NargoCommand::Backend(args) => backend_cmd::run(args),
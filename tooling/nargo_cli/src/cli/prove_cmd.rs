@@ -1,5 +1,6 @@
 use clap::Args;
 use nargo::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
+use nargo::ops::ForeignCallExecutor;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
@@ -47,6 +48,10 @@ pub(crate) fn run(
     args: ProveCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    if args.compile_options.force_brillig {
+        return Err(CliError::ForceBrilligNotProvable);
+    }
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
@@ -90,11 +95,16 @@ pub(crate) fn prove_package(
     verifier_name: &str,
     check_proof: bool,
 ) -> Result<(), CliError> {
+    if !compiled_program.functions.is_empty() {
+        return Err(CliError::FoldedFunctionsNotProvable(package.name.clone()));
+    }
+
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)?;
 
-    let solved_witness = execute_program(&compiled_program, &inputs_map)?;
+    let solved_witness =
+        execute_program(&compiled_program, &inputs_map, &mut ForeignCallExecutor::new(true), None)?;
 
     // Write public inputs into Verifier.toml
     let public_abi = compiled_program.abi.public_abi();
@@ -3,18 +3,28 @@ use std::io::Write;
 use acvm::BlackBoxFunctionSolver;
 use clap::Args;
 use nargo::{
-    ops::{run_test, TestStatus},
+    constants::PROVER_INPUT_FILE,
+    ops::{run_differential, run_test, DifferentialStatus, ForeignCallExecutor, TestStatus},
     package::Package,
     prepare_package,
 };
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_driver::{CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
-use noirc_frontend::{graph::CrateName, hir::FunctionNameMatch};
+use noirc_abi::{input_parser::Format, InputMap};
+use noirc_driver::{compile_no_check, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::{
+    graph::CrateName,
+    hir::{Context, FunctionNameMatch},
+    node_interner::FuncId,
+};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
+use crate::{
+    backends::Backend,
+    cli::check_cmd::check_crate_and_report_errors,
+    errors::{CliError, FilesystemError},
+};
 
-use super::NargoConfig;
+use super::{fs::inputs::read_inputs_from_file, NargoConfig};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -38,6 +48,15 @@ pub(crate) struct TestCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Compile each test (and `main` using the inputs in Prover.toml) both normally and with every
+    /// function forced to be unconstrained, and check that both executions agree
+    #[clap(long)]
+    differential: bool,
+
+    /// JSON-RPC server used to resolve oracle calls which are not built in or mocked
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -73,7 +92,18 @@ pub(crate) fn run(
     for package in &workspace {
         // By unwrapping here with `?`, we stop the test runner upon a package failing
         // TODO: We should run the whole suite even if there are failures in a package
-        run_tests(&blackbox_solver, package, pattern, args.show_output, &args.compile_options)?;
+        if args.differential {
+            run_differential_tests(&blackbox_solver, package, pattern, &args.compile_options)?;
+        } else {
+            run_tests(
+                &blackbox_solver,
+                package,
+                pattern,
+                args.show_output,
+                args.oracle_resolver.as_deref(),
+                &args.compile_options,
+            )?;
+        }
     }
 
     Ok(())
@@ -84,6 +114,7 @@ fn run_tests<S: BlackBoxFunctionSolver>(
     package: &Package,
     test_name: FunctionNameMatch,
     show_output: bool,
+    foreign_call_resolver_url: Option<&str>,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError> {
     let (mut context, crate_id) =
@@ -108,7 +139,19 @@ fn run_tests<S: BlackBoxFunctionSolver>(
             .expect("Failed to write to stdout");
         writer.flush().expect("Failed to flush writer");
 
-        match run_test(blackbox_solver, &context, test_function, show_output, compile_options) {
+        let mut foreign_call_executor = ForeignCallExecutor::new(show_output);
+        if let Some(resolver_url) = foreign_call_resolver_url {
+            foreign_call_executor =
+                foreign_call_executor.with_oracle_resolver(resolver_url.to_string());
+        }
+
+        match run_test(
+            blackbox_solver,
+            &context,
+            test_function,
+            &mut foreign_call_executor,
+            compile_options,
+        ) {
             TestStatus::Pass { .. } => {
                 writer
                     .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
@@ -158,3 +201,119 @@ fn run_tests<S: BlackBoxFunctionSolver>(
     writer.reset().expect("Failed to reset writer");
     Ok(())
 }
+
+fn run_differential_tests<S: BlackBoxFunctionSolver>(
+    blackbox_solver: &S,
+    package: &Package,
+    test_name: FunctionNameMatch,
+    compile_options: &CompileOptions,
+) -> Result<(), CliError> {
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(
+        &mut context,
+        crate_id,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
+
+    let mut functions: Vec<(String, FuncId, InputMap)> = context
+        .get_all_test_functions_in_crate_matching(&crate_id, test_name)
+        .into_iter()
+        .map(|(name, test_function)| (name, test_function.get_id(), InputMap::new()))
+        .collect();
+
+    if package.is_binary() {
+        if let Some(main) = context.get_main_function(&crate_id) {
+            if let Some(inputs) = read_main_inputs(&context, package, main, compile_options)? {
+                functions.push(("main".to_string(), main, inputs));
+            }
+        }
+    }
+
+    println!("[{}] Running {} differential tests", package.name, functions.len());
+    let mut failing = 0;
+
+    let writer = StandardStream::stderr(ColorChoice::Always);
+    let mut writer = writer.lock();
+
+    for (name, function, inputs) in functions {
+        write!(writer, "[{}] Comparing ACIR and Brillig for {name}... ", package.name)
+            .expect("Failed to write to stdout");
+        writer.flush().expect("Failed to flush writer");
+
+        match run_differential(blackbox_solver, &context, function, &inputs, compile_options) {
+            DifferentialStatus::Match => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
+                    .expect("Failed to set color");
+                writeln!(writer, "ok").expect("Failed to write to stdout");
+            }
+            DifferentialStatus::Mismatch { acir, brillig } => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
+                    .expect("Failed to set color");
+                writeln!(writer, "mismatch").expect("Failed to write to stdout");
+                writer.reset().expect("Failed to reset writer");
+                writeln!(writer, "    ACIR {acir}\n    Brillig {brillig}")
+                    .expect("Failed to write to stdout");
+                failing += 1;
+            }
+            DifferentialStatus::CompileError(err) => {
+                noirc_errors::reporter::report_all(
+                    context.file_manager.as_file_map(),
+                    &[err],
+                    compile_options.deny_warnings,
+                    compile_options.silence_warnings,
+                );
+                failing += 1;
+            }
+        }
+        writer.reset().expect("Failed to reset writer");
+    }
+
+    if failing == 0 {
+        write!(writer, "[{}] ", package.name).expect("Failed to write to stdout");
+        writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).expect("Failed to set color");
+        writeln!(writer, "ACIR and Brillig agree on all tests").expect("Failed to write to stdout");
+    } else {
+        let plural = if failing == 1 { "" } else { "s" };
+        return Err(CliError::Generic(format!(
+            "[{}] {failing} differential test{plural} failed",
+            package.name
+        )));
+    }
+
+    writer.reset().expect("Failed to reset writer");
+    Ok(())
+}
+
+/// Reads the inputs to `main` from the package's Prover.toml.
+///
+/// Returns `None` if the package has no Prover.toml, in which case `main` cannot be executed.
+fn read_main_inputs(
+    context: &Context,
+    package: &Package,
+    main: FuncId,
+    compile_options: &CompileOptions,
+) -> Result<Option<InputMap>, CliError> {
+    // We need the program's ABI in order to parse its inputs.
+    let program = match compile_no_check(context, compile_options, main, None, false) {
+        Ok(program) => program,
+        Err(err) => {
+            noirc_errors::reporter::report_all(
+                context.file_manager.as_file_map(),
+                &[err.into()],
+                compile_options.deny_warnings,
+                compile_options.silence_warnings,
+            );
+            return Err(CliError::Generic(format!("[{}] Failed to compile main", package.name)));
+        }
+    };
+
+    match read_inputs_from_file(&package.root_dir, PROVER_INPUT_FILE, Format::Toml, &program.abi) {
+        Ok((inputs, _)) => Ok(Some(inputs)),
+        Err(FilesystemError::MissingTomlFile(..)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -38,6 +38,10 @@ pub(crate) fn run(
     args: VerifyCommand,
     config: NargoConfig,
 ) -> Result<(), CliError> {
+    if args.compile_options.force_brillig {
+        return Err(CliError::ForceBrilligNotProvable);
+    }
+
     let toml_path = get_package_manifest(&config.program_dir)?;
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
@@ -71,6 +75,10 @@ fn verify_package(
     compiled_program: CompiledProgram,
     verifier_name: &str,
 ) -> Result<(), CliError> {
+    if !compiled_program.functions.is_empty() {
+        return Err(CliError::FoldedFunctionsNotProvable(package.name.clone()));
+    }
+
     // Load public inputs (if any) from `verifier_name`.
     let public_abi = compiled_program.abi.public_abi();
     let (public_inputs_map, return_value) =
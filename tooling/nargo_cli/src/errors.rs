@@ -29,6 +29,15 @@ pub(crate) enum FilesystemError {
 
     #[error("Error: could not deserialize build program: {0}")]
     ProgramSerializationError(String),
+
+    #[error("Error: could not read baseline report {}: {1}", .0.display())]
+    InvalidBaselineReport(PathBuf, String),
+
+    #[error("Error: could not read oracle transcript {}: {1}", .0.display())]
+    InvalidOracleTranscript(PathBuf, String),
+
+    #[error("Error: could not create execution trace {}: {1}", .0.display())]
+    TraceFileNotCreated(PathBuf, String),
 }
 
 #[derive(Debug, Error)]
@@ -44,6 +53,12 @@ pub(crate) enum CliError {
     #[error("Invalid package name {0}. Did you mean to use `--name`?")]
     InvalidPackageName(String),
 
+    #[error("`--force-brillig` cannot be used with proofs as the resulting circuit does not constrain its execution")]
+    ForceBrilligNotProvable,
+
+    #[error("Package `{0}` cannot be used with proofs as it contains `#[fold]` functions, which backends do not support")]
+    FoldedFunctionsNotProvable(CrateName),
+
     /// ABI encoding/decoding error
     #[error(transparent)]
     AbiError(#[from] AbiError),
@@ -55,6 +70,10 @@ pub(crate) enum CliError {
     #[error(transparent)]
     LspError(#[from] async_lsp::Error),
 
+    /// Error communicating with a Debug Adapter Protocol client
+    #[error(transparent)]
+    DapError(#[from] noir_debugger::dap::DapError),
+
     /// Error from Nargo
     #[error(transparent)]
     NargoError(#[from] NargoError),